@@ -0,0 +1,57 @@
+import type { createFetch } from ".";
+import { getErrorCode } from "./problem";
+
+type Axios = ReturnType<typeof createFetch>["axios"];
+
+// サーバーがCookieのトークンと同じかを確認するヘッダー
+const CSRF_HEADER_NAME = "x-csrf-token";
+const SAFE_METHODS = ["get", "head", "options"];
+
+/**
+ * 状態を変更するリクエストに、CSRFトークンをヘッダーで付ける。
+ * トークンは最初に必要になったときに取得する。
+ * Cookieが変わってトークンが拒否された場合は、取得し直して一度だけ送り直す。
+ */
+export const withCsrfToken = (axios: Axios) => {
+  let token: Promise<string | undefined> | undefined;
+
+  const fetchToken = () => {
+    token = axios
+      .get<{ token?: string | null }>("/auth/csrf-token")
+      .then((res) => res.data.token ?? undefined)
+      .catch(() => {
+        // 取得できなかった場合は、次のリクエストでもう一度取得する
+        token = undefined;
+        return undefined;
+      });
+    return token;
+  };
+
+  axios.interceptors.request.use(async (config) => {
+    if (SAFE_METHODS.includes(config.method?.toLowerCase() ?? "get")) {
+      return config;
+    }
+
+    const value = await (token ?? fetchToken());
+    if (value) {
+      config.headers.set(CSRF_HEADER_NAME, value);
+    }
+    return config;
+  });
+
+  axios.interceptors.response.use(undefined, async (err) => {
+    if (getErrorCode(err) !== "InvalidCsrfToken" || !err.config) {
+      throw err;
+    }
+
+    // 取得し直しても同じトークンの場合は、送り直しても拒否される
+    const sent = err.config.headers?.get(CSRF_HEADER_NAME);
+    const value = await fetchToken();
+    if (!value || value === sent) {
+      throw err;
+    }
+    return axios.request(err.config);
+  });
+
+  return axios;
+};
@@ -1,3 +1,7 @@
 import { createFetch } from ".";
+import { withCsrfToken } from "./csrf";
 
 export const api = createFetch(window.ENV.BACKEND_URL || "");
+
+// サーバーのCSRF対策で、Cookieでログインしているときは状態を変更するリクエストにトークンが必要
+withCsrfToken(api.axios);
@@ -0,0 +1,24 @@
+import { z } from "zod";
+
+// サーバーが返すRFC 7807のエラーのボディ。
+// schema.tsは生成したファイルなので、エラーのコードを読むためのスキーマはここに書く
+export const problemDetailsSchema = z.object({
+  type: z.string(),
+  title: z.string(),
+  status: z.number(),
+  code: z.string(),
+  detail: z.string().nullish(),
+});
+
+const errorResponseSchema = z.object({
+  response: z.object({ data: problemDetailsSchema }),
+});
+
+/**
+ * リクエストのエラーから、サーバーが返したエラーのコードを取り出す。
+ * ネットワークのエラーなど、エラーのボディがない場合はundefinedを返す
+ */
+export const getErrorCode = (err: unknown): string | undefined => {
+  const result = errorResponseSchema.safeParse(err);
+  return result.success ? result.data.response.data.code : undefined;
+};
@@ -5,10 +5,10 @@ import { Edge, useReactFlow } from "@xyflow/react";
 import { toast } from "sonner";
 import { z } from "zod";
 import { api } from "~/api/index.client";
-import { endpoints, schemas } from "~/api/schema";
+import { schemas } from "~/api/schema";
+import { getErrorCode } from "~/api/problem";
 import { generateBlockTaskEdge, generateBlockTaskEdgeId } from "../util";
 import { useTaskNodeViewAction } from "../task-node-view-provider";
-import { isErrorFromPath } from "@zodios/core";
 
 export const useReconnectBlockTask = () => {
   const flow = useReactFlow();
@@ -22,14 +22,10 @@ export const useReconnectBlockTask = () => {
     onError: (err) => {
       console.error(err);
 
-      const message = isErrorFromPath(
-        endpoints,
-        "put",
-        "/block-task/reconnect",
-        err
-      )
-        ? getErrorMessage(err.response.data.error_type)
-        : "ブロックタスクをつなげることができませんでした";
+      // 個別の文言がないエラーは、まとめて同じ文言にする
+      const message =
+        getErrorMessage(getErrorCode(err)) ??
+        "ブロックタスクをつなげることができませんでした";
 
       toast.error(message);
     },
@@ -77,21 +73,19 @@ export const useReconnectBlockTask = () => {
   return { reconnectBlockTask };
 };
 
-const getErrorMessage = (
-  type: z.infer<typeof schemas.ConnectBlockTaskErrorType>
-): string => {
-  switch (type) {
+const getErrorMessage = (code: string | undefined): string | undefined => {
+  switch (code) {
     case "TaskNotFound": {
       return "タスクが存在しません";
     }
-    case "CircularTask": {
+    case "CircularConnection": {
       return "タスクを循環させることはできません";
     }
     case "IsSubTask": {
       return "サブタスクをブロックすることはできません";
     }
     default: {
-      throw new Error(type satisfies never);
+      return undefined;
     }
   }
 };
@@ -5,10 +5,10 @@ import { Edge, useReactFlow } from "@xyflow/react";
 import { toast } from "sonner";
 import { z } from "zod";
 import { api } from "~/api/index.client";
-import { endpoints, schemas } from "~/api/schema";
+import { schemas } from "~/api/schema";
+import { getErrorCode } from "~/api/problem";
 import { generateSubTaskEdge, generateSubTaskEdgeId } from "../util";
 import { useTaskNodeViewAction } from "../task-node-view-provider";
-import { isErrorFromPath } from "@zodios/core";
 
 export const useReconnectSubTask = () => {
   const flow = useReactFlow();
@@ -22,14 +22,10 @@ export const useReconnectSubTask = () => {
     onError: (err) => {
       console.error(err);
 
-      const message = isErrorFromPath(
-        endpoints,
-        "put",
-        "/sub-task/reconnect",
-        err
-      )
-        ? getErrorMessage(err.response.data.error_type)
-        : "サブタスクをつなげることができませんでした";
+      // 個別の文言がないエラーは、まとめて同じ文言にする
+      const message =
+        getErrorMessage(getErrorCode(err)) ??
+        "サブタスクをつなげることができませんでした";
 
       toast.error(message);
     },
@@ -78,24 +74,22 @@ export const useReconnectSubTask = () => {
   return { reconnectSubTask };
 };
 
-const getErrorMessage = (
-  type: z.infer<typeof schemas.ConnectSubTaskErrorType>
-): string => {
-  switch (type) {
+const getErrorMessage = (code: string | undefined): string | undefined => {
+  switch (code) {
     case "MultipleMainTask": {
       return "複数のメインタスクを持たせることはできません";
     }
     case "BlockedByMainTask": {
       return "ブロックしているタスクをサブタスクにすることはできません";
     }
-    case "CircularTask": {
+    case "CircularConnection": {
       return "タスクを循環させることはできません";
     }
     case "TaskNotFound": {
       return "タスクが存在しません";
     }
     default: {
-      throw new Error(type satisfies never);
+      return undefined;
     }
   }
 };
@@ -0,0 +1,44 @@
+ALTER TABLE `tasks` ADD COLUMN `completed_at` text;
+
+CREATE TABLE `status_transitions` (
+    `id` integer PRIMARY KEY AUTOINCREMENT NOT NULL,
+    `task_id` text NOT NULL,
+    `user_id` text NOT NULL,
+    -- タスクが作成されたときはNULLになる
+    `from_status` text,
+    `to_status` text NOT NULL,
+    `transitioned_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+
+    FOREIGN KEY (`task_id`) REFERENCES `tasks`(`id`) ON UPDATE no action ON DELETE cascade,
+    FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE INDEX `status_transitions_task_id_index` ON `status_transitions`(`task_id`);
+
+UPDATE `tasks` SET `completed_at` = `updated_at` WHERE `status` = 'Done';
+
+-- サブタスクやブロックされたタスクへの連鎖的な更新でも記録されるように、
+-- アプリケーション側ではなくトリガーで完了日時と状態の遷移を記録する
+CREATE TRIGGER `trigger_tasks_inserted_status` AFTER INSERT ON `tasks`
+BEGIN
+    UPDATE `tasks`
+    SET `completed_at` = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')
+    WHERE rowid == NEW.rowid AND NEW.status = 'Done';
+
+    INSERT INTO `status_transitions`(`task_id`, `user_id`, `from_status`, `to_status`)
+    VALUES (NEW.id, NEW.user_id, NULL, NEW.status);
+END;
+
+CREATE TRIGGER `trigger_tasks_updated_status` AFTER UPDATE OF `status` ON `tasks`
+WHEN OLD.status <> NEW.status
+BEGIN
+    UPDATE `tasks`
+    SET `completed_at` = CASE
+        WHEN NEW.status = 'Done' THEN strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')
+        ELSE NULL
+    END
+    WHERE rowid == NEW.rowid;
+
+    INSERT INTO `status_transitions`(`task_id`, `user_id`, `from_status`, `to_status`)
+    VALUES (NEW.id, NEW.user_id, OLD.status, NEW.status);
+END;
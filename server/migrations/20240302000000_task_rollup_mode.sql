@@ -0,0 +1,6 @@
+-- サブタスクの状態からメインタスクの状態をどう更新するか
+-- Auto: すべてのサブタスクが完了したら完了、そうでなければ未完了にする
+-- Manual: サブタスクの状態によって変更しない
+-- AutoReopenOnly: サブタスクが未完了になったときだけ未完了にする
+ALTER TABLE `tasks` ADD COLUMN `rollup_mode` text DEFAULT 'Auto' NOT NULL
+    CHECK (`rollup_mode` = 'Auto' OR `rollup_mode` = 'Manual' OR `rollup_mode` = 'AutoReopenOnly');
@@ -0,0 +1,50 @@
+-- ユーザーのタスクの状態やつながりが変更されるたびに増える番号。
+-- サーバーがメモリ上に持っているタスクのグラフが古くなっていないかを確認するために使う。
+CREATE TABLE `task_graph_versions` (
+    -- ユーザーの削除でタスクが削除されたときにもトリガーで書き込まれるので、外部キーは設定しない
+    `user_id` text PRIMARY KEY NOT NULL,
+    `version` integer DEFAULT 0 NOT NULL
+);
+
+CREATE TRIGGER `trigger_tasks_inserted_graph_version` AFTER INSERT ON `tasks`
+BEGIN
+    INSERT INTO `task_graph_versions`(`user_id`, `version`) VALUES (NEW.user_id, 1)
+    ON CONFLICT(`user_id`) DO UPDATE SET `version` = `version` + 1;
+END;
+
+CREATE TRIGGER `trigger_tasks_deleted_graph_version` AFTER DELETE ON `tasks`
+BEGIN
+    INSERT INTO `task_graph_versions`(`user_id`, `version`) VALUES (OLD.user_id, 1)
+    ON CONFLICT(`user_id`) DO UPDATE SET `version` = `version` + 1;
+END;
+
+CREATE TRIGGER `trigger_tasks_updated_status_graph_version` AFTER UPDATE OF `status` ON `tasks`
+WHEN OLD.status <> NEW.status
+BEGIN
+    INSERT INTO `task_graph_versions`(`user_id`, `version`) VALUES (NEW.user_id, 1)
+    ON CONFLICT(`user_id`) DO UPDATE SET `version` = `version` + 1;
+END;
+
+CREATE TRIGGER `trigger_sub_tasks_inserted_graph_version` AFTER INSERT ON `sub_tasks`
+BEGIN
+    INSERT INTO `task_graph_versions`(`user_id`, `version`) VALUES (NEW.user_id, 1)
+    ON CONFLICT(`user_id`) DO UPDATE SET `version` = `version` + 1;
+END;
+
+CREATE TRIGGER `trigger_sub_tasks_deleted_graph_version` AFTER DELETE ON `sub_tasks`
+BEGIN
+    INSERT INTO `task_graph_versions`(`user_id`, `version`) VALUES (OLD.user_id, 1)
+    ON CONFLICT(`user_id`) DO UPDATE SET `version` = `version` + 1;
+END;
+
+CREATE TRIGGER `trigger_blocking_tasks_inserted_graph_version` AFTER INSERT ON `blocking_tasks`
+BEGIN
+    INSERT INTO `task_graph_versions`(`user_id`, `version`) VALUES (NEW.user_id, 1)
+    ON CONFLICT(`user_id`) DO UPDATE SET `version` = `version` + 1;
+END;
+
+CREATE TRIGGER `trigger_blocking_tasks_deleted_graph_version` AFTER DELETE ON `blocking_tasks`
+BEGIN
+    INSERT INTO `task_graph_versions`(`user_id`, `version`) VALUES (OLD.user_id, 1)
+    ON CONFLICT(`user_id`) DO UPDATE SET `version` = `version` + 1;
+END;
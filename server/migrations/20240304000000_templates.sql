@@ -0,0 +1,44 @@
+CREATE TABLE `templates` (
+    `id` text PRIMARY KEY NOT NULL,
+    `user_id` text NOT NULL,
+    `name` text NOT NULL,
+    `created_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+    `updated_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+
+    FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE TRIGGER `trigger_templates_updated_at` AFTER UPDATE ON `templates`
+BEGIN
+    UPDATE `templates` SET `updated_at` = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime') WHERE rowid == NEW.rowid;
+END;
+
+CREATE TABLE `template_tasks` (
+    `id` text PRIMARY KEY NOT NULL,
+    `template_id` text NOT NULL,
+    `title` text NOT NULL,
+    `description` text DEFAULT '' NOT NULL,
+    -- テンプレートのルートのタスクからの相対位置
+    `x` real NOT NULL,
+    `y` real NOT NULL,
+    -- ルートのタスクの場合はNULLになる
+    `main_template_task_id` text,
+    -- メインタスクがサブタスクより前になるように並べるための順番
+    `sort_order` integer NOT NULL,
+
+    FOREIGN KEY (`template_id`) REFERENCES `templates`(`id`) ON UPDATE no action ON DELETE cascade,
+    FOREIGN KEY (`main_template_task_id`) REFERENCES `template_tasks`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE INDEX `template_tasks_template_id_index` ON `template_tasks`(`template_id`);
+
+CREATE TABLE `template_blocking_tasks` (
+    `template_id` text NOT NULL,
+    `blocking_template_task_id` text NOT NULL,
+    `blocked_template_task_id` text NOT NULL,
+
+    FOREIGN KEY (`template_id`) REFERENCES `templates`(`id`) ON UPDATE no action ON DELETE cascade,
+    FOREIGN KEY (`blocking_template_task_id`) REFERENCES `template_tasks`(`id`) ON UPDATE no action ON DELETE cascade,
+    FOREIGN KEY (`blocked_template_task_id`) REFERENCES `template_tasks`(`id`) ON UPDATE no action ON DELETE cascade,
+    PRIMARY KEY (`blocking_template_task_id`, `blocked_template_task_id`)
+);
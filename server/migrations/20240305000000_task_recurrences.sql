@@ -0,0 +1,23 @@
+-- 完了したときに次のタスクを作成する繰り返しのルール。
+-- 次のタスクが作成されると、ルールは次のタスクに移される。
+CREATE TABLE `task_recurrences` (
+    `task_id` text PRIMARY KEY NOT NULL,
+    `user_id` text NOT NULL,
+    -- Daily: 日ごと、Weekly: 週ごと、Monthly: 月ごと
+    `frequency` text NOT NULL
+        CHECK (`frequency` = 'Daily' OR `frequency` = 'Weekly' OR `frequency` = 'Monthly'),
+    -- 何日・何週・何ヶ月ごとに繰り返すか
+    `interval` integer NOT NULL CHECK (`interval` >= 1),
+    -- このタスクの予定日(%Y/%m/%d)
+    `due_on` text NOT NULL,
+    `created_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+    `updated_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+
+    FOREIGN KEY (`task_id`) REFERENCES `tasks`(`id`) ON UPDATE no action ON DELETE cascade,
+    FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE TRIGGER `trigger_task_recurrences_updated_at` AFTER UPDATE ON `task_recurrences`
+BEGIN
+    UPDATE `task_recurrences` SET `updated_at` = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime') WHERE rowid == NEW.rowid;
+END;
@@ -0,0 +1,22 @@
+-- キャンバスにノードを置くほどではない、タスクの中の小さな手順
+CREATE TABLE `checklist_items` (
+    `id` text PRIMARY KEY NOT NULL,
+    `task_id` text NOT NULL,
+    `user_id` text NOT NULL,
+    `text` text NOT NULL,
+    `checked` boolean DEFAULT false NOT NULL,
+    -- タスクの中での並び順
+    `sort_order` integer NOT NULL,
+    `created_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+    `updated_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+
+    FOREIGN KEY (`task_id`) REFERENCES `tasks`(`id`) ON UPDATE no action ON DELETE cascade,
+    FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE INDEX `checklist_items_task_id_index` ON `checklist_items`(`task_id`);
+
+CREATE TRIGGER `trigger_checklist_items_updated_at` AFTER UPDATE ON `checklist_items`
+BEGIN
+    UPDATE `checklist_items` SET `updated_at` = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime') WHERE rowid == NEW.rowid;
+END;
@@ -0,0 +1,20 @@
+CREATE TABLE `task_comments` (
+    `id` text PRIMARY KEY NOT NULL,
+    `task_id` text NOT NULL,
+    -- コメントを書いたユーザー
+    `user_id` text NOT NULL,
+    -- 返信の場合は返信先のコメント
+    `parent_comment_id` text,
+    `body` text NOT NULL,
+    `created_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+    -- 編集されていない場合はNULL
+    `edited_at` text,
+    -- 返信を残すために、削除してもレコードは残す。削除されていない場合はNULL
+    `deleted_at` text,
+
+    FOREIGN KEY (`task_id`) REFERENCES `tasks`(`id`) ON UPDATE no action ON DELETE cascade,
+    FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON UPDATE no action ON DELETE cascade,
+    FOREIGN KEY (`parent_comment_id`) REFERENCES `task_comments`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE INDEX `task_comments_task_id_index` ON `task_comments`(`task_id`);
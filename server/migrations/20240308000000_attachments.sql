@@ -0,0 +1,18 @@
+-- タスクの添付ファイルの情報。ファイルの中身はストレージに保存する
+CREATE TABLE `attachments` (
+    `id` text PRIMARY KEY NOT NULL,
+    `task_id` text NOT NULL,
+    `user_id` text NOT NULL,
+    `file_name` text NOT NULL,
+    `content_type` text NOT NULL,
+    -- バイト数
+    `size` integer NOT NULL,
+    -- ストレージの中でファイルを識別するためのキー
+    `storage_key` text NOT NULL,
+    `created_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+
+    FOREIGN KEY (`task_id`) REFERENCES `tasks`(`id`) ON UPDATE no action ON DELETE cascade,
+    FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE INDEX `attachments_task_id_index` ON `attachments`(`task_id`);
@@ -0,0 +1,23 @@
+-- タスク自身の見積もり時間(分)。見積もっていない場合はNULLになる
+ALTER TABLE `tasks` ADD COLUMN `estimated_minutes` integer CHECK (`estimated_minutes` >= 0);
+
+-- タスクに費やした時間の記録。タイマーで計測したものと、手動で入力したものがある
+CREATE TABLE `time_entries` (
+    `id` text PRIMARY KEY NOT NULL,
+    `task_id` text NOT NULL,
+    `user_id` text NOT NULL,
+    `started_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+    -- タイマーが動いている間はNULLになる
+    `stopped_at` text,
+    -- 費やした秒数。タイマーが動いている間はNULLになる
+    `seconds` integer CHECK (`seconds` >= 0),
+    `created_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+
+    FOREIGN KEY (`task_id`) REFERENCES `tasks`(`id`) ON UPDATE no action ON DELETE cascade,
+    FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE INDEX `time_entries_task_id_index` ON `time_entries`(`task_id`);
+
+-- 一つのタスクで同時に動かせるタイマーは一つだけ
+CREATE UNIQUE INDEX `time_entries_running_task_id_index` ON `time_entries`(`task_id`) WHERE `stopped_at` IS NULL;
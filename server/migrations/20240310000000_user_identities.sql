@@ -0,0 +1,17 @@
+-- ログインに使ったプロバイダーと、プロバイダーの中でユーザーを識別するID(subject)の組み合わせ。
+-- プロバイダーが違うとsubjectが重複する可能性があるので、組み合わせでユーザーを識別する。
+CREATE TABLE `user_identities` (
+    `provider` text NOT NULL,
+    `subject` text NOT NULL,
+    `user_id` text NOT NULL,
+    `created_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+
+    PRIMARY KEY (`provider`, `subject`),
+    FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE INDEX `user_identities_user_id_index` ON `user_identities`(`user_id`);
+
+-- これまではGoogleのsubjectをそのままユーザーのIDにしていた
+INSERT INTO `user_identities`(`provider`, `subject`, `user_id`)
+SELECT 'google', `id`, `id` FROM `users`;
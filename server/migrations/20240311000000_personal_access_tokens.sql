@@ -0,0 +1,18 @@
+-- スクリプトなどからAPIを使うためのトークン。トークンそのものは保存せず、ハッシュだけを保存する
+CREATE TABLE `personal_access_tokens` (
+    `id` text PRIMARY KEY NOT NULL,
+    `user_id` text NOT NULL,
+    `name` text NOT NULL,
+    `token_hash` text NOT NULL UNIQUE,
+    -- 空白区切りの`<機能>:read`か`<機能>:write`
+    `scopes` text NOT NULL,
+    `expires_at` text NOT NULL,
+    `last_used_at` text,
+    -- 取り消されていない場合はNULLになる
+    `revoked_at` text,
+    `created_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+
+    FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE INDEX `personal_access_tokens_user_id_index` ON `personal_access_tokens`(`user_id`);
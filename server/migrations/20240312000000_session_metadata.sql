@@ -0,0 +1,14 @@
+-- ログインしているセッションの情報。セッションそのものはtower-sessionsのsessionsテーブルにあり、
+-- セッションにこのテーブルのIDを保存して対応させる
+CREATE TABLE `session_metadata` (
+    `id` text PRIMARY KEY NOT NULL,
+    `user_id` text NOT NULL,
+    `user_agent` text,
+    `ip_address` text,
+    `created_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+    `last_seen_at` text DEFAULT (strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')) NOT NULL,
+
+    FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON UPDATE no action ON DELETE cascade
+);
+
+CREATE INDEX `session_metadata_user_id_index` ON `session_metadata`(`user_id`);
@@ -0,0 +1,4 @@
+-- プロバイダーで認証してログインした日時。
+-- アカウントの削除などの操作の前に、最近ログインし直したかを確認するために使う。
+-- 記録する前からあるセッションや、認証せずに記録したセッションではNULLになる。
+ALTER TABLE `session_metadata` ADD COLUMN `authenticated_at` text;
@@ -0,0 +1,3 @@
+-- エラーメッセージなどを返すときに使う、ユーザーが設定した言語。
+-- 設定していない場合はNULLになり、リクエストのAccept-Languageで決める。
+ALTER TABLE `users` ADD COLUMN `locale` text;
@@ -0,0 +1,10 @@
+-- セッションはtower-sessionsが起動時に作成するsessionsテーブルにある。
+-- セッションの情報から取り消すときに削除できるように、同じ定義でここでも作成しておく
+CREATE TABLE IF NOT EXISTS `sessions` (
+    `id` text PRIMARY KEY NOT NULL,
+    `data` blob NOT NULL,
+    `expiry_date` integer NOT NULL
+);
+
+-- 対応するsessionsテーブルのID。記録する前からあるセッションはアクセスしたときに埋める
+ALTER TABLE `session_metadata` ADD COLUMN `session_id` text;
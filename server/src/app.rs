@@ -1,12 +1,14 @@
-use axum::Router;
+use std::sync::Arc;
+
+use axum::{middleware, Router};
 use axum_login::{
-    tower_sessions::{
-        cookie::{time::Duration, SameSite},
-        Expiry, SessionManagerLayer,
-    },
+    tower_sessions::{cookie::time::Duration, Expiry, SessionManagerLayer},
     AuthManagerLayerBuilder,
 };
-use http::{header::CONTENT_TYPE, Method};
+use http::{
+    header::{AUTHORIZATION, CONTENT_TYPE},
+    HeaderName, HeaderValue, Method,
+};
 use sqlx::{Pool, Sqlite, SqliteConnection};
 use tower_http::cors::CorsLayer;
 use tower_sessions_sqlx_store::SqliteStore;
@@ -14,7 +16,16 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 use utoipauto::utoipauto;
 
-use crate::{config::Env, error::AppError, features};
+use crate::{
+    config::Config,
+    error::AppError,
+    features::{
+        self,
+        attachment::{storage::LocalFileStorage, AttachmentConfig},
+        task::graph::TaskGraphCache,
+    },
+    i18n,
+};
 
 pub type Db = Pool<Sqlite>;
 pub type Connection = SqliteConnection;
@@ -22,15 +33,19 @@ pub type Connection = SqliteConnection;
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub db: Db,
-}
-
-impl axum::extract::FromRef<AppState> for () {
-    fn from_ref(_: &AppState) {}
+    pub task_graph: TaskGraphCache,
+    pub attachments: AttachmentConfig,
+    pub config: Arc<Config>,
 }
 
 pub type AppResult<T> = anyhow::Result<T, AppError>;
 
-async fn build_inner(db: Db, router: Option<Router<AppState>>) -> Router {
+async fn build_inner(
+    db: Db,
+    router: Option<Router<AppState>>,
+    config: Config,
+    attachments: AttachmentConfig,
+) -> Router {
     #[utoipauto]
     #[derive(OpenApi)]
     #[openapi()]
@@ -45,12 +60,20 @@ async fn build_inner(db: Db, router: Option<Router<AppState>>) -> Router {
         .expect("Failed to migrate session store");
 
     let session_layer = SessionManagerLayer::new(session_store)
-        .with_secure(false)
-        .with_same_site(SameSite::Lax)
+        .with_secure(config.session.cookie_secure)
+        .with_same_site(features::auth::csrf::same_site(
+            config.session.cookie_same_site,
+        ))
         .with_http_only(true)
-        .with_expiry(Expiry::OnInactivity(Duration::days(30)));
+        .with_expiry(Expiry::OnInactivity(Duration::days(
+            config.session.expiry_days,
+        )));
+    let session_layer = match &config.session.cookie_domain {
+        Some(domain) => session_layer.with_domain(domain.clone()),
+        None => session_layer,
+    };
 
-    let auth = features::auth::Auth::new(db.clone()).await;
+    let auth = features::auth::Auth::new(db.clone(), &config);
     let auth_layer = AuthManagerLayerBuilder::new(auth, session_layer).build();
 
     let app = if let Some(router) = router {
@@ -59,56 +82,177 @@ async fn build_inner(db: Db, router: Option<Router<AppState>>) -> Router {
         Router::new()
     };
 
-    app.merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+    // 開発中は、同じサーバーで動かしているモックの発行者でログインできるようにする
+    #[cfg(feature = "mock-idp")]
+    let app = app.merge(
+        features::auth::mock_idp::MockIdp::new(
+            config.server.base_url.clone() + &features::auth::mock_idp::MockIdpPaths::issuer(),
+        )
+        .router(),
+    );
+
+    let app = app
+        .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .merge(features::auth::router())
         .merge(features::task::router())
         .merge(features::sub_task::router())
         .merge(features::block_task::router())
         .merge(features::task_node::router())
-        .layer(
-            CorsLayer::new()
-                .allow_origin([Env::client_url().parse().unwrap()])
-                .allow_credentials(true)
-                .allow_headers([CONTENT_TYPE])
-                .allow_methods([
-                    Method::GET,
-                    Method::POST,
-                    Method::HEAD,
-                    Method::DELETE,
-                    Method::PUT,
-                ]),
-        )
-        .layer(auth_layer)
-        .with_state(AppState { db })
+        .merge(features::stats::router())
+        .merge(features::template::router())
+        .merge(features::recurrence::router())
+        .merge(features::checklist::router())
+        .merge(features::comment::router())
+        .merge(features::attachment::router())
+        .merge(features::time_tracking::router())
+        .merge(features::personal_access_token::router())
+        .merge(features::user::router());
+
+    // トークンで認証したリクエストは確認しないので、トークンで認証したあとで確認する
+    let app = if config.session.csrf_protection {
+        app.layer(middleware::from_fn_with_state(
+            config.session.clone(),
+            features::auth::csrf::csrf_protect,
+        ))
+    } else {
+        app
+    };
+
+    app.layer(
+        CorsLayer::new()
+            .allow_origin([config
+                .client
+                .url
+                .parse::<HeaderValue>()
+                .expect("CLIENT_URL is validated when loading the config")])
+            .allow_credentials(true)
+            .allow_headers([
+                CONTENT_TYPE,
+                AUTHORIZATION,
+                HeaderName::from_static(features::auth::csrf::CSRF_HEADER_NAME),
+            ])
+            .allow_methods([
+                Method::GET,
+                Method::POST,
+                Method::HEAD,
+                Method::DELETE,
+                Method::PUT,
+            ]),
+    )
+    // トークンで認証したユーザーの言語の設定も使えるように、トークンで認証したあとで言語を決める。
+    // そのため、トークンの認証に失敗したエラーは既定の言語で返る
+    .layer(middleware::from_fn(i18n::localize))
+    .layer(middleware::from_fn(
+        features::auth::session_metadata::track_session,
+    ))
+    // セッションを読み込んだあとで、トークンで認証できるようにする
+    .layer(middleware::from_fn(
+        features::personal_access_token::middleware::bearer_auth,
+    ))
+    .layer(auth_layer)
+    .with_state(AppState {
+        db,
+        task_graph: TaskGraphCache::default(),
+        attachments,
+        config: Arc::new(config),
+    })
 }
 
-pub async fn build(db: Db) -> Router {
-    build_inner(db, None).await
+pub async fn build(db: Db, config: Config) -> Router {
+    let attachments = AttachmentConfig {
+        storage: Arc::new(LocalFileStorage::new(config.attachments.dir.clone())),
+        max_bytes: config.attachments.max_bytes,
+        allowed_content_types: config.attachments.allowed_content_types.clone(),
+    };
+
+    build_inner(db, None, config, attachments).await
 }
 
 #[cfg(test)]
 pub mod tests {
     use crate::features::auth::routes::signup::CreateUser;
     use crate::{
-        config::Env,
-        features::{auth, user::User},
+        config::{AuthProviderConfig, AuthProviderKind, Config},
+        features::{
+            attachment::{test::attachment_config, AttachmentConfig},
+            auth::{self, mock_idp::MockIdp},
+            user::User,
+        },
     };
+    use axum::Router;
     use axum_test::TestServer;
 
-    use super::{AppResult, Db};
+    use super::{AppResult, AppState, Db};
+
+    /// CSRFトークンを確認するかどうか。確認のテスト以外では、ヘッダーを付けなくてもいいように確認しない
+    #[derive(Clone, Copy, PartialEq)]
+    enum CsrfProtection {
+        Enabled,
+        Disabled,
+    }
 
     pub struct AppTest {
         server: TestServer,
+        attachments: AttachmentConfig,
+        config: Config,
     }
     impl AppTest {
         pub async fn new(db: &Db) -> AppResult<Self> {
-            Env::load();
+            Self::build(db, load_config(), CsrfProtection::Disabled, Router::new()).await
+        }
 
-            let router = super::build_inner(db.clone(), Some(auth::test::routes::router())).await;
+        /// CSRFトークンを確認するようにする
+        pub async fn with_csrf_protection(db: &Db) -> AppResult<Self> {
+            Self::build(db, load_config(), CsrfProtection::Enabled, Router::new()).await
+        }
+
+        /// テスト用のルートに加えて、指定したルートも同じサーバーで動かす
+        pub async fn with_routes(db: &Db, routes: Router<AppState>) -> AppResult<Self> {
+            Self::build(db, load_config(), CsrfProtection::Disabled, routes).await
+        }
+
+        /// モックの発行者を起動して、それだけをプロバイダーとして使う
+        pub async fn with_mock_idp(db: &Db) -> AppResult<(Self, MockIdp)> {
+            let idp = auth::mock_idp::test::spawn().await?;
+            let mut config = load_config();
+            config.auth_providers = vec![AuthProviderConfig {
+                id: "mock".into(),
+                kind: AuthProviderKind::Oidc {
+                    issuer_url: idp.issuer_url().into(),
+                    metadata_file: None,
+                    jwks_file: None,
+                },
+                client_id: "client_id".into(),
+                client_secret: oauth2::ClientSecret::new("client_secret".into()),
+            }];
+            let test = Self::build(db, config, CsrfProtection::Disabled, Router::new()).await?;
+
+            Ok((test, idp))
+        }
+
+        async fn build(
+            db: &Db,
+            mut config: Config,
+            csrf_protection: CsrfProtection,
+            routes: Router<AppState>,
+        ) -> AppResult<Self> {
+            let attachments = attachment_config::create();
+            config.session.csrf_protection = csrf_protection == CsrfProtection::Enabled;
+            let router = super::build_inner(
+                db.clone(),
+                Some(auth::test::routes::router().merge(routes)),
+                config.clone(),
+                attachments.clone(),
+            )
+            .await;
             let mut server = TestServer::new(router)?;
             server.do_save_cookies();
 
-            Ok(AppTest { server })
+            Ok(AppTest {
+                server,
+                attachments,
+                config,
+            })
         }
 
         /// 指定したユーザーでログイン状態にする
@@ -123,8 +267,33 @@ pub mod tests {
             Ok(logged_in_user)
         }
 
+        /// 作成済みのユーザーでログイン状態にする。同じユーザーで別の端末からログインしているときに使う
+        pub async fn login_as(&self, user_id: &str) -> AppResult<User> {
+            let logged_in_user: User = self
+                .server
+                .post(&auth::test::routes::Paths::test_login_as(user_id))
+                .await
+                .json();
+
+            Ok(logged_in_user)
+        }
+
         pub fn server(&self) -> &TestServer {
             &self.server
         }
+
+        /// テストで使っている添付ファイルの設定。ストレージのファイルを直接確認するときに使う
+        pub fn attachments(&self) -> &AttachmentConfig {
+            &self.attachments
+        }
+
+        /// テストで使っている設定。リダイレクト先などを確認するときに使う
+        pub fn config(&self) -> &Config {
+            &self.config
+        }
+    }
+
+    fn load_config() -> Config {
+        Config::load().expect("Invalid config")
     }
 }
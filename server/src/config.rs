@@ -1,56 +1,783 @@
 use dotenv::dotenv;
-use std::env;
+use oauth2::ClientSecret;
+use serde::Deserialize;
+use std::{env, fmt, path::Path, str::FromStr};
+use url::Url;
 
-pub struct Env;
-impl Env {
-    pub fn load() {
+/// 設定ファイルを指定する環境変数。指定しない場合は`config.toml`があれば読み込む
+const CONFIG_FILE_KEY: &str = "CONFIG_FILE";
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// アプリケーションの設定。起動時に一度だけ読み込み、AppStateに入れて使う。
+/// 環境変数、設定ファイル、デフォルト値の順に優先する。
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub client: ClientConfig,
+    pub auth_providers: Vec<AuthProviderConfig>,
+    pub session: SessionConfig,
+    pub attachments: AttachmentsConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// 待ち受けるアドレス。コンテナの中で動かす場合は`0.0.0.0`にする
+    pub host: String,
+    pub port: u16,
+    /// 外部から見たAPIのURL。ログインのコールバックのURLに使う
+    pub base_url: String,
+    pub database_url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    pub url: String,
+    pub signup_page: String,
+    pub auth_error_page: String,
+    /// ログイン後にリダイレクトできるクライアントのパス。指定したパスの下のページも許可する
+    pub after_login_redirect_allowed_paths: Vec<String>,
+}
+
+/// ログインに使うプロバイダーの種類
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthProviderKind {
+    /// OpenID Connectに対応しているプロバイダー。発行者のURLからエンドポイントを探す
+    Oidc {
+        /// 開発中はローカルで動かしているモックの発行者を指定することもできる
+        issuer_url: String,
+        /// 指定した場合は、発行者のURLから探さずにファイルからプロバイダーの情報を読み込む
+        metadata_file: Option<String>,
+        /// 指定した場合は、プロバイダーの情報に含まれるURLから取得せずにファイルから公開鍵を読み込む
+        jwks_file: Option<String>,
+    },
+    /// OpenID Connectに対応していないので、OAuth2でアクセストークンを取得してからユーザーを取得する
+    GitHub,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthProviderConfig {
+    /// ログインするときに指定するプロバイダーのID
+    pub id: String,
+    pub kind: AuthProviderKind,
+    pub client_id: String,
+    /// Debugでは`[redacted]`と表示されるので、設定やセッションをログに出しても漏れない
+    pub client_secret: ClientSecret,
+}
+
+/// セッションのCookieのSameSite属性
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CookieSameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl FromStr for CookieSameSite {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "strict" => Ok(CookieSameSite::Strict),
+            "lax" => Ok(CookieSameSite::Lax),
+            "none" => Ok(CookieSameSite::None),
+            _ => Err(format!("unknown SameSite: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    /// HTTPSでしかCookieを送らないようにする。本番環境ではtrueにする
+    pub cookie_secure: bool,
+    /// 指定しない場合は、APIのホストだけにCookieを送る
+    pub cookie_domain: Option<String>,
+    pub cookie_same_site: CookieSameSite,
+    /// 最後に使われてから、セッションが期限切れになるまでの日数
+    pub expiry_days: i64,
+    /// CookieでログインしているときにCSRFトークンを確認する
+    pub csrf_protection: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttachmentsConfig {
+    /// 添付ファイルを保存するディレクトリ
+    pub dir: String,
+    /// アップロードできる添付ファイルの最大バイト数
+    pub max_bytes: usize,
+    /// アップロードできる添付ファイルのContent-Type
+    pub allowed_content_types: Vec<String>,
+}
+
+/// 設定の読み込みで見つかったすべての問題
+#[derive(Debug)]
+pub struct ConfigError(pub Vec<String>);
+
+impl std::error::Error for ConfigError {}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid configuration:")?;
+        for problem in &self.0 {
+            writeln!(f, "  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// `.env`と環境変数、設定ファイルから設定を読み込む。
+    /// 最初の問題で止めずに、見つかったすべての問題をまとめて返す。
+    pub fn load() -> Result<Self, ConfigError> {
         dotenv().ok();
 
-        // 全部の環境変数が読み込めるか試す
-        Self::port();
-        Self::base_url();
-        Self::client_url();
-        Self::database_url();
-        Self::google_client_id();
-        Self::google_client_secret();
-        Self::signup_page();
-        Self::auth_error_page();
+        let mut loader = Loader::new(|key| env::var(key).ok());
+        let file = loader.read_file();
+        let config = loader.config(file);
+
+        if loader.problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError(loader.problems))
+        }
+    }
+}
+
+/// 設定ファイルの形式。環境変数と同じ項目をセクションに分けて書く
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    server: FileServerConfig,
+    client: FileClientConfig,
+    auth: FileAuthConfig,
+    session: FileSessionConfig,
+    attachments: FileAttachmentsConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileServerConfig {
+    host: Option<String>,
+    port: Option<u16>,
+    base_url: Option<String>,
+    database_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileClientConfig {
+    url: Option<String>,
+    signup_page: Option<String>,
+    auth_error_page: Option<String>,
+    after_login_redirect_allowed_paths: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileAuthConfig {
+    providers: Option<Vec<String>>,
+    /// プロバイダーのID -> 設定
+    provider: std::collections::HashMap<String, FileAuthProviderConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileAuthProviderConfig {
+    kind: Option<String>,
+    issuer_url: Option<String>,
+    metadata_file: Option<String>,
+    jwks_file: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileSessionConfig {
+    cookie_secure: Option<bool>,
+    cookie_domain: Option<String>,
+    cookie_same_site: Option<CookieSameSite>,
+    expiry_days: Option<i64>,
+    csrf_protection: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileAttachmentsConfig {
+    dir: Option<String>,
+    max_bytes: Option<usize>,
+    allowed_content_types: Option<Vec<String>>,
+}
+
+/// 環境変数の名前から値を返す
+type EnvLookup = Box<dyn Fn(&str) -> Option<String>>;
+
+/// 環境変数と設定ファイルから値を取り出しながら、見つかった問題を溜めておく
+struct Loader {
+    problems: Vec<String>,
+    /// 環境変数を読む。テストではプロセスの環境変数の代わりに決まった値を渡す
+    env: EnvLookup,
+}
+
+impl Loader {
+    fn new(env: impl Fn(&str) -> Option<String> + 'static) -> Self {
+        Loader {
+            problems: vec![],
+            env: Box::new(env),
+        }
+    }
+
+    fn read_file(&mut self) -> FileConfig {
+        let (path, required) = match (self.env)(CONFIG_FILE_KEY) {
+            Some(path) => (path, true),
+            None => (DEFAULT_CONFIG_FILE.to_string(), false),
+        };
+        // 指定されていない場合は、デフォルトのファイルがなくてもいい
+        if !required && !Path::new(&path).exists() {
+            return FileConfig::default();
+        }
+
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                self.problems.push(format!(
+                    "{}: failed to read {}: {}",
+                    CONFIG_FILE_KEY, path, e
+                ));
+                return FileConfig::default();
+            }
+        };
+        match toml::from_str(&text) {
+            Ok(file) => file,
+            Err(e) => {
+                self.problems.push(format!("{}: {}", path, e));
+                FileConfig::default()
+            }
+        }
+    }
+
+    fn config(&mut self, file: FileConfig) -> Config {
+        let server = ServerConfig {
+            host: self.or("HOST", file.server.host, "127.0.0.1".into()),
+            port: self.or("PORT", file.server.port, 8000),
+            base_url: self.required("BASE_URL", file.server.base_url),
+            database_url: self.required("DATABASE_URL", file.server.database_url),
+        };
+        let client = ClientConfig {
+            url: self.required("CLIENT_URL", file.client.url),
+            signup_page: self.required("SIGNUP_PAGE", file.client.signup_page),
+            auth_error_page: self.required("AUTH_ERROR_PAGE", file.client.auth_error_page),
+            after_login_redirect_allowed_paths: self.list(
+                "AFTER_LOGIN_REDIRECT_ALLOWED_PATHS",
+                file.client.after_login_redirect_allowed_paths,
+                &["/"],
+            ),
+        };
+        let auth_providers = self.auth_providers(file.auth);
+        let session = SessionConfig {
+            cookie_secure: self.or("SESSION_COOKIE_SECURE", file.session.cookie_secure, false),
+            cookie_domain: self
+                .optional("SESSION_COOKIE_DOMAIN", file.session.cookie_domain)
+                .filter(|d: &String| !d.is_empty()),
+            cookie_same_site: self.or(
+                "SESSION_COOKIE_SAME_SITE",
+                file.session.cookie_same_site,
+                CookieSameSite::Lax,
+            ),
+            expiry_days: self.or("SESSION_EXPIRY_DAYS", file.session.expiry_days, 30),
+            csrf_protection: self.or("CSRF_PROTECTION", file.session.csrf_protection, true),
+        };
+        let attachments = AttachmentsConfig {
+            dir: self.or(
+                "ATTACHMENT_DIR",
+                file.attachments.dir,
+                "./attachments".into(),
+            ),
+            max_bytes: self.or("ATTACHMENT_MAX_BYTES", file.attachments.max_bytes, 10485760),
+            allowed_content_types: self.list(
+                "ATTACHMENT_ALLOWED_CONTENT_TYPES",
+                file.attachments.allowed_content_types,
+                &[
+                    "image/png",
+                    "image/jpeg",
+                    "image/gif",
+                    "application/pdf",
+                    "text/plain",
+                ],
+            ),
+        };
+
+        let config = Config {
+            server,
+            client,
+            auth_providers,
+            session,
+            attachments,
+        };
+        self.validate(&config);
+
+        config
     }
 
-    pub fn port() -> String {
-        Self::get_env("PORT")
+    /// ログインに使うプロバイダーの設定。
+    /// AUTH_PROVIDERSにカンマ区切りでプロバイダーのIDを並べ、IDごとに`AUTH_<ID>_*`で設定する。
+    /// 最初のプロバイダーが、ログインするときに指定しなかった場合に使われる。
+    fn auth_providers(&mut self, mut file: FileAuthConfig) -> Vec<AuthProviderConfig> {
+        let ids = self.list("AUTH_PROVIDERS", file.providers.take(), &["google"]);
+        if ids.is_empty() {
+            self.problems
+                .push("AUTH_PROVIDERS: at least one provider is required".into());
+        }
+
+        ids.iter()
+            .map(|id| {
+                let provider = file.provider.remove(id).unwrap_or_default();
+                self.auth_provider(id, provider)
+            })
+            .collect()
     }
 
-    pub fn base_url() -> String {
-        Self::get_env("BASE_URL")
+    fn auth_provider(&mut self, id: &str, file: FileAuthProviderConfig) -> AuthProviderConfig {
+        let prefix = format!("AUTH_{}_", id.to_uppercase().replace('-', "_"));
+        let key = |name: &str| format!("{}{}", prefix, name);
+
+        let default_kind = if id == "github" { "github" } else { "oidc" };
+        let kind = match self
+            .or(&key("KIND"), file.kind, default_kind.to_string())
+            .as_str()
+        {
+            "github" => AuthProviderKind::GitHub,
+            kind => {
+                if kind != "oidc" {
+                    self.problems.push(format!(
+                        "{}: unknown auth provider kind: {}",
+                        key("KIND"),
+                        kind
+                    ));
+                }
+                let issuer_url = if id == "google" {
+                    self.or(
+                        &key("ISSUER_URL"),
+                        file.issuer_url,
+                        "https://accounts.google.com".into(),
+                    )
+                } else {
+                    self.required(&key("ISSUER_URL"), file.issuer_url)
+                };
+                AuthProviderKind::Oidc {
+                    issuer_url,
+                    metadata_file: self.optional(&key("METADATA_FILE"), file.metadata_file),
+                    jwks_file: self.optional(&key("JWKS_FILE"), file.jwks_file),
+                }
+            }
+        };
+
+        AuthProviderConfig {
+            id: id.to_string(),
+            kind,
+            client_id: self.required(&key("CLIENT_ID"), file.client_id),
+            client_secret: ClientSecret::new(
+                self.required(&key("CLIENT_SECRET"), file.client_secret),
+            ),
+        }
+    }
+
+    fn validate(&mut self, config: &Config) {
+        for (key, url) in [
+            ("BASE_URL", &config.server.base_url),
+            ("CLIENT_URL", &config.client.url),
+        ] {
+            if !url.is_empty() && Url::parse(url).is_err() {
+                self.problems.push(format!("{}: invalid URL: {}", key, url));
+            }
+        }
+        // CORSで許可するオリジンとしてそのまま使うので、パスや末尾の`/`は付けない
+        if !config.client.url.is_empty() && !is_origin(&config.client.url) {
+            self.problems.push(format!(
+                "CLIENT_URL: must be an origin without a path or trailing slash: {}",
+                config.client.url
+            ));
+        }
+        for (key, page) in [
+            ("SIGNUP_PAGE", &config.client.signup_page),
+            ("AUTH_ERROR_PAGE", &config.client.auth_error_page),
+        ] {
+            if !page.is_empty() && !page.starts_with('/') {
+                self.problems
+                    .push(format!("{}: must be a path starting with '/'", key));
+            }
+        }
+
+        let mut ids: Vec<&str> = config
+            .auth_providers
+            .iter()
+            .map(|p| p.id.as_str())
+            .collect();
+        ids.sort();
+        if ids.windows(2).any(|w| w[0] == w[1]) {
+            self.problems
+                .push("AUTH_PROVIDERS: provider IDs must be unique".into());
+        }
+
+        // ブラウザはSecureでないSameSite=NoneのCookieを受け付けない
+        if config.session.cookie_same_site == CookieSameSite::None && !config.session.cookie_secure
+        {
+            self.problems
+                .push("SESSION_COOKIE_SAME_SITE: none requires SESSION_COOKIE_SECURE=true".into());
+        }
+        if config.session.expiry_days < 1 {
+            self.problems
+                .push("SESSION_EXPIRY_DAYS: must be at least 1".into());
+        }
+        if config.attachments.max_bytes == 0 {
+            self.problems
+                .push("ATTACHMENT_MAX_BYTES: must be at least 1".into());
+        }
     }
 
-    pub fn client_url() -> String {
-        Self::get_env("CLIENT_URL")
+    /// 環境変数を優先して値を返す。環境変数の値が読み取れない場合は問題として記録する
+    fn optional<T>(&mut self, key: &str, file: Option<T>) -> Option<T>
+    where
+        T: ParseEnv,
+    {
+        match (self.env)(key) {
+            Some(value) => match T::parse_env(&value) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    self.problems.push(format!("{}: {}", key, e));
+                    None
+                }
+            },
+            None => file,
+        }
     }
 
-    pub fn database_url() -> String {
-        Self::get_env("DATABASE_URL")
+    fn or<T>(&mut self, key: &str, file: Option<T>, default: T) -> T
+    where
+        T: ParseEnv,
+    {
+        self.optional(key, file).unwrap_or(default)
     }
 
-    pub fn google_client_id() -> String {
-        Self::get_env("GOOGLE_CLIENT_ID")
+    /// 指定されていない場合は問題として記録して、仮の値を返す
+    fn required<T>(&mut self, key: &str, file: Option<T>) -> T
+    where
+        T: ParseEnv + Default,
+    {
+        let value = self.optional(key, file);
+        // 値が読み取れなかった場合は、すでに記録されている
+        let prefix = format!("{}:", key);
+        if value.is_none() && !self.problems.iter().any(|p| p.starts_with(&prefix)) {
+            self.problems.push(format!("{}: required", key));
+        }
+        value.unwrap_or_default()
     }
 
-    pub fn google_client_secret() -> String {
-        Self::get_env("GOOGLE_CLIENT_SECRET")
+    /// 環境変数ではカンマ区切りで、設定ファイルでは配列で指定する
+    fn list(&mut self, key: &str, file: Option<Vec<String>>, default: &[&str]) -> Vec<String> {
+        let values = match (self.env)(key) {
+            Some(value) => value.split(',').map(String::from).collect(),
+            None => file.unwrap_or_else(|| default.iter().map(|v| v.to_string()).collect()),
+        };
+
+        values
+            .into_iter()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect()
     }
+}
+
+/// `http://localhost:3000`のように、スキームとホスト(とポート)だけのURLかどうか
+fn is_origin(value: &str) -> bool {
+    let Ok(url) = Url::parse(value) else {
+        return false;
+    };
+    url.origin().is_tuple()
+        && url.origin().ascii_serialization() == value
+        && http::HeaderValue::from_str(value).is_ok()
+}
+
+/// 環境変数の文字列から読み取れる値
+trait ParseEnv: Sized {
+    fn parse_env(value: &str) -> Result<Self, String>;
+}
 
-    pub fn signup_page() -> String {
-        Self::get_env("SIGNUP_PAGE")
+impl ParseEnv for String {
+    fn parse_env(value: &str) -> Result<Self, String> {
+        Ok(value.to_string())
     }
+}
+
+impl ParseEnv for bool {
+    fn parse_env(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "true" | "1" => Ok(true),
+            "false" | "0" => Ok(false),
+            _ => Err(format!("invalid boolean: {}", value)),
+        }
+    }
+}
+
+macro_rules! impl_parse_env_from_str {
+    ($($t:ty),*) => {
+        $(
+            impl ParseEnv for $t {
+                fn parse_env(value: &str) -> Result<Self, String> {
+                    value.trim().parse().map_err(|e| format!("{}: {}", e, value))
+                }
+            }
+        )*
+    };
+}
+impl_parse_env_from_str!(u16, i64, usize, CookieSameSite);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{AuthProviderKind, CookieSameSite, FileConfig, Loader};
+
+    /// プロセスの環境変数の代わりに`env`を読んで、設定ファイルの内容から設定を作る
+    fn load(env: &[(&str, &str)], file: &str) -> (Vec<String>, super::Config) {
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let mut loader = Loader::new(move |key| env.get(key).cloned());
+        let file: FileConfig = toml::from_str(file).unwrap();
+        let config = loader.config(file);
+        (loader.problems, config)
+    }
+
+    const FILE: &str = r#"
+        [server]
+        host = "0.0.0.0"
+        port = 9000
+        base_url = "http://localhost:9000"
+        database_url = "sqlite://db/test.db"
+
+        [client]
+        url = "http://localhost:3000"
+        signup_page = "/auth/signup"
+        auth_error_page = "/auth/error"
+        after_login_redirect_allowed_paths = ["/tasks", "/settings"]
+
+        [auth]
+        providers = ["config-test", "github"]
+
+        [auth.provider.config-test]
+        issuer_url = "http://localhost:9000/mock-idp"
+        jwks_file = "jwks.json"
+        client_id = "client_id"
+        client_secret = "client_secret"
+
+        [auth.provider.github]
+        client_id = "github_client_id"
+        client_secret = "github_client_secret"
+
+        [session]
+        cookie_secure = true
+        cookie_domain = "example.com"
+        cookie_same_site = "none"
+        expiry_days = 7
+        csrf_protection = false
+
+        [attachments]
+        dir = "/var/attachments"
+        max_bytes = 1024
+        allowed_content_types = ["image/png"]
+        "#;
+
+    #[test]
+    fn 設定ファイルから読み込める() {
+        let (problems, config) = load(&[], FILE);
+
+        assert!(problems.is_empty(), "{:?}", problems);
+
+        assert_eq!(config.server.host, "0.0.0.0");
+        assert_eq!(config.server.port, 9000);
+        assert_eq!(config.server.base_url, "http://localhost:9000");
+        assert_eq!(config.server.database_url, "sqlite://db/test.db");
+
+        assert_eq!(config.client.url, "http://localhost:3000");
+        assert_eq!(config.client.signup_page, "/auth/signup");
+        assert_eq!(config.client.auth_error_page, "/auth/error");
+        assert_eq!(
+            config.client.after_login_redirect_allowed_paths,
+            vec!["/tasks", "/settings"]
+        );
+
+        assert_eq!(config.auth_providers.len(), 2);
+        assert_eq!(config.auth_providers[0].id, "config-test");
+        assert_eq!(
+            config.auth_providers[0].kind,
+            AuthProviderKind::Oidc {
+                issuer_url: "http://localhost:9000/mock-idp".into(),
+                metadata_file: None,
+                jwks_file: Some("jwks.json".into()),
+            }
+        );
+        assert_eq!(config.auth_providers[0].client_id, "client_id");
+        assert_eq!(
+            config.auth_providers[0].client_secret.secret(),
+            "client_secret"
+        );
+        assert_eq!(config.auth_providers[1].id, "github");
+        assert_eq!(config.auth_providers[1].kind, AuthProviderKind::GitHub);
+        assert_eq!(config.auth_providers[1].client_id, "github_client_id");
+        assert_eq!(
+            config.auth_providers[1].client_secret.secret(),
+            "github_client_secret"
+        );
+
+        assert!(config.session.cookie_secure);
+        assert_eq!(config.session.cookie_domain, Some("example.com".into()));
+        assert_eq!(config.session.cookie_same_site, CookieSameSite::None);
+        assert_eq!(config.session.expiry_days, 7);
+        assert!(!config.session.csrf_protection);
+
+        assert_eq!(config.attachments.dir, "/var/attachments");
+        assert_eq!(config.attachments.max_bytes, 1024);
+        assert_eq!(config.attachments.allowed_content_types, vec!["image/png"]);
+    }
+
+    #[test]
+    fn デバッグ表示にクライアントシークレットを含めない() {
+        let (_, config) = load(&[], FILE);
+
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("client_secret\""), "{}", debug);
+        assert!(!debug.contains("github_client_secret"), "{}", debug);
+    }
+
+    #[test]
+    fn 環境変数を設定ファイルより優先する() {
+        let (problems, config) = load(
+            &[
+                ("PORT", "9100"),
+                ("CLIENT_URL", "https://app.example.com"),
+                ("AFTER_LOGIN_REDIRECT_ALLOWED_PATHS", "/a, /b"),
+                ("AUTH_CONFIG_TEST_CLIENT_ID", "env_client_id"),
+                ("SESSION_COOKIE_SECURE", "false"),
+                ("SESSION_COOKIE_SAME_SITE", "Strict"),
+                ("SESSION_COOKIE_DOMAIN", ""),
+            ],
+            FILE,
+        );
+
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(config.server.port, 9100);
+        assert_eq!(config.server.host, "0.0.0.0");
+        assert_eq!(config.client.url, "https://app.example.com");
+        assert_eq!(
+            config.client.after_login_redirect_allowed_paths,
+            vec!["/a", "/b"]
+        );
+        assert_eq!(config.auth_providers[0].client_id, "env_client_id");
+        assert!(!config.session.cookie_secure);
+        assert_eq!(config.session.cookie_same_site, CookieSameSite::Strict);
+        assert_eq!(config.session.cookie_domain, None);
+    }
+
+    #[test]
+    fn 指定しない項目はデフォルト値になる() {
+        let (problems, config) = load(
+            &[
+                ("BASE_URL", "http://localhost:8000"),
+                ("DATABASE_URL", "sqlite://db/dev.db"),
+                ("CLIENT_URL", "http://localhost:3000"),
+                ("SIGNUP_PAGE", "/auth/signup"),
+                ("AUTH_ERROR_PAGE", "/auth/error"),
+                ("AUTH_GOOGLE_CLIENT_ID", "client_id"),
+                ("AUTH_GOOGLE_CLIENT_SECRET", "client_secret"),
+            ],
+            "",
+        );
+
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.server.port, 8000);
+        assert_eq!(config.client.after_login_redirect_allowed_paths, vec!["/"]);
+        assert_eq!(config.auth_providers.len(), 1);
+        assert_eq!(config.auth_providers[0].id, "google");
+        assert_eq!(
+            config.auth_providers[0].kind,
+            AuthProviderKind::Oidc {
+                issuer_url: "https://accounts.google.com".into(),
+                metadata_file: None,
+                jwks_file: None,
+            }
+        );
+        assert!(!config.session.cookie_secure);
+        assert_eq!(config.session.cookie_domain, None);
+        assert_eq!(config.session.cookie_same_site, CookieSameSite::Lax);
+        assert_eq!(config.session.expiry_days, 30);
+        assert!(config.session.csrf_protection);
+        assert_eq!(config.attachments.dir, "./attachments");
+        assert_eq!(config.attachments.max_bytes, 10485760);
+        assert_eq!(config.attachments.allowed_content_types.len(), 5);
+    }
+
+    #[test]
+    fn すべての問題をまとめて報告する() {
+        let (problems, _) = load(
+            &[("PORT", "not-a-port")],
+            r#"
+            [auth]
+            providers = ["config-test-a", "config-test-a"]
+
+            [session]
+            cookie_same_site = "none"
+            cookie_secure = false
+            expiry_days = 0
+            "#,
+        );
+
+        for expected in [
+            "BASE_URL: required",
+            "DATABASE_URL: required",
+            "CLIENT_URL: required",
+            "AUTH_CONFIG_TEST_A_ISSUER_URL: required",
+            "AUTH_CONFIG_TEST_A_CLIENT_ID: required",
+            "AUTH_PROVIDERS: provider IDs must be unique",
+            "SESSION_COOKIE_SAME_SITE: none requires SESSION_COOKIE_SECURE=true",
+            "SESSION_EXPIRY_DAYS: must be at least 1",
+        ] {
+            assert!(
+                problems.iter().any(|p| p == expected),
+                "{} not in {:?}",
+                expected,
+                problems
+            );
+        }
+        assert!(problems.iter().any(|p| p.starts_with("PORT: ")));
+    }
+
+    #[test]
+    fn クライアントのurlはオリジンでなければならない() {
+        for url in [
+            "http://localhost:3000/",
+            "http://localhost:3000/app",
+            "http://localhost:3000?a=1",
+            "localhost:3000",
+            "not a url",
+        ] {
+            let (problems, _) = load(&[("CLIENT_URL", url)], FILE);
+            assert!(
+                problems.iter().any(|p| p.starts_with("CLIENT_URL: ")),
+                "{} {:?}",
+                url,
+                problems
+            );
+        }
 
-    pub fn auth_error_page() -> String {
-        Self::get_env("AUTH_ERROR_PAGE")
+        let (problems, _) = load(&[("CLIENT_URL", "https://app.example.com:8443")], FILE);
+        assert!(problems.is_empty(), "{:?}", problems);
     }
 
-    fn get_env(key: &str) -> String {
-        let error_message = format!("Failed to load {}", key);
-        env::var(key).expect(&error_message)
+    #[test]
+    fn 設定ファイルに知らない項目があると読み込めない() {
+        assert!(toml::from_str::<FileConfig>("[server]\nunknown = 1").is_err());
     }
 }
@@ -1,56 +1,292 @@
 use axum::response::IntoResponse;
-use http::StatusCode;
+use http::{header::CONTENT_TYPE, StatusCode};
 use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumIter};
+use utoipa::{ToResponse, ToSchema};
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct Empty;
+use crate::i18n::{Locale, Message};
+
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// クライアントがエラーを見分けるためのコード。
+/// クライアントはこの値で分岐するので、一度公開したコードの名前は変えないこと。
+#[derive(
+    Serialize, Deserialize, ToSchema, EnumIter, AsRefStr, Debug, PartialEq, Eq, Clone, Copy,
+)]
+pub enum ErrorCode {
+    // 個別のコードを持たないエラー。ステータスコードから決まる
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    PayloadTooLarge,
+    UnsupportedMediaType,
+    InternalServerError,
+    ServiceUnavailable,
+
+    /// リクエストの値が検証に失敗した。項目ごとのエラーは`errors`に入る
+    ValidationFailed,
+
+    // タスクのつながり
+    /// 指定したタスクが存在しない
+    TaskNotFound,
+    /// つなげるとタスクが循環してしまう
+    CircularConnection,
+    /// サブタスクはメインタスクを一つしか持てない
+    MultipleMainTask,
+    /// ブロックしているタスクはサブタスクにできない
+    BlockedByMainTask,
+    /// サブタスクはブロックするタスクにできない
+    IsSubTask,
+    /// ブロックしているタスクが全て完了していないので完了にできない
+    BlockingNotDone,
+
+    // タスクに付随するデータ
+    /// 並び替えで、チェックリストのすべての項目が指定されていない
+    ChecklistItemsMismatch,
+    /// 返信先のコメントが存在しない
+    ReplyTargetNotFound,
+    /// タイマーがすでに動いている
+    TimerAlreadyRunning,
+    /// 添付ファイルのフィールドが指定されていない
+    AttachmentFileMissing,
+    /// 添付ファイルが大きすぎる
+    AttachmentTooLarge,
+    /// 添付できないContent-Type
+    AttachmentContentTypeNotAllowed,
+
+    // 認証
+    /// ログイン後のリダイレクト先が許可されていない
+    InvalidRedirect,
+    /// 設定されていないプロバイダー
+    UnknownAuthProvider,
+    /// プロバイダーに接続できない
+    AuthProviderUnavailable,
+    /// ログイン中の情報がセッションに存在しない
+    LoginSessionNotFound,
+    /// 新規登録中の情報がセッションに存在しない
+    SignupSessionNotFound,
+    /// CSRFトークンが一致しない
+    InvalidCsrfToken,
+    /// アクセストークンに必要なスコープがない
+    InsufficientScope,
+    /// アクセストークンでは使えない
+    TokenAuthNotAllowed,
+    /// 最近ログインし直していない
+    ReauthenticationRequired,
+    /// 指定したセッションが存在しない
+    SessionNotFound,
+}
+
+impl ErrorCode {
+    pub fn status(self) -> StatusCode {
+        use ErrorCode::*;
+
+        match self {
+            BadRequest
+            | CircularConnection
+            | MultipleMainTask
+            | BlockedByMainTask
+            | IsSubTask
+            | BlockingNotDone
+            | ChecklistItemsMismatch
+            | ReplyTargetNotFound
+            | AttachmentFileMissing
+            | InvalidRedirect
+            | UnknownAuthProvider
+            | LoginSessionNotFound
+            | SignupSessionNotFound => StatusCode::BAD_REQUEST,
+            Unauthorized => StatusCode::UNAUTHORIZED,
+            Forbidden
+            | InvalidCsrfToken
+            | InsufficientScope
+            | TokenAuthNotAllowed
+            | ReauthenticationRequired => StatusCode::FORBIDDEN,
+            NotFound | TaskNotFound | SessionNotFound => StatusCode::NOT_FOUND,
+            Conflict | TimerAlreadyRunning => StatusCode::CONFLICT,
+            PayloadTooLarge | AttachmentTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ValidationFailed => StatusCode::UNPROCESSABLE_ENTITY,
+            UnsupportedMediaType | AttachmentContentTypeNotAllowed => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+            ServiceUnavailable | AuthProviderUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// 個別のコードを持たないエラーのコード
+    pub fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::BAD_REQUEST => ErrorCode::BadRequest,
+            StatusCode::UNAUTHORIZED => ErrorCode::Unauthorized,
+            StatusCode::FORBIDDEN => ErrorCode::Forbidden,
+            StatusCode::NOT_FOUND => ErrorCode::NotFound,
+            StatusCode::CONFLICT => ErrorCode::Conflict,
+            StatusCode::PAYLOAD_TOO_LARGE => ErrorCode::PayloadTooLarge,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => ErrorCode::UnsupportedMediaType,
+            StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::ValidationFailed,
+            StatusCode::SERVICE_UNAVAILABLE => ErrorCode::ServiceUnavailable,
+            status if status.is_client_error() => ErrorCode::BadRequest,
+            _ => ErrorCode::InternalServerError,
+        }
+    }
+}
+
+/// RFC 7807のエラーレスポンス
+#[derive(Serialize, Deserialize, ToSchema, ToResponse, Debug)]
+#[response(
+    description = "RFC 7807 problem details",
+    content_type = "application/problem+json"
+)]
+pub struct ProblemDetails {
+    /// 問題の種類を表すURI。今はコードで見分けるので`about:blank`にしている
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub code: ErrorCode,
+    /// リクエストの言語で書かれた、利用者向けの説明
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// 検証に失敗した項目ごとのエラー
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, PartialEq)]
+pub struct FieldError {
+    /// 検証に失敗した項目。ネストしている場合は`items[0].title`のようになる
+    pub field: String,
+    pub message: String,
+}
+
+/// 検証に失敗した項目と、カタログのメッセージ
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub field: String,
+    pub message: Message,
+}
+
+/// レスポンスを作るのに必要なエラーの情報。
+/// レスポンスの拡張に入れておき、言語が決まったあとで`i18n::localize`がボディを作り直す
+#[derive(Debug, Clone)]
+pub struct Problem {
+    status: StatusCode,
+    code: ErrorCode,
+    violations: Vec<Violation>,
+}
+
+impl Problem {
+    /// `login_required!`や抽出に失敗したときのような、エラーの情報を持たないレスポンスのステータスコードから作る
+    pub fn from_status(status: StatusCode) -> Self {
+        Problem {
+            status,
+            code: ErrorCode::from_status(status),
+            violations: vec![],
+        }
+    }
+
+    pub fn details(&self, locale: Locale) -> ProblemDetails {
+        let localize = |message: &Message| {
+            locale
+                .message(message)
+                .or_else(|| locale.message(&Message::new("validation.invalid")))
+                .unwrap_or_else(|| message.key.clone())
+        };
+
+        ProblemDetails {
+            problem_type: "about:blank".into(),
+            title: self.status.canonical_reason().unwrap_or("Unknown").into(),
+            status: self.status.as_u16(),
+            code: self.code,
+            // 内部のエラーメッセージは外に出さず、コードに対応する文言を返す
+            detail: locale.message(&Message::new(format!("error.{}", self.code.as_ref()))),
+            errors: self
+                .violations
+                .iter()
+                .map(|violation| FieldError {
+                    field: violation.field.clone(),
+                    message: localize(&violation.message),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn into_response_with(self, locale: Locale) -> axum::response::Response {
+        let body = serde_json::to_string(&self.details(locale)).unwrap_or_default();
+
+        let mut res = (
+            self.status,
+            [(CONTENT_TYPE, PROBLEM_JSON_CONTENT_TYPE)],
+            body,
+        )
+            .into_response();
+        res.extensions_mut().insert(self);
+        res
+    }
+}
 
 // thiserror::ErrorをderiveするとFromの実装でコンフリクトが起こるのでderiveをしていない
 // 下から上がってきたErrorを?で返せるようにしたいからFromを実装しているのだが、素直にmap_errorとかを使うべきなんだろうか。
 #[derive(Debug)]
 pub struct AppError {
-    code: StatusCode,
+    status: StatusCode,
+    code: ErrorCode,
+    violations: Vec<Violation>,
     inner: anyhow::Error,
-    body: Option<serde_json::Value>,
 }
 
 impl AppError {
     // From<(StatusCode, &str)>を実装したかったが、From<E: Into<anyhow::Error>とコンフリクトするので
     // メソッドで実装する
-    pub fn new(code: StatusCode, msg: Option<&str>) -> Self {
-        let msg = msg.unwrap_or(code.canonical_reason().unwrap_or("Unknown"));
+    pub fn new(status: StatusCode, msg: Option<&str>) -> Self {
+        let msg = msg.unwrap_or(status.canonical_reason().unwrap_or("Unknown"));
         AppError {
-            code,
+            status,
+            code: ErrorCode::from_status(status),
+            violations: vec![],
             inner: anyhow::anyhow!("{}", msg),
-            body: None,
         }
     }
 
-    pub fn with_json<T>(code: StatusCode, json: T) -> Self
-    where
-        T: Serialize,
-    {
-        let msg = serde_json::to_string(&json).unwrap_or("Unknown".into());
+    /// クライアントが見分けられるエラーを返す。ステータスコードはコードから決まる
+    pub fn with_code(code: ErrorCode) -> Self {
         AppError {
+            status: code.status(),
             code,
-            inner: anyhow::anyhow!("{}", msg),
-            body: Some(serde_json::to_value(&json).unwrap_or(serde_json::Value::Null)),
+            violations: vec![],
+            inner: anyhow::anyhow!("{:?}", code),
+        }
+    }
+
+    /// 検証に失敗した項目を返す
+    pub fn validation(violations: Vec<Violation>) -> Self {
+        AppError {
+            status: ErrorCode::ValidationFailed.status(),
+            code: ErrorCode::ValidationFailed,
+            inner: anyhow::anyhow!("{:?}", violations),
+            violations,
         }
     }
 
     pub fn unauthorized() -> Self {
         Self::new(StatusCode::UNAUTHORIZED, None)
     }
+
+    pub fn problem(&self) -> Problem {
+        Problem {
+            status: self.status,
+            code: self.code,
+            violations: self.violations.clone(),
+        }
+    }
 }
 
 impl IntoResponse for AppError {
+    /// 言語が決まっていないので既定の言語で作る。`i18n::localize`の内側ではリクエストの言語で作り直される
     fn into_response(self) -> axum::response::Response {
-        if let Some(json) = self.body {
-            (self.code, json.to_string()).into_response()
-        } else {
-            // bodyが存在しない場合は内部のエラーメッセージは外に出さない
-            (self.code, self.code.canonical_reason().unwrap_or("Unknown")).into_response()
-        }
+        self.problem().into_response_with(Locale::default())
     }
 }
 
@@ -61,9 +297,10 @@ where
     fn from(err: E) -> Self {
         let err: anyhow::Error = err.into();
         Self {
-            code: StatusCode::INTERNAL_SERVER_ERROR,
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: ErrorCode::InternalServerError,
+            violations: vec![],
             inner: err,
-            body: None,
         }
     }
 }
@@ -73,3 +310,89 @@ impl std::fmt::Display for AppError {
         write!(f, "{}", self.inner)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::response::IntoResponse;
+    use http::{header::CONTENT_TYPE, StatusCode};
+
+    use super::{
+        AppError, ErrorCode, FieldError, ProblemDetails, Violation, PROBLEM_JSON_CONTENT_TYPE,
+    };
+    use crate::i18n::{Locale, Message};
+
+    async fn problem(error: AppError) -> (StatusCode, String, serde_json::Value) {
+        let res = error.into_response();
+        let status = res.status();
+        let content_type = res.headers()[CONTENT_TYPE].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (status, content_type, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn コードを指定したエラーはproblem_jsonで返す() {
+        let (status, content_type, body) =
+            problem(AppError::with_code(ErrorCode::CircularConnection)).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(content_type, PROBLEM_JSON_CONTENT_TYPE);
+        assert_eq!(body["type"], "about:blank");
+        assert_eq!(body["title"], "Bad Request");
+        assert_eq!(body["status"], 400);
+        assert_eq!(body["code"], "CircularConnection");
+        assert!(body["detail"].is_string());
+    }
+
+    #[tokio::test]
+    async fn 内部のエラーメッセージは返さない() {
+        let (status, _, body) = problem(anyhow::anyhow!("secret").into()).await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        let body: ProblemDetails = serde_json::from_value(body).unwrap();
+        assert_eq!(body.code, ErrorCode::InternalServerError);
+        assert!(!body.detail.unwrap().contains("secret"));
+
+        let (_, _, body) = problem(AppError::new(StatusCode::NOT_FOUND, Some("secret"))).await;
+        assert_eq!(body["code"], "NotFound");
+        assert!(!body["detail"].as_str().unwrap().contains("secret"));
+    }
+
+    #[test]
+    fn 検証のエラーを項目ごとに指定した言語で返す() {
+        let error = AppError::validation(vec![
+            Violation {
+                field: "name".into(),
+                message: Message::new("validation.length_min").arg("min", 1),
+            },
+            Violation {
+                field: "scopes".into(),
+                message: Message::new("validation.unknown"),
+            },
+        ]);
+
+        let details = error.problem().details(Locale::En);
+        assert_eq!(details.status, 422);
+        assert_eq!(details.code, ErrorCode::ValidationFailed);
+        assert_eq!(details.detail.as_deref(), Some("Please check your input."));
+        assert_eq!(
+            details.errors,
+            vec![
+                FieldError {
+                    field: "name".into(),
+                    message: "Length must be at least 1.".into(),
+                },
+                // カタログにないメッセージは汎用の文言になる
+                FieldError {
+                    field: "scopes".into(),
+                    message: "The value is invalid.".into(),
+                },
+            ]
+        );
+
+        let details = error.problem().details(Locale::Ja);
+        assert_eq!(details.errors[0].message, "長さは1以上にしてください");
+    }
+}
@@ -1,6 +1,14 @@
+pub mod attachment;
 pub mod auth;
 pub mod block_task;
+pub mod checklist;
+pub mod comment;
+pub mod personal_access_token;
+pub mod recurrence;
+pub mod stats;
 pub mod sub_task;
 pub mod task;
 pub mod task_node;
+pub mod template;
+pub mod time_tracking;
 pub mod user;
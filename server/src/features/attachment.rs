@@ -0,0 +1,46 @@
+pub mod db;
+pub mod routes;
+pub mod storage;
+pub mod test;
+
+pub use routes::router;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use self::storage::AttachmentStorage;
+
+/// タスクの添付ファイルの情報
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct Attachment {
+    pub id: String,
+    pub task_id: String,
+    pub file_name: String,
+    pub content_type: String,
+    /// バイト数
+    pub size: i64,
+    pub created_at: String,
+}
+
+/// 添付ファイルをアップロードするときのmultipartのフォーム。ドキュメントのためだけに使う
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct UploadAttachment {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct DeleteAttachmentResponse {
+    pub attachment_id: String,
+}
+
+/// 添付ファイルの保存先と、アップロードできるファイルの制限
+#[derive(Debug, Clone)]
+pub struct AttachmentConfig {
+    pub storage: Arc<dyn AttachmentStorage>,
+    /// アップロードできるファイルの最大バイト数
+    pub max_bytes: usize,
+    /// アップロードできるファイルのContent-Type
+    pub allowed_content_types: Vec<String>,
+}
@@ -0,0 +1,159 @@
+use crate::app::Connection;
+
+use super::Attachment;
+
+pub struct InsertAttachmentArgs<'a> {
+    pub id: &'a str,
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+    pub file_name: &'a str,
+    pub content_type: &'a str,
+    pub size: i64,
+    pub storage_key: &'a str,
+}
+/// 追加した添付ファイルのIDを返す。タスクが存在しない場合はNoneを返す
+pub async fn insert_attachment<'a>(
+    db: &mut Connection,
+    args: InsertAttachmentArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO attachments(id, task_id, user_id, file_name, content_type, size, storage_key)
+        SELECT $1, id, user_id, $2, $3, $4, $5
+        FROM tasks
+        WHERE id = $6 AND user_id = $7
+        RETURNING id;
+        "#,
+        args.id,
+        args.file_name,
+        args.content_type,
+        args.size,
+        args.storage_key,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+pub struct FindAttachmentArgs<'a> {
+    pub attachment_id: &'a str,
+    pub user_id: &'a str,
+}
+/// 添付ファイルの情報と、ストレージのキーを返す。添付ファイルが存在しない場合はNoneを返す
+pub async fn find_attachment<'a>(
+    db: &mut Connection,
+    args: FindAttachmentArgs<'a>,
+) -> anyhow::Result<Option<(Attachment, String)>> {
+    let raw = sqlx::query!(
+        r#"
+        SELECT id, task_id, file_name, content_type, size, storage_key, created_at
+        FROM attachments
+        WHERE id = $1 AND user_id = $2;
+        "#,
+        args.attachment_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(raw.map(|raw| {
+        (
+            Attachment {
+                id: raw.id,
+                task_id: raw.task_id,
+                file_name: raw.file_name,
+                content_type: raw.content_type,
+                size: raw.size,
+                created_at: raw.created_at,
+            },
+            raw.storage_key,
+        )
+    }))
+}
+
+pub struct FindAttachmentsArgs<'a> {
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+}
+/// タスクの添付ファイルを古い順に返す
+pub async fn find_attachments<'a>(
+    db: &mut Connection,
+    args: FindAttachmentsArgs<'a>,
+) -> anyhow::Result<Vec<Attachment>> {
+    let attachments = sqlx::query_as!(
+        Attachment,
+        r#"
+        SELECT id, task_id, file_name, content_type, size, created_at
+        FROM attachments
+        WHERE task_id = $1 AND user_id = $2
+        ORDER BY created_at, rowid;
+        "#,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    Ok(attachments)
+}
+
+/// タスクのすべての添付ファイルのストレージのキーを返す。
+/// タスクを削除すると添付ファイルの情報も削除されるので、削除する前にストレージのファイルを消すために使う。
+pub async fn find_attachment_storage_keys<'a>(
+    db: &mut Connection,
+    args: FindAttachmentsArgs<'a>,
+) -> anyhow::Result<Vec<String>> {
+    let keys = sqlx::query!(
+        "SELECT storage_key FROM attachments WHERE task_id = $1 AND user_id = $2;",
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?
+    .into_iter()
+    .map(|r| r.storage_key)
+    .collect();
+
+    Ok(keys)
+}
+
+/// ユーザーのすべての添付ファイルのストレージのキーを返す。ユーザーを削除するときに使う
+pub async fn find_user_attachment_storage_keys(
+    db: &mut Connection,
+    user_id: &str,
+) -> anyhow::Result<Vec<String>> {
+    let keys = sqlx::query!(
+        "SELECT storage_key FROM attachments WHERE user_id = $1;",
+        user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?
+    .into_iter()
+    .map(|r| r.storage_key)
+    .collect();
+
+    Ok(keys)
+}
+
+pub struct DeleteAttachmentArgs<'a> {
+    pub attachment_id: &'a str,
+    pub user_id: &'a str,
+}
+/// 削除した添付ファイルのストレージのキーを返す。添付ファイルが存在しない場合はNoneを返す
+pub async fn delete_attachment<'a>(
+    db: &mut Connection,
+    args: DeleteAttachmentArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        "DELETE FROM attachments WHERE id = $1 AND user_id = $2 RETURNING storage_key;",
+        args.attachment_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.storage_key))
+}
@@ -0,0 +1,58 @@
+use axum::{extract::DefaultBodyLimit, middleware, routing::get, Router};
+use axum_login::login_required;
+
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope, task::routes::TaskPaths},
+};
+
+pub mod delete_attachment;
+pub mod download_attachment;
+pub mod get_attachments;
+pub mod upload_attachment;
+
+pub const TAG: &str = "attachment";
+
+pub struct AttachmentPaths;
+impl AttachmentPaths {
+    pub fn task_attachments_base() -> String {
+        "/attachments".into()
+    }
+
+    pub fn task_attachments() -> String {
+        TaskPaths::task() + &Self::task_attachments_base()
+    }
+
+    pub fn task_attachments_open_api() -> String {
+        TaskPaths::task_open_api() + &Self::task_attachments_base()
+    }
+
+    pub fn attachments() -> String {
+        "/attachments".into()
+    }
+
+    pub fn attachment() -> String {
+        Self::attachments() + "/:id"
+    }
+
+    pub fn attachment_open_api() -> String {
+        Self::attachments() + "/{id}"
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            &AttachmentPaths::task_attachments(),
+            // アップロードできるサイズはハンドラーで設定に合わせて確認する
+            get(get_attachments::handler)
+                .post(upload_attachment::handler)
+                .layer(DefaultBodyLimit::disable()),
+        )
+        .route(
+            &AttachmentPaths::attachment(),
+            get(download_attachment::handler).delete(delete_attachment::handler),
+        )
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
+        .route_layer(login_required!(Auth))
+}
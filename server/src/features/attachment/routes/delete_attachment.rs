@@ -0,0 +1,156 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        attachment::{
+            db::{delete_attachment, DeleteAttachmentArgs},
+            DeleteAttachmentResponse,
+        },
+        auth::Auth,
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    delete,
+    tag = super::TAG,
+    path = super::AttachmentPaths::attachment_open_api(),
+    responses(
+        (status = 200, body = DeleteAttachmentResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState {
+        db, attachments, ..
+    }): State<AppState>,
+) -> AppResult<Json<DeleteAttachmentResponse>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(storage_key) = delete_attachment(
+        &mut tx,
+        DeleteAttachmentArgs {
+            attachment_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    tx.commit().await?;
+
+    // 情報を削除したあとでファイルを消す。失敗してもファイルが残るだけなので、エラーにはしない
+    if let Err(e) = attachments.storage.delete(&storage_key).await {
+        tracing::warn!("Failed to delete attachment file {}: {:?}", storage_key, e);
+    }
+
+    Ok(Json(DeleteAttachmentResponse { attachment_id: id }))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum_test::multipart::{MultipartForm, Part};
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            attachment::{
+                routes::{upload_attachment::FILE_FIELD_NAME, AttachmentPaths},
+                Attachment,
+            },
+            task::test::task_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 添付ファイルを削除するとストレージのファイルも削除される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let attachment: Attachment = test
+            .server()
+            .post(&AttachmentPaths::one_task_attachments(&task.id))
+            .multipart(
+                MultipartForm::new().add_part(
+                    FILE_FIELD_NAME,
+                    Part::bytes(b"hello".to_vec())
+                        .file_name("hello.txt")
+                        .mime_type("text/plain"),
+                ),
+            )
+            .await
+            .json();
+
+        test.server()
+            .delete(&AttachmentPaths::one_attachment(&attachment.id))
+            .await
+            .assert_status_ok();
+
+        let res = test
+            .server()
+            .get(&AttachmentPaths::one_attachment(&attachment.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+        assert!(test
+            .attachments()
+            .storage
+            .get(&attachment.id)
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人の添付ファイルは削除できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        // ログインするたびに新しいユーザーが作られるので、一人目を他人として扱う
+        let other_user = test.login(None).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+        let attachment: Attachment = test
+            .server()
+            .post(&AttachmentPaths::one_task_attachments(&other_user_task.id))
+            .multipart(
+                MultipartForm::new().add_part(
+                    FILE_FIELD_NAME,
+                    Part::bytes(b"hello".to_vec())
+                        .file_name("hello.txt")
+                        .mime_type("text/plain"),
+                ),
+            )
+            .await
+            .json();
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .delete(&AttachmentPaths::one_attachment(&attachment.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use axum_login::AuthSession;
+use http::{
+    header::{CONTENT_DISPOSITION, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+    StatusCode,
+};
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        attachment::db::{find_attachment, FindAttachmentArgs},
+        auth::Auth,
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::AttachmentPaths::attachment_open_api(),
+    responses(
+        (status = 200, content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState {
+        db, attachments, ..
+    }): State<AppState>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some((attachment, storage_key)) = find_attachment(
+        &mut tx,
+        FindAttachmentArgs {
+            attachment_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    tx.commit().await?;
+
+    let data = attachments.storage.get(&storage_key).await?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, attachment.content_type),
+            (
+                CONTENT_DISPOSITION,
+                content_disposition(&attachment.file_name),
+            ),
+            (X_CONTENT_TYPE_OPTIONS, "nosniff".into()),
+        ],
+        data,
+    ))
+}
+
+/// ファイル名に日本語などが含まれていても扱えるように、RFC 5987の形式でファイル名を指定する
+fn content_disposition(file_name: &str) -> String {
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect();
+
+    format!("attachment; filename*=UTF-8''{}", encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum_test::multipart::{MultipartForm, Part};
+    use http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    };
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            attachment::{
+                routes::{upload_attachment::FILE_FIELD_NAME, AttachmentPaths},
+                Attachment,
+            },
+            task::test::task_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn アップロードしたファイルをダウンロードできる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let attachment: Attachment = test
+            .server()
+            .post(&AttachmentPaths::one_task_attachments(&task.id))
+            .multipart(
+                MultipartForm::new().add_part(
+                    FILE_FIELD_NAME,
+                    Part::bytes(b"hello".to_vec())
+                        .file_name("メモ 1.txt")
+                        .mime_type("text/plain"),
+                ),
+            )
+            .await
+            .json();
+
+        let res = test
+            .server()
+            .get(&AttachmentPaths::one_attachment(&attachment.id))
+            .await;
+        res.assert_status_ok();
+        assert_eq!(res.as_bytes().as_ref(), b"hello");
+        assert_eq!(res.header(CONTENT_TYPE), "text/plain");
+        assert_eq!(
+            res.header(CONTENT_DISPOSITION),
+            "attachment; filename*=UTF-8''%E3%83%A1%E3%83%A2%201.txt"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 存在しない添付ファイルはダウンロードできない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        let res = test
+            .server()
+            .get(&AttachmentPaths::one_attachment("not-found"))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        attachment::{
+            db::{find_attachments, FindAttachmentsArgs},
+            Attachment,
+        },
+        auth::Auth,
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::AttachmentPaths::task_attachments_open_api(),
+    responses(
+        (status = 200, body = [Attachment]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<Vec<Attachment>>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let attachments = find_attachments(
+        &mut tx,
+        FindAttachmentsArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(attachments))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum_test::multipart::{MultipartForm, Part};
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            attachment::{
+                routes::{upload_attachment::FILE_FIELD_NAME, AttachmentPaths},
+                Attachment,
+            },
+            task::test::task_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn タスクの添付ファイルの一覧を取得できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let other_task = task_factory::create_with_user(&db, &user.id).await?;
+        for (task_id, file_name) in [
+            (&task.id, "1.txt"),
+            (&task.id, "2.txt"),
+            (&other_task.id, "other.txt"),
+        ] {
+            test.server()
+                .post(&AttachmentPaths::one_task_attachments(task_id))
+                .multipart(
+                    MultipartForm::new().add_part(
+                        FILE_FIELD_NAME,
+                        Part::bytes(b"hello".to_vec())
+                            .file_name(file_name)
+                            .mime_type("text/plain"),
+                    ),
+                )
+                .await;
+        }
+
+        let attachments: Vec<Attachment> = test
+            .server()
+            .get(&AttachmentPaths::one_task_attachments(&task.id))
+            .await
+            .json();
+        let file_names: Vec<_> = attachments.iter().map(|a| a.file_name.as_str()).collect();
+        assert_eq!(file_names, vec!["1.txt", "2.txt"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Multipart, Path, State},
+    response::IntoResponse,
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        attachment::db::{
+            find_attachment, insert_attachment, FindAttachmentArgs, InsertAttachmentArgs,
+        },
+        auth::Auth,
+    },
+};
+
+/// アップロードするファイルを入れるmultipartのフィールド名
+pub const FILE_FIELD_NAME: &str = "file";
+
+#[tracing::instrument(err, skip(multipart))]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::AttachmentPaths::task_attachments_open_api(),
+    request_body(content = UploadAttachment, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, body = Attachment),
+        (status = 400, response = ProblemDetails),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails),
+        (status = 413, response = ProblemDetails),
+        (status = 415, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState {
+        db, attachments, ..
+    }): State<AppState>,
+    mut multipart: Multipart,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let invalid_multipart = |_| AppError::new(StatusCode::BAD_REQUEST, None);
+    let mut field = loop {
+        let Some(field) = multipart.next_field().await.map_err(invalid_multipart)? else {
+            return Err(AppError::with_code(ErrorCode::AttachmentFileMissing));
+        };
+        if field.name() == Some(FILE_FIELD_NAME) {
+            break field;
+        }
+    };
+
+    let file_name = field.file_name().unwrap_or(FILE_FIELD_NAME).to_string();
+    let content_type = field
+        .content_type()
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    if !attachments.allowed_content_types.contains(&content_type) {
+        return Err(AppError::with_code(
+            ErrorCode::AttachmentContentTypeNotAllowed,
+        ));
+    }
+
+    // 大きすぎるファイルを最後まで読み込まないように、少しずつ読み込みながらサイズを確認する
+    let mut data = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(invalid_multipart)? {
+        if data.len() + chunk.len() > attachments.max_bytes {
+            return Err(AppError::with_code(ErrorCode::AttachmentTooLarge));
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    let mut tx = db.begin().await?;
+
+    let attachment_id = uuid::Uuid::new_v4().to_string();
+    if insert_attachment(
+        &mut tx,
+        InsertAttachmentArgs {
+            id: &attachment_id,
+            task_id: &id,
+            user_id: &user.id,
+            file_name: &file_name,
+            content_type: &content_type,
+            size: data.len() as i64,
+            storage_key: &attachment_id,
+        },
+    )
+    .await?
+    .is_none()
+    {
+        return Err(AppError::with_code(ErrorCode::TaskNotFound));
+    }
+
+    // ストレージへの保存に失敗した場合は、添付ファイルの情報も保存しない
+    attachments.storage.put(&attachment_id, &data).await?;
+
+    let (attachment, _) = find_attachment(
+        &mut tx,
+        FindAttachmentArgs {
+            attachment_id: &attachment_id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or_else(|| anyhow!("attachment not found"))?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(attachment)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum_test::multipart::{MultipartForm, Part};
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            attachment::{routes::AttachmentPaths, test::attachment_config, Attachment},
+            task::test::task_factory,
+            user::test::user_factory,
+        },
+    };
+
+    use super::FILE_FIELD_NAME;
+
+    fn file_form(data: Vec<u8>, file_name: &str, content_type: &str) -> MultipartForm {
+        MultipartForm::new().add_part(
+            FILE_FIELD_NAME,
+            Part::bytes(data)
+                .file_name(file_name)
+                .mime_type(content_type),
+        )
+    }
+
+    #[sqlx::test]
+    async fn ファイルをアップロードできる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .post(&AttachmentPaths::one_task_attachments(&task.id))
+            .multipart(file_form(b"hello".to_vec(), "hello.txt", "text/plain"))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+        let attachment: Attachment = res.json();
+        assert_eq!(attachment.task_id, task.id);
+        assert_eq!(attachment.file_name, "hello.txt");
+        assert_eq!(attachment.content_type, "text/plain");
+        assert_eq!(attachment.size, 5);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 許可されていない種類のファイルはアップロードできない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .post(&AttachmentPaths::one_task_attachments(&task.id))
+            .multipart(file_form(
+                b"<html></html>".to_vec(),
+                "index.html",
+                "text/html",
+            ))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 大きすぎるファイルはアップロードできない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .post(&AttachmentPaths::one_task_attachments(&task.id))
+            .multipart(file_form(
+                vec![b'a'; attachment_config::MAX_BYTES + 1],
+                "large.txt",
+                "text/plain",
+            ))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // 最大サイズちょうどのファイルはアップロードできる
+        let res = test
+            .server()
+            .post(&AttachmentPaths::one_task_attachments(&task.id))
+            .multipart(file_form(
+                vec![b'a'; attachment_config::MAX_BYTES],
+                "max.txt",
+                "text/plain",
+            ))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクにはアップロードできない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .post(&AttachmentPaths::one_task_attachments(&other_user_task.id))
+            .multipart(file_form(b"hello".to_vec(), "hello.txt", "text/plain"))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
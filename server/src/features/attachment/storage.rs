@@ -0,0 +1,91 @@
+use std::{fmt::Debug, io::ErrorKind, path::PathBuf};
+
+use axum::async_trait;
+
+/// 添付ファイルの中身を保存する場所。
+/// ファイルの情報はデータベースに保存し、ここではキーと中身だけを扱う。
+#[async_trait]
+pub trait AttachmentStorage: Debug + Send + Sync {
+    async fn put(&self, key: &str, data: &[u8]) -> anyhow::Result<()>;
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>>;
+
+    /// キーに対応するファイルが存在しない場合も成功する
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+}
+
+/// ローカルのディレクトリにファイルを保存するストレージ
+#[derive(Debug)]
+pub struct LocalFileStorage {
+    root: PathBuf,
+}
+
+impl LocalFileStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalFileStorage { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> anyhow::Result<PathBuf> {
+        // キーはサーバーで生成したIDだが、ディレクトリの外を指せないようにしておく
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(anyhow::anyhow!("invalid storage key: {}", key));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl AttachmentStorage for LocalFileStorage {
+    async fn put(&self, key: &str, data: &[u8]) -> anyhow::Result<()> {
+        let path = self.path(key)?;
+        tokio::fs::create_dir_all(&self.root).await?;
+        tokio::fs::write(path, data).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Vec<u8>> {
+        let data = tokio::fs::read(self.path(key)?).await?;
+
+        Ok(data)
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AttachmentStorage, LocalFileStorage};
+
+    #[tokio::test]
+    async fn 保存したファイルを読み込んで削除できる() -> anyhow::Result<()> {
+        let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let storage = LocalFileStorage::new(&root);
+        let key = uuid::Uuid::new_v4().to_string();
+
+        storage.put(&key, b"hello").await?;
+        assert_eq!(storage.get(&key).await?, b"hello");
+
+        storage.delete(&key).await?;
+        assert!(storage.get(&key).await.is_err());
+        // 存在しないファイルを削除してもエラーにならない
+        storage.delete(&key).await?;
+
+        tokio::fs::remove_dir_all(root).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ディレクトリの外を指すキーは使えない() {
+        let storage = LocalFileStorage::new(std::env::temp_dir());
+
+        assert!(storage.put("../escape", b"hello").await.is_err());
+        assert!(storage.get("a/b").await.is_err());
+        assert!(storage.delete("").await.is_err());
+    }
+}
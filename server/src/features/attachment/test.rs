@@ -0,0 +1,37 @@
+#[cfg(test)]
+pub mod attachment_config {
+    use std::sync::Arc;
+
+    use crate::features::attachment::{storage::LocalFileStorage, AttachmentConfig};
+
+    /// テストでアップロードできるファイルの最大バイト数
+    pub const MAX_BYTES: usize = 1024;
+
+    /// テストごとに別の一時ディレクトリにファイルを保存する設定
+    pub fn create() -> AttachmentConfig {
+        let root = std::env::temp_dir()
+            .join("evodo-attachments")
+            .join(uuid::Uuid::new_v4().to_string());
+
+        AttachmentConfig {
+            storage: Arc::new(LocalFileStorage::new(root)),
+            max_bytes: MAX_BYTES,
+            allowed_content_types: vec!["text/plain".into(), "image/png".into()],
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod routes {
+    use crate::features::{attachment, task::routes::TaskPaths};
+
+    impl attachment::routes::AttachmentPaths {
+        pub fn one_task_attachments(task_id: &str) -> String {
+            TaskPaths::one_task(task_id) + &Self::task_attachments_base()
+        }
+
+        pub fn one_attachment(id: &str) -> String {
+            Self::attachments() + "/" + id
+        }
+    }
+}
@@ -1,22 +1,33 @@
+pub mod csrf;
+#[cfg(any(test, feature = "mock-idp"))]
+pub mod mock_idp;
+pub mod provider;
+pub mod redirect;
 pub mod routes;
+pub mod session_metadata;
 pub mod test;
 
-use self::routes::AuthPaths;
+use self::{
+    provider::{LazyAuthProvider, ProviderUnavailable},
+    redirect::RedirectPolicy,
+    routes::AuthPaths,
+};
 
-use super::user::{db::find_user, User};
-use crate::{app::Db, config::Env};
+use super::user::{
+    db::{find_user, find_user_by_identity, FindUserByIdentityArgs},
+    User,
+};
+use crate::{
+    app::Db,
+    config::{ClientConfig, Config},
+};
 use anyhow::anyhow;
 use axum::async_trait;
 use axum_login::{AuthnBackend, UserId};
-use openidconnect::{
-    core::{CoreClient, CoreProviderMetadata, CoreResponseType},
-    reqwest::async_http_client,
-    AuthenticationFlow, AuthorizationCode, ClientId, ClientSecret, CsrfToken, IssuerUrl, Nonce,
-    RedirectUrl, Scope,
-};
+use openidconnect::{AuthorizationCode, CsrfToken, Nonce, RedirectUrl};
 pub use routes::router;
 use serde::{Deserialize, Serialize};
-use url::Url;
+use std::{collections::HashMap, sync::Arc};
 use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, ToSchema, Debug)]
@@ -26,56 +37,75 @@ pub struct Session {
 
 #[derive(Debug, Deserialize)]
 pub struct Credentials {
+    /// ログインを開始したときに選ばれたプロバイダーのID
+    pub provider: String,
     pub code: String,
     pub old_state: CsrfToken,
     pub new_state: CsrfToken,
     pub nonce: Nonce,
 }
 
+/// プロバイダーとプロバイダーの中でのユーザーのIDの組み合わせ。
+/// プロバイダーが違うとsubjectが重複する可能性があるので、組み合わせでユーザーを識別する。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Identity {
+    pub provider: String,
+    pub subject: String,
+}
+
 #[derive(Clone, Debug)]
 pub struct Auth {
     pub db: Db,
-    pub client: CoreClient,
+    providers: Arc<HashMap<String, LazyAuthProvider>>,
+    /// プロバイダーを指定せずにログインしたときに使うプロバイダーのID
+    default_provider: String,
+    /// 認証機能がクライアントにリダイレクトするときに使う
+    pub redirect_policy: RedirectPolicy,
+    /// 新規登録や認証エラーのときにリダイレクトするクライアントのページ
+    pub client: ClientConfig,
 }
 
 impl Auth {
-    pub async fn new(db: Db) -> Self {
-        let google_client_id = ClientId::new(Env::google_client_id());
-        let google_client_secret = ClientSecret::new(Env::google_client_secret());
-
-        let issuer_url =
-            IssuerUrl::new("https://accounts.google.com".to_string()).expect("Invalid issuer URL");
-
-        let provider_metadata = CoreProviderMetadata::discover_async(issuer_url, async_http_client)
-            .await
-            .expect("Failed");
-
-        let client = CoreClient::from_provider_metadata(
-            provider_metadata,
-            google_client_id,
-            Some(google_client_secret),
-        )
-        .set_redirect_uri(
-            RedirectUrl::new(format!(
-                "{}{}",
-                Env::base_url(),
-                AuthPaths::login_callback()
-            ))
-            .expect("Invalid redirect URL"),
-        );
-
-        Auth { db, client }
+    /// プロバイダーの情報は使われたときに取得するので、ここではプロバイダーに接続しない
+    pub fn new(db: Db, config: &Config) -> Self {
+        let redirect_url = RedirectUrl::new(format!(
+            "{}{}",
+            config.server.base_url,
+            AuthPaths::login_callback()
+        ))
+        .expect("Invalid redirect URL");
+
+        let default_provider = config.auth_providers[0].id.clone();
+
+        let providers = config
+            .auth_providers
+            .iter()
+            .map(|provider| {
+                (
+                    provider.id.clone(),
+                    LazyAuthProvider::new(provider.clone(), redirect_url.clone()),
+                )
+            })
+            .collect();
+
+        Auth {
+            db,
+            providers: Arc::new(providers),
+            default_provider,
+            redirect_policy: RedirectPolicy::new(
+                config.client.url.clone(),
+                config.client.after_login_redirect_allowed_paths.clone(),
+            ),
+            client: config.client.clone(),
+        }
     }
 
-    pub fn authorize_url(&self) -> (Url, CsrfToken, Nonce) {
-        self.client
-            .authorize_url(
-                AuthenticationFlow::<CoreResponseType>::AuthorizationCode,
-                CsrfToken::new_random,
-                Nonce::new_random,
-            )
-            .add_scope(Scope::new("profile".into()))
-            .url()
+    /// IDに対応するプロバイダーを返す。IDを指定しない場合は最初に設定されたプロバイダーを返す
+    pub fn provider(&self, id: Option<&str>) -> Option<(&str, &LazyAuthProvider)> {
+        let id = id.unwrap_or(&self.default_provider);
+        self.providers
+            .get_key_value(id)
+            .map(|(id, provider)| (id.as_str(), provider))
     }
 }
 
@@ -88,7 +118,10 @@ pub enum AuthError {
     // authenticateがResult<User, Error>を返すのでOkとして返せない。
     // けど、authenticateがErrを返すとログに出力されちゃう・・・
     #[error("User not found")]
-    UserNotFound(UserId<Auth>),
+    UserNotFound(Identity),
+
+    #[error(transparent)]
+    ProviderUnavailable(#[from] ProviderUnavailable),
 }
 
 #[async_trait]
@@ -105,32 +138,35 @@ impl AuthnBackend for Auth {
             return Ok(None);
         }
 
-        let token_res = self
-            .client
-            .exchange_code(AuthorizationCode::new(credentials.code))
-            .request_async(openidconnect::reqwest::async_http_client)
-            .await
-            .map_err(|e| AuthError::Unknown(e.into()))?;
-
-        let token_verifier = self.client.id_token_verifier();
-        let id_token_claims = token_res
-            .extra_fields()
-            .id_token()
-            .ok_or_else(|| anyhow!("Server did not return an ID token"))?
-            .claims(&token_verifier, &credentials.nonce)
-            .map_err(|e| AuthError::Unknown(e.into()))?;
-
-        let user_id = id_token_claims.subject().to_string();
+        let Some((_, provider)) = self.provider(Some(&credentials.provider)) else {
+            return Ok(None);
+        };
+
+        let subject = provider
+            .get()
+            .await?
+            .fetch_subject(AuthorizationCode::new(credentials.code), &credentials.nonce)
+            .await?;
+        let identity = Identity {
+            provider: credentials.provider,
+            subject,
+        };
 
         let mut conn = self
             .db
             .acquire()
             .await
             .map_err(|e| AuthError::Unknown(e.into()))?;
-        let user = find_user(&mut conn, &user_id)
-            .await
-            .map_err(|e| AuthError::Unknown(anyhow!(e.to_string())))?
-            .ok_or(AuthError::UserNotFound(user_id))?;
+        let user = find_user_by_identity(
+            &mut conn,
+            FindUserByIdentityArgs {
+                provider: &identity.provider,
+                subject: &identity.subject,
+            },
+        )
+        .await
+        .map_err(|e| AuthError::Unknown(anyhow!(e.to_string())))?
+        .ok_or(AuthError::UserNotFound(identity))?;
 
         Ok(Some(user))
     }
@@ -0,0 +1,207 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_login::tower_sessions::cookie::{Cookie, SameSite};
+use http::{
+    header::{COOKIE, SET_COOKIE},
+    HeaderMap, HeaderValue, Method,
+};
+
+use crate::{
+    config::{CookieSameSite, SessionConfig},
+    error::{AppError, ErrorCode},
+    features::personal_access_token::middleware::TokenScopes,
+};
+
+/// CSRFトークンを入れるCookieの名前。クライアントのJavaScriptから読めるようにHttpOnlyにはしない
+pub const CSRF_COOKIE_NAME: &str = "csrf_token";
+/// 状態を変更するリクエストで、Cookieと同じCSRFトークンを入れるヘッダーの名前
+pub const CSRF_HEADER_NAME: &str = "x-csrf-token";
+
+/// リクエストで使われているCSRFトークン。Cookieにない場合は新しく作ったトークンが入る
+#[derive(Debug, Clone)]
+pub struct CsrfToken(pub String);
+
+/// Double Submit Cookieで、Cookieで認証しているリクエストが他のサイトから送られていないかを確認する。
+/// 他のサイトはCookieを読めないので、ヘッダーにCookieと同じトークンを入れられない。
+/// Cookieを送っていないリクエストや、トークンで認証したリクエストは確認しない。
+pub async fn csrf_protect(
+    State(session_config): State<SessionConfig>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let cookie_token = find_cookie(req.headers(), CSRF_COOKIE_NAME);
+    let token = cookie_token
+        .clone()
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+    req.extensions_mut().insert(CsrfToken(token.clone()));
+
+    let mut res = if requires_check(&req) && !matches_header(req.headers(), &cookie_token) {
+        AppError::with_code(ErrorCode::InvalidCsrfToken).into_response()
+    } else {
+        next.run(req).await
+    };
+
+    if cookie_token.is_none() {
+        if let Ok(value) = HeaderValue::from_str(&csrf_cookie(&session_config, token).to_string()) {
+            res.headers_mut().append(SET_COOKIE, value);
+        }
+    }
+
+    res
+}
+
+fn requires_check(req: &Request) -> bool {
+    let safe_method = matches!(
+        *req.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    );
+    let token_auth = req.extensions().get::<TokenScopes>().is_some();
+    let has_cookie = req.headers().contains_key(COOKIE);
+
+    !safe_method && !token_auth && has_cookie
+}
+
+fn matches_header(headers: &HeaderMap, cookie_token: &Option<String>) -> bool {
+    let header_token = headers.get(CSRF_HEADER_NAME).and_then(|v| v.to_str().ok());
+
+    match (header_token, cookie_token) {
+        (Some(header_token), Some(cookie_token)) => header_token == cookie_token,
+        _ => false,
+    }
+}
+
+fn find_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn csrf_cookie(session_config: &SessionConfig, token: String) -> Cookie<'static> {
+    let mut cookie = Cookie::build((CSRF_COOKIE_NAME, token))
+        .path("/")
+        .http_only(false)
+        .secure(session_config.cookie_secure)
+        .same_site(same_site(session_config.cookie_same_site))
+        .build();
+    if let Some(domain) = &session_config.cookie_domain {
+        cookie.set_domain(domain.clone());
+    }
+
+    cookie
+}
+
+pub fn same_site(same_site: CookieSameSite) -> SameSite {
+    match same_site {
+        CookieSameSite::Strict => SameSite::Strict,
+        CookieSameSite::Lax => SameSite::Lax,
+        CookieSameSite::None => SameSite::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header::AUTHORIZATION, HeaderValue, StatusCode};
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            auth::routes::{csrf_token::CsrfTokenResponse, AuthPaths},
+            personal_access_token::test::personal_access_token_factory,
+            task::{routes::TaskPaths, CreateTask},
+        },
+    };
+
+    use super::CSRF_HEADER_NAME;
+
+    fn create_task() -> CreateTask {
+        CreateTask {
+            title: "title".into(),
+        }
+    }
+
+    #[sqlx::test]
+    async fn csrfトークンがないとcookieで状態を変更できない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::with_csrf_protection(&db).await?;
+        test.login(None).await?;
+
+        let res = test
+            .server()
+            .post(&TaskPaths::tasks())
+            .json(&create_task())
+            .await;
+        assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+        let res = test
+            .server()
+            .post(&TaskPaths::tasks())
+            .add_header(CSRF_HEADER_NAME.parse()?, "invalid".parse()?)
+            .json(&create_task())
+            .await;
+        assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn cookieと同じcsrfトークンを送ると状態を変更できる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::with_csrf_protection(&db).await?;
+        test.login(None).await?;
+
+        let CsrfTokenResponse { token } = test.server().get(&AuthPaths::csrf_token()).await.json();
+        let token = token.unwrap();
+
+        let res = test
+            .server()
+            .post(&TaskPaths::tasks())
+            .add_header(CSRF_HEADER_NAME.parse()?, token.parse()?)
+            .json(&create_task())
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+
+        // 状態を変更しないリクエストにはCSRFトークンはいらない
+        test.server()
+            .get(&TaskPaths::tasks())
+            .await
+            .assert_status_ok();
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn トークンで認証したリクエストはcsrfトークンを確認しない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::with_csrf_protection(&db).await?;
+        let user = test.login(None).await?;
+
+        let (token, _) =
+            personal_access_token_factory::create(&db, &user.id, &["task:write"], 30).await?;
+
+        // Cookieが送られていてもトークンで認証していれば確認しない
+        let res = test
+            .server()
+            .post(&TaskPaths::tasks())
+            .add_header(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", token))?,
+            )
+            .json(&create_task())
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+//! 開発やテストで使う、プロセス内で動くOpenID Connectのモックの発行者。
+//! 認可エンドポイントにアクセスすると、パスワードなしでそのままログインできるので本番では有効にしないこと。
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use anyhow::anyhow;
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Form, Json, Router,
+};
+use http::StatusCode;
+use openidconnect::{
+    core::{
+        CoreIdToken, CoreIdTokenClaims, CoreJsonWebKeySet, CoreJwsSigningAlgorithm,
+        CoreRsaPrivateSigningKey,
+    },
+    Audience, EmptyAdditionalClaims, IssuerUrl, JsonWebKeyId, Nonce, PrivateSigningKey,
+    StandardClaims, SubjectIdentifier,
+};
+use rsa::{
+    pkcs1::{EncodeRsaPrivateKey, LineEnding},
+    RsaPrivateKey,
+};
+use serde::Deserialize;
+use url::Url;
+
+const SIGNING_KEY_ID: &str = "mock-idp";
+const SIGNING_KEY_BITS: usize = 2048;
+
+/// login_hintを指定せずにログインしたときのユーザーのsubject
+pub const DEFAULT_SUBJECT: &str = "mock-user";
+
+pub struct MockIdpPaths;
+impl MockIdpPaths {
+    pub fn issuer() -> String {
+        "/mock-idp".into()
+    }
+
+    pub fn discovery() -> String {
+        Self::issuer() + "/.well-known/openid-configuration"
+    }
+
+    pub fn authorize() -> String {
+        Self::issuer() + "/authorize"
+    }
+
+    pub fn token() -> String {
+        Self::issuer() + "/token"
+    }
+
+    pub fn jwks() -> String {
+        Self::issuer() + "/jwks"
+    }
+}
+
+/// 認可エンドポイントで発行した認可コードに紐づく情報
+#[derive(Debug)]
+struct IssuedCode {
+    client_id: String,
+    subject: String,
+    nonce: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct MockIdp {
+    /// このモックにアクセスするときのURL。`/mock-idp`で終わる
+    issuer_url: String,
+    codes: Arc<Mutex<HashMap<String, IssuedCode>>>,
+    signing_key: Arc<CoreRsaPrivateSigningKey>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuthorizeQuery {
+    client_id: String,
+    redirect_uri: String,
+    state: String,
+    nonce: Option<String>,
+    /// ログインするユーザーのsubject。指定しない場合はDEFAULT_SUBJECTになる
+    login_hint: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenRequest {
+    code: String,
+}
+
+/// IDトークンに署名する鍵をPKCS#1のPEMで返す。
+/// 秘密鍵をリポジトリに置かないように起動してから作り、鍵の生成は遅いのでプロセスの中で使い回す。
+fn signing_key_pem() -> &'static str {
+    static PEM: OnceLock<String> = OnceLock::new();
+    PEM.get_or_init(|| {
+        let key = RsaPrivateKey::new(&mut rand::rngs::OsRng, SIGNING_KEY_BITS)
+            .expect("Failed to generate signing key");
+        key.to_pkcs1_pem(LineEnding::LF)
+            .expect("Failed to encode signing key")
+            .to_string()
+    })
+}
+
+impl MockIdp {
+    pub fn new(issuer_url: String) -> Self {
+        let signing_key = CoreRsaPrivateSigningKey::from_pem(
+            signing_key_pem(),
+            Some(JsonWebKeyId::new(SIGNING_KEY_ID.into())),
+        )
+        .expect("Invalid signing key");
+
+        MockIdp {
+            issuer_url,
+            codes: Arc::new(Mutex::new(HashMap::new())),
+            signing_key: Arc::new(signing_key),
+        }
+    }
+
+    pub fn issuer_url(&self) -> &str {
+        &self.issuer_url
+    }
+
+    pub fn router<S>(&self) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Router::new()
+            .route(&MockIdpPaths::discovery(), get(discovery))
+            .route(&MockIdpPaths::authorize(), get(authorize))
+            .route(&MockIdpPaths::token(), post(token))
+            .route(&MockIdpPaths::jwks(), get(jwks))
+            .with_state(self.clone())
+    }
+
+    /// 認可リクエストを受け付けて認可コードを発行し、リダイレクト先のURLを返す
+    pub fn authorize(&self, query: AuthorizeQuery) -> anyhow::Result<Url> {
+        let code = uuid::Uuid::new_v4().to_string();
+        self.codes.lock().unwrap().insert(
+            code.clone(),
+            IssuedCode {
+                client_id: query.client_id,
+                subject: query.login_hint.unwrap_or(DEFAULT_SUBJECT.into()),
+                nonce: query.nonce,
+            },
+        );
+
+        let mut redirect_url = Url::parse(&query.redirect_uri)?;
+        redirect_url
+            .query_pairs_mut()
+            .append_pair("code", &code)
+            .append_pair("state", &query.state);
+
+        Ok(redirect_url)
+    }
+
+    /// アプリが作った認可リクエストのURLに、ブラウザでアクセスした場合のリダイレクト先を返す
+    #[cfg(test)]
+    pub fn authorize_by_url(
+        &self,
+        authorize_url: &Url,
+        login_hint: Option<&str>,
+    ) -> anyhow::Result<Url> {
+        let params: HashMap<String, String> = authorize_url.query_pairs().into_owned().collect();
+        let param = |key: &str| {
+            params
+                .get(key)
+                .cloned()
+                .ok_or_else(|| anyhow!("{} not found", key))
+        };
+
+        self.authorize(AuthorizeQuery {
+            client_id: param("client_id")?,
+            redirect_uri: param("redirect_uri")?,
+            state: param("state")?,
+            nonce: params.get("nonce").cloned(),
+            login_hint: login_hint.map(String::from),
+        })
+    }
+
+    /// 認可コードを一度だけ使えるIDトークンに交換する
+    fn issue_id_token(&self, code: &str) -> anyhow::Result<CoreIdToken> {
+        let issued = self
+            .codes
+            .lock()
+            .unwrap()
+            .remove(code)
+            .ok_or_else(|| anyhow!("Unknown code"))?;
+
+        let now = chrono::Utc::now();
+        let claims = CoreIdTokenClaims::new(
+            IssuerUrl::new(self.issuer_url.clone())?,
+            vec![Audience::new(issued.client_id)],
+            now + chrono::Duration::hours(1),
+            now,
+            StandardClaims::new(SubjectIdentifier::new(issued.subject)),
+            EmptyAdditionalClaims {},
+        )
+        .set_nonce(issued.nonce.map(Nonce::new));
+
+        let id_token = CoreIdToken::new(
+            claims,
+            self.signing_key.as_ref(),
+            CoreJwsSigningAlgorithm::RsaSsaPkcs1V15Sha256,
+            None,
+            None,
+        )?;
+
+        Ok(id_token)
+    }
+}
+
+async fn discovery(State(idp): State<MockIdp>) -> impl IntoResponse {
+    let issuer_url = idp.issuer_url();
+    let base_url = issuer_url.trim_end_matches(&MockIdpPaths::issuer());
+
+    Json(serde_json::json!({
+        "issuer": issuer_url,
+        "authorization_endpoint": format!("{}{}", base_url, MockIdpPaths::authorize()),
+        "token_endpoint": format!("{}{}", base_url, MockIdpPaths::token()),
+        "jwks_uri": format!("{}{}", base_url, MockIdpPaths::jwks()),
+        "response_types_supported": ["code"],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": ["RS256"],
+        "scopes_supported": ["openid", "profile"],
+        "token_endpoint_auth_methods_supported": ["client_secret_basic", "client_secret_post"],
+    }))
+}
+
+async fn authorize(State(idp): State<MockIdp>, Query(query): Query<AuthorizeQuery>) -> Response {
+    match idp.authorize(query) {
+        Ok(url) => Redirect::to(url.as_str()).into_response(),
+        Err(_) => (StatusCode::BAD_REQUEST, "Invalid redirect_uri").into_response(),
+    }
+}
+
+async fn token(State(idp): State<MockIdp>, Form(req): Form<TokenRequest>) -> Response {
+    match idp.issue_id_token(&req.code) {
+        Ok(id_token) => Json(serde_json::json!({
+            "access_token": uuid::Uuid::new_v4().to_string(),
+            "token_type": "Bearer",
+            "expires_in": 3600,
+            "id_token": id_token,
+        }))
+        .into_response(),
+        Err(_) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "invalid_grant" })),
+        )
+            .into_response(),
+    }
+}
+
+async fn jwks(State(idp): State<MockIdp>) -> impl IntoResponse {
+    Json(CoreJsonWebKeySet::new(vec![idp
+        .signing_key
+        .as_verification_key()]))
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::{MockIdp, MockIdpPaths};
+
+    /// テスト用に、空いているポートでモックの発行者を起動する
+    pub async fn spawn() -> anyhow::Result<MockIdp> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let issuer_url = format!(
+            "http://{}{}",
+            listener.local_addr()?,
+            MockIdpPaths::issuer()
+        );
+
+        let idp = MockIdp::new(issuer_url);
+        let router = idp.router::<()>();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        Ok(idp)
+    }
+}
@@ -0,0 +1,340 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use oauth2::{
+    basic::BasicClient,
+    http::{
+        header::{ACCEPT, AUTHORIZATION, USER_AGENT},
+        HeaderMap, HeaderValue, Method,
+    },
+    AuthUrl, HttpRequest, TokenResponse, TokenUrl,
+};
+use openidconnect::{
+    core::{CoreClient, CoreJsonWebKeySet, CoreProviderMetadata, CoreResponseType},
+    reqwest::async_http_client,
+    AuthenticationFlow, AuthorizationCode, ClientId, CsrfToken, IssuerUrl, JsonWebKeySet, Nonce,
+    RedirectUrl, Scope,
+};
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+use url::Url;
+
+use crate::config::{AuthProviderConfig, AuthProviderKind};
+
+const GITHUB_AUTH_URL: &str = "https://github.com/login/oauth/authorize";
+const GITHUB_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+const GITHUB_USER_URL: &str = "https://api.github.com/user";
+
+/// プロバイダーの情報の取得に失敗してから、もう一度取得を試すまでの間隔
+const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+/// プロバイダーの情報の取得を諦めるまでの時間
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// プロバイダーの情報をまだ取得できていないため、ログインに使えない
+#[derive(Debug, thiserror::Error)]
+#[error("Auth provider is unavailable")]
+pub struct ProviderUnavailable;
+
+/// 最初に使われたときにプロバイダーの情報を取得して、成功したらそれを使い回すプロバイダー。
+/// 起動時に取得しないので、プロバイダーに接続できなくてもサーバーは起動できる。
+/// 取得に失敗した場合は、RETRY_INTERVALが過ぎたあとに使われたときにもう一度取得する。
+#[derive(Debug)]
+pub struct LazyAuthProvider {
+    config: AuthProviderConfig,
+    redirect_url: RedirectUrl,
+    provider: OnceCell<AuthProvider>,
+    last_failed_at: Mutex<Option<Instant>>,
+}
+
+impl LazyAuthProvider {
+    pub fn new(config: AuthProviderConfig, redirect_url: RedirectUrl) -> Self {
+        LazyAuthProvider {
+            config,
+            redirect_url,
+            provider: OnceCell::new(),
+            last_failed_at: Mutex::new(None),
+        }
+    }
+
+    pub async fn get(&self) -> Result<&AuthProvider, ProviderUnavailable> {
+        if let Some(provider) = self.provider.get() {
+            return Ok(provider);
+        }
+
+        // 接続できない間に、リクエストのたびにプロバイダーへの接続を待たせないようにする
+        let recently_failed = self
+            .last_failed_at
+            .lock()
+            .unwrap()
+            .is_some_and(|failed_at| failed_at.elapsed() < RETRY_INTERVAL);
+        if recently_failed {
+            return Err(ProviderUnavailable);
+        }
+
+        self.provider
+            .get_or_try_init(|| async {
+                tokio::time::timeout(
+                    DISCOVERY_TIMEOUT,
+                    AuthProvider::new(&self.config, self.redirect_url.clone()),
+                )
+                .await
+                .map_err(|_| anyhow!("Timed out"))?
+            })
+            .await
+            .map_err(|e| {
+                tracing::warn!("Failed to load auth provider {}: {:?}", self.config.id, e);
+                *self.last_failed_at.lock().unwrap() = Some(Instant::now());
+                ProviderUnavailable
+            })
+    }
+}
+
+/// ログインに使うプロバイダー
+// プロバイダーごとに一つしか作らないので、大きさの違いは気にしない
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum AuthProvider {
+    Oidc(CoreClient),
+    GitHub(BasicClient),
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubUser {
+    id: i64,
+}
+
+impl AuthProvider {
+    pub async fn new(
+        config: &AuthProviderConfig,
+        redirect_url: RedirectUrl,
+    ) -> anyhow::Result<Self> {
+        let client_id = ClientId::new(config.client_id.clone());
+        let client_secret = config.client_secret.clone();
+
+        let provider = match &config.kind {
+            AuthProviderKind::Oidc {
+                issuer_url,
+                metadata_file,
+                jwks_file,
+            } => {
+                let provider_metadata = match metadata_file {
+                    Some(metadata_file) => {
+                        let provider_metadata: CoreProviderMetadata =
+                            serde_json::from_slice(&tokio::fs::read(metadata_file).await?)?;
+                        let jwks: CoreJsonWebKeySet = match jwks_file {
+                            Some(jwks_file) => {
+                                serde_json::from_slice(&tokio::fs::read(jwks_file).await?)?
+                            }
+                            None => {
+                                JsonWebKeySet::fetch_async(
+                                    provider_metadata.jwks_uri(),
+                                    async_http_client,
+                                )
+                                .await?
+                            }
+                        };
+                        provider_metadata.set_jwks(jwks)
+                    }
+                    None => {
+                        CoreProviderMetadata::discover_async(
+                            IssuerUrl::new(issuer_url.clone())?,
+                            async_http_client,
+                        )
+                        .await?
+                    }
+                };
+
+                let client = CoreClient::from_provider_metadata(
+                    provider_metadata,
+                    client_id,
+                    Some(client_secret),
+                )
+                .set_redirect_uri(redirect_url);
+
+                AuthProvider::Oidc(client)
+            }
+            AuthProviderKind::GitHub => {
+                let client = BasicClient::new(
+                    client_id,
+                    Some(client_secret),
+                    AuthUrl::new(GITHUB_AUTH_URL.into())?,
+                    Some(TokenUrl::new(GITHUB_TOKEN_URL.into())?),
+                )
+                .set_redirect_uri(redirect_url);
+
+                AuthProvider::GitHub(client)
+            }
+        };
+
+        Ok(provider)
+    }
+
+    pub fn authorize_url(&self) -> (Url, CsrfToken, Nonce) {
+        match self {
+            AuthProvider::Oidc(client) => client
+                .authorize_url(
+                    AuthenticationFlow::<CoreResponseType>::AuthorizationCode,
+                    CsrfToken::new_random,
+                    Nonce::new_random,
+                )
+                .add_scope(Scope::new("profile".into()))
+                .url(),
+            AuthProvider::GitHub(client) => {
+                let (url, csrf_state) = client
+                    .authorize_url(CsrfToken::new_random)
+                    .add_scope(Scope::new("read:user".into()))
+                    .url();
+
+                // GitHubはIDトークンを発行しないのでnonceは使わないが、ログインの流れを揃えるために作っておく
+                (url, csrf_state, Nonce::new_random())
+            }
+        }
+    }
+
+    /// 認可コードを使って、プロバイダーの中でユーザーを識別するID(subject)を取得する
+    pub async fn fetch_subject(
+        &self,
+        code: AuthorizationCode,
+        nonce: &Nonce,
+    ) -> anyhow::Result<String> {
+        match self {
+            AuthProvider::Oidc(client) => {
+                let token_res = client
+                    .exchange_code(code)
+                    .request_async(async_http_client)
+                    .await?;
+
+                let token_verifier = client.id_token_verifier();
+                let id_token_claims = token_res
+                    .extra_fields()
+                    .id_token()
+                    .ok_or_else(|| anyhow!("Server did not return an ID token"))?
+                    .claims(&token_verifier, nonce)?;
+
+                Ok(id_token_claims.subject().to_string())
+            }
+            AuthProvider::GitHub(client) => {
+                let token_res = client
+                    .exchange_code(code)
+                    .request_async(oauth2::reqwest::async_http_client)
+                    .await?;
+
+                let mut headers = HeaderMap::new();
+                headers.insert(
+                    AUTHORIZATION,
+                    HeaderValue::from_str(&format!(
+                        "Bearer {}",
+                        token_res.access_token().secret()
+                    ))?,
+                );
+                headers.insert(
+                    ACCEPT,
+                    HeaderValue::from_static("application/vnd.github+json"),
+                );
+                // GitHubのAPIはUser-Agentがないリクエストを拒否する
+                headers.insert(USER_AGENT, HeaderValue::from_static("evodo"));
+
+                let res = oauth2::reqwest::async_http_client(HttpRequest {
+                    url: Url::parse(GITHUB_USER_URL)?,
+                    method: Method::GET,
+                    headers,
+                    body: Vec::new(),
+                })
+                .await?;
+                if !res.status_code.is_success() {
+                    return Err(anyhow!("Failed to fetch GitHub user: {}", res.status_code));
+                }
+
+                let user: GitHubUser = serde_json::from_slice(&res.body)?;
+                Ok(user.id.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openidconnect::{ClientSecret, RedirectUrl};
+
+    use crate::config::{AuthProviderConfig, AuthProviderKind};
+
+    use super::LazyAuthProvider;
+
+    fn redirect_url() -> RedirectUrl {
+        RedirectUrl::new("http://localhost/auth/login-callback".into()).unwrap()
+    }
+
+    fn oidc_config(
+        issuer_url: &str,
+        metadata_file: Option<String>,
+        jwks_file: Option<String>,
+    ) -> AuthProviderConfig {
+        AuthProviderConfig {
+            id: "test".into(),
+            kind: AuthProviderKind::Oidc {
+                issuer_url: issuer_url.into(),
+                metadata_file,
+                jwks_file,
+            },
+            client_id: "client_id".into(),
+            client_secret: ClientSecret::new("client_secret".into()),
+        }
+    }
+
+    #[tokio::test]
+    async fn 接続できないプロバイダーは使えない() -> anyhow::Result<()> {
+        // 接続を受け付けていないポートを指定する
+        let provider = LazyAuthProvider::new(
+            oidc_config("http://127.0.0.1:1", None, None),
+            redirect_url(),
+        );
+
+        assert!(provider.get().await.is_err());
+        // 失敗した直後は、もう一度取得を試さずに失敗する
+        assert!(provider.get().await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ファイルからプロバイダーの情報を読み込める() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        tokio::fs::create_dir_all(&dir).await?;
+
+        let metadata_file = dir.join("metadata.json");
+        tokio::fs::write(
+            &metadata_file,
+            serde_json::json!({
+                "issuer": "http://localhost:9000",
+                "authorization_endpoint": "http://localhost:9000/authorize",
+                "token_endpoint": "http://localhost:9000/token",
+                "jwks_uri": "http://localhost:9000/jwks",
+                "response_types_supported": ["code"],
+                "subject_types_supported": ["public"],
+                "id_token_signing_alg_values_supported": ["RS256"]
+            })
+            .to_string(),
+        )
+        .await?;
+        let jwks_file = dir.join("jwks.json");
+        tokio::fs::write(&jwks_file, r#"{"keys": []}"#).await?;
+
+        let provider = LazyAuthProvider::new(
+            oidc_config(
+                "http://localhost:9000",
+                Some(metadata_file.to_string_lossy().into()),
+                Some(jwks_file.to_string_lossy().into()),
+            ),
+            redirect_url(),
+        );
+
+        let (url, _, _) = provider.get().await?.authorize_url();
+        assert!(url.as_str().starts_with("http://localhost:9000/authorize?"));
+
+        tokio::fs::remove_dir_all(dir).await?;
+        Ok(())
+    }
+}
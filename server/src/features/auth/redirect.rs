@@ -0,0 +1,224 @@
+use axum::response::Redirect;
+use url::Url;
+
+/// 相対パスを解決するときに使う仮のURL。解決したあとにホストが変わっていないかを確認する
+const BASE_URL: &str = "http://client.invalid";
+
+/// 認証機能がクライアントにリダイレクトするときに、リダイレクト先を制限する。
+/// 許可したパスで始まるクライアントのページへの相対パスだけを受け付けて、それ以外は拒否する。
+#[derive(Debug, Clone)]
+pub struct RedirectPolicy {
+    client_url: String,
+    /// リダイレクトを許可するパス。パスそのものか、`/`で区切られたその下のパスを許可する
+    allowed_paths: Vec<String>,
+}
+
+impl RedirectPolicy {
+    pub fn new(client_url: String, allowed_paths: Vec<String>) -> Self {
+        RedirectPolicy {
+            client_url: client_url.trim_end_matches('/').to_string(),
+            allowed_paths,
+        }
+    }
+
+    /// リダイレクト先のパスを正規化して返す。許可されていない場合はNoneを返す
+    pub fn normalize(&self, target: &str) -> Option<String> {
+        // `//host`や`/\host`は別のホストとして扱われるので、`/`一つで始まるものだけを受け付ける
+        if !target.starts_with('/') || target.starts_with("//") {
+            return None;
+        }
+        // ブラウザによっては`\`を`/`として扱ったり、制御文字を取り除いたりするので、含まれていたら拒否する
+        if target.contains('\\') || target.chars().any(|c| c.is_control() || c == ' ') {
+            return None;
+        }
+        // エンコードされた区切り文字は、クライアントでデコードされると別のパスになる可能性がある
+        let lower = target.to_ascii_lowercase();
+        if ["%2f", "%5c", "%2e", "%00"]
+            .iter()
+            .any(|e| lower.contains(e))
+        {
+            return None;
+        }
+
+        let base = Url::parse(BASE_URL).ok()?;
+        let url = base.join(target).ok()?;
+        if url.origin() != base.origin() {
+            return None;
+        }
+
+        let path = url.path();
+        let allowed = self.allowed_paths.iter().any(|allowed| {
+            let allowed = allowed.trim_end_matches('/');
+            allowed.is_empty()
+                || path == allowed
+                || path
+                    .strip_prefix(allowed)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        });
+        if !allowed {
+            return None;
+        }
+
+        let mut normalized = path.to_string();
+        if let Some(query) = url.query() {
+            normalized.push('?');
+            normalized.push_str(query);
+        }
+        if let Some(fragment) = url.fragment() {
+            normalized.push('#');
+            normalized.push_str(fragment);
+        }
+
+        Some(normalized)
+    }
+
+    /// クライアントのページにリダイレクトする。許可されていないパスの場合はクライアントのトップページにリダイレクトする
+    pub fn to_client(&self, target: &str) -> Redirect {
+        let path = self.normalize(target).unwrap_or_else(|| {
+            tracing::warn!("Rejected redirect to {:?}", target);
+            "/".into()
+        });
+
+        Redirect::to(&format!("{}{}", self.client_url, path))
+    }
+
+    /// 設定したクライアントのページにリダイレクトする。
+    /// 新規登録やエラーのページは利用者が指定したものではないので、許可したパスに含まれていなくてもリダイレクトする
+    pub fn to_client_page(&self, page: &str) -> Redirect {
+        Redirect::to(&format!("{}{}", self.client_url, page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RedirectPolicy;
+
+    fn policy(allowed_paths: &[&str]) -> RedirectPolicy {
+        RedirectPolicy::new(
+            "http://localhost:3000/".into(),
+            allowed_paths.iter().map(|p| p.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn 相対パスはそのまま受け付ける() {
+        let policy = policy(&["/"]);
+
+        assert_eq!(policy.normalize("/"), Some("/".into()));
+        assert_eq!(policy.normalize("/tasks"), Some("/tasks".into()));
+        assert_eq!(
+            policy.normalize("/tasks/1?view=tree#comments"),
+            Some("/tasks/1?view=tree#comments".into())
+        );
+    }
+
+    #[test]
+    fn ドットのセグメントは正規化する() {
+        let policy = policy(&["/tasks"]);
+
+        assert_eq!(policy.normalize("/tasks/./1"), Some("/tasks/1".into()));
+        assert_eq!(policy.normalize("/tasks/1/../2"), Some("/tasks/2".into()));
+        // 正規化すると許可されていないパスになる
+        assert_eq!(policy.normalize("/tasks/../admin"), None);
+    }
+
+    #[test]
+    fn 許可されていないパスは拒否する() {
+        let policy = policy(&["/tasks", "/settings/"]);
+
+        assert_eq!(policy.normalize("/tasks"), Some("/tasks".into()));
+        assert_eq!(policy.normalize("/tasks/1"), Some("/tasks/1".into()));
+        assert_eq!(
+            policy.normalize("/settings/profile"),
+            Some("/settings/profile".into())
+        );
+        assert_eq!(policy.normalize("/tasks-admin"), None);
+        assert_eq!(policy.normalize("/"), None);
+    }
+
+    #[test]
+    fn 絶対urlやプロトコル相対urlは拒否する() {
+        let policy = policy(&["/"]);
+
+        for target in [
+            "https://evil.example",
+            "http:evil.example",
+            "javascript:alert(1)",
+            "//evil.example",
+            "//evil.example/tasks",
+            "///evil.example",
+            "evil.example",
+            "",
+        ] {
+            assert_eq!(policy.normalize(target), None, "{}", target);
+        }
+    }
+
+    #[test]
+    fn バックスラッシュや制御文字を使ったurlは拒否する() {
+        let policy = policy(&["/"]);
+
+        for target in [
+            "/\\evil.example",
+            "/\\/evil.example",
+            "\\\\evil.example",
+            "/\t/evil.example",
+            "/\n/evil.example",
+            "/ /evil.example",
+        ] {
+            assert_eq!(policy.normalize(target), None, "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn エンコードされた区切り文字を使ったurlは拒否する() {
+        let policy = policy(&["/"]);
+
+        for target in [
+            "/%2F%2Fevil.example",
+            "/%2f/evil.example",
+            "/%5Cevil.example",
+            "/%5c%5cevil.example",
+            "/tasks/%2E%2E/admin",
+            "/tasks%00",
+        ] {
+            assert_eq!(policy.normalize(target), None, "{}", target);
+        }
+    }
+
+    fn location(redirect: axum::response::Redirect) -> String {
+        use axum::response::IntoResponse;
+        redirect
+            .into_response()
+            .headers()
+            .get(http::header::LOCATION)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn 許可されていないリダイレクト先はトップページにリダイレクトする() {
+        let policy = policy(&["/"]);
+
+        assert_eq!(
+            location(policy.to_client("/tasks")),
+            "http://localhost:3000/tasks"
+        );
+        assert_eq!(
+            location(policy.to_client("//evil.example")),
+            "http://localhost:3000/"
+        );
+    }
+
+    #[test]
+    fn 設定したページには許可されていないパスでもリダイレクトする() {
+        let policy = policy(&["/tasks"]);
+
+        assert_eq!(
+            location(policy.to_client_page("/auth/signup")),
+            "http://localhost:3000/auth/signup"
+        );
+    }
+}
@@ -1,14 +1,18 @@
 pub mod cancel_signup;
+pub mod csrf_token;
+pub mod get_sessions;
 pub mod login;
 pub mod login_callback;
 pub mod logout;
+pub mod revoke_other_sessions;
+pub mod revoke_session;
 pub mod session;
 pub mod signup;
 pub mod signup_session;
-use crate::app::AppState;
+use crate::{app::AppState, features::personal_access_token::middleware::reject_token_auth};
 use axum::{
     middleware,
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 
@@ -41,6 +45,21 @@ impl AuthPaths {
     pub fn logout() -> String {
         Self::auth() + "/logout"
     }
+    pub fn csrf_token() -> String {
+        Self::auth() + "/csrf-token"
+    }
+    pub fn sessions() -> String {
+        Self::auth() + "/sessions"
+    }
+    pub fn login_session() -> String {
+        Self::sessions() + "/:id"
+    }
+    pub fn login_session_open_api() -> String {
+        Self::sessions() + "/{id}"
+    }
+    pub fn revoke_other_sessions() -> String {
+        Self::sessions() + "/revoke-others"
+    }
 }
 
 pub fn router() -> Router<AppState> {
@@ -56,4 +75,16 @@ pub fn router() -> Router<AppState> {
         .route(&AuthPaths::session(), get(session::handler))
         .route(&AuthPaths::cancel_signup(), post(cancel_signup::handler))
         .route(&AuthPaths::logout(), post(logout::handler))
+        .route(&AuthPaths::csrf_token(), get(csrf_token::handler))
+        .merge(
+            Router::new()
+                .route(&AuthPaths::sessions(), get(get_sessions::handler))
+                .route(&AuthPaths::login_session(), delete(revoke_session::handler))
+                .route(
+                    &AuthPaths::revoke_other_sessions(),
+                    post(revoke_other_sessions::handler),
+                )
+                // トークンではセッションを操作できないようにする
+                .route_layer(middleware::from_fn(reject_token_auth)),
+        )
 }
@@ -2,19 +2,23 @@ use axum::response::IntoResponse;
 use axum_login::tower_sessions::Session;
 use http::StatusCode;
 
-use crate::app::AppResult;
+use crate::{app::AppResult, error::ProblemDetails, features::auth::Identity};
 
-use super::login_callback::SIGNUP_USER_ID_KEY;
+use super::login_callback::SIGNUP_IDENTITY_KEY;
 
 #[tracing::instrument(err)]
 #[utoipa::path(
     post,
     tag = super::TAG,
     path = super::AuthPaths::cancel_signup(),
-    responses((status = 200))
+    responses(
+        (status = 200),
+        (status = 403, response = ProblemDetails),
+        (status = 500, response = ProblemDetails)
+    )
 )]
 pub async fn handler(session: Session) -> AppResult<impl IntoResponse> {
-    if let Ok(Some(_)) = session.get::<String>(SIGNUP_USER_ID_KEY).await {
+    if let Ok(Some(_)) = session.get::<Identity>(SIGNUP_IDENTITY_KEY).await {
         session.flush().await?;
     }
 
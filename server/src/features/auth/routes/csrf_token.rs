@@ -0,0 +1,31 @@
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{app::AppResult, error::ProblemDetails, features::auth::csrf::CsrfToken};
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CsrfTokenResponse {
+    /// 状態を変更するリクエストのヘッダーに入れるトークン。CSRFの対策が無効になっている場合はNone
+    pub token: Option<String>,
+}
+
+/// CSRFトークンを返す。
+/// クライアントとAPIのドメインが違うとクライアントからCookieを読めないので、このエンドポイントから取得する。
+#[tracing::instrument(err, skip(csrf_token))]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::AuthPaths::csrf_token(),
+    responses(
+        (status = 200, body = CsrfTokenResponse),
+        (status = 500, response = ProblemDetails)
+    )
+)]
+pub async fn handler(
+    csrf_token: Option<Extension<CsrfToken>>,
+) -> AppResult<Json<CsrfTokenResponse>> {
+    Ok(Json(CsrfTokenResponse {
+        token: csrf_token.map(|Extension(CsrfToken(token))| token),
+    }))
+}
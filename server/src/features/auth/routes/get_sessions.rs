@@ -0,0 +1,107 @@
+use axum::{extract::State, Json};
+use axum_login::{tower_sessions::Session, AuthSession};
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::auth::{
+        session_metadata::{db::find_session_metadata_list, SessionMetadata, SESSION_METADATA_KEY},
+        Auth,
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::AuthPaths::sessions(),
+    responses(
+        (status = 200, body = [SessionMetadata]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    session: Session,
+    State(AppState { config, .. }): State<AppState>,
+) -> AppResult<Json<Vec<SessionMetadata>>> {
+    let Some(user) = &auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+    let current_id = session.get::<String>(SESSION_METADATA_KEY).await?;
+
+    let mut conn = auth_session.backend.db.acquire().await?;
+    let sessions = find_session_metadata_list(
+        &mut conn,
+        &user.id,
+        current_id.as_deref(),
+        config.session.expiry_days,
+    )
+    .await?;
+
+    Ok(Json(sessions))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::auth::{
+            routes::AuthPaths, session_metadata::SessionMetadata, test::session_metadata_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn ログインしているセッションの一覧を取得できる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        // ログインするたびに新しいユーザーが作られるので、一人目を他人として扱う
+        let other_user = test.login(None).await?;
+        session_metadata_factory::create(&db, &other_user.id, None).await?;
+
+        let user = test.login(None).await?;
+        let other_id = session_metadata_factory::create(&db, &user.id, Some("other")).await?;
+
+        let sessions: Vec<SessionMetadata> = test.server().get(&AuthPaths::sessions()).await.json();
+        assert_eq!(sessions.len(), 2);
+
+        let current = sessions.iter().find(|s| s.current).unwrap();
+        assert_ne!(current.id, other_id);
+        let other = sessions.iter().find(|s| s.id == other_id).unwrap();
+        assert!(!other.current);
+        assert_eq!(other.user_agent.as_deref(), Some("other"));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 最終アクセス日時は一定の間隔でしか更新されない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        for (modifier, touched) in [("-1 minutes", false), ("-10 minutes", true)] {
+            let last_seen_at = sqlx::query_scalar!(
+                r#"
+                UPDATE session_metadata
+                SET last_seen_at = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime', $2)
+                WHERE user_id = $1
+                RETURNING last_seen_at;
+                "#,
+                user.id,
+                modifier,
+            )
+            .fetch_one(&db)
+            .await?;
+
+            let sessions: Vec<SessionMetadata> =
+                test.server().get(&AuthPaths::sessions()).await.json();
+            assert_eq!(sessions[0].last_seen_at != last_seen_at, touched);
+        }
+
+        Ok(())
+    }
+}
@@ -3,18 +3,24 @@ use axum::{
     response::{IntoResponse, Redirect},
 };
 use axum_login::{tower_sessions::Session, AuthSession};
-use http::StatusCode;
 use serde::Deserialize;
 use utoipa::IntoParams;
 
-use crate::{app::AppResult, error::AppError, features::auth::Auth};
+use crate::{
+    app::AppResult,
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::auth::Auth,
+};
 
+pub const PROVIDER_KEY: &str = "auth.provider";
 pub const CSRF_STATE_KEY: &str = "auth.state";
 pub const NONCE_KEY: &str = "auth.nonce";
 pub const AFTER_LOGIN_REDIRECT_KEY: &str = "auth.after-login-redirect-key";
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct LoginRedirectsQuery {
+    /// ログインに使うプロバイダーのID。指定しない場合は最初に設定されたプロバイダーを使う
+    provider: Option<String>,
     /// ログイン後にリダイレクトするページへのパス
     after_login_redirect: Option<String>,
 }
@@ -24,26 +30,40 @@ pub struct LoginRedirectsQuery {
     get,
     tag = super::TAG,
     path = super::AuthPaths::login(),
+    responses(
+        (status = 303),
+        (status = 400, response = ProblemDetails),
+        (status = 503, response = ProblemDetails)
+    ),
     params(LoginRedirectsQuery)
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
     session: Session,
     Query(LoginRedirectsQuery {
+        provider,
         after_login_redirect,
     }): Query<LoginRedirectsQuery>,
 ) -> AppResult<impl IntoResponse> {
-    let (auth_url, csrf_state, nonce) = auth_session.backend.authorize_url();
-
-    // CLIENT_URLと合わせて使用するので`/`で始まっているかだけを確認する。
-    let after_login_redirect = after_login_redirect.unwrap_or("/".into());
-    if !after_login_redirect.starts_with('/') {
-        return Err(AppError::new(
-            StatusCode::BAD_REQUEST,
-            Some("Invalid after_login_redirect"),
-        ));
-    }
+    // 別のサイトにリダイレクトさせられないように、許可されたクライアントのパスだけを受け付ける
+    let Some(after_login_redirect) = auth_session
+        .backend
+        .redirect_policy
+        .normalize(after_login_redirect.as_deref().unwrap_or("/"))
+    else {
+        return Err(AppError::with_code(ErrorCode::InvalidRedirect));
+    };
 
+    let Some((provider_id, provider)) = auth_session.backend.provider(provider.as_deref()) else {
+        return Err(AppError::with_code(ErrorCode::UnknownAuthProvider));
+    };
+    // プロバイダーに接続できるようになるまでは、ログインできない
+    let Ok(provider) = provider.get().await else {
+        return Err(AppError::with_code(ErrorCode::AuthProviderUnavailable));
+    };
+    let (auth_url, csrf_state, nonce) = provider.authorize_url();
+
+    session.insert(PROVIDER_KEY, provider_id).await?;
     session.insert(CSRF_STATE_KEY, csrf_state.secret()).await?;
     session.insert(NONCE_KEY, nonce.secret()).await?;
     session
@@ -52,3 +72,47 @@ pub async fn handler(
 
     Ok(Redirect::to(auth_url.as_str()).into_response())
 }
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::auth::routes::AuthPaths,
+    };
+
+    #[sqlx::test]
+    async fn 設定されていないプロバイダーではログインできない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let res = test
+            .server()
+            .get(&AuthPaths::login())
+            .add_query_param("provider", "unknown")
+            .await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 別のサイトにはログイン後にリダイレクトできない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        for after_login_redirect in ["https://evil.example", "//evil.example", "/\\evil.example"] {
+            let res = test
+                .server()
+                .get(&AuthPaths::login())
+                .add_query_param("after_login_redirect", after_login_redirect)
+                .await;
+            assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+        }
+
+        Ok(())
+    }
+}
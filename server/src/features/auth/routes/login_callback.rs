@@ -1,22 +1,24 @@
-use super::login::{AFTER_LOGIN_REDIRECT_KEY, CSRF_STATE_KEY, NONCE_KEY};
+use super::login::{AFTER_LOGIN_REDIRECT_KEY, CSRF_STATE_KEY, NONCE_KEY, PROVIDER_KEY};
 use crate::{
     app::AppResult,
-    config::Env,
-    error::AppError,
-    features::auth::{Auth, AuthError, Credentials},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::auth::{
+        session_metadata::{self, ClientInfo},
+        Auth, AuthError, Credentials,
+    },
 };
 use axum::{
     extract::{Query, Request},
     middleware::Next,
-    response::{IntoResponse, Redirect},
+    response::IntoResponse,
 };
 use axum_login::{tower_sessions::Session, AuthSession};
 use http::StatusCode;
 use openidconnect::{CsrfToken, Nonce};
 use serde::Deserialize;
 
-/// 新規登録しようとしているユーザーのID
-pub const SIGNUP_USER_ID_KEY: &str = "auth.signup.user-id";
+/// 新規登録しようとしているユーザーのプロバイダーとID
+pub const SIGNUP_IDENTITY_KEY: &str = "auth.signup.identity";
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct AuthzResp {
@@ -24,41 +26,43 @@ pub struct AuthzResp {
     state: CsrfToken,
 }
 
-#[tracing::instrument(err, skip(auth_session, session))]
+#[tracing::instrument(err, skip(auth_session, session, client))]
 #[utoipa::path(
     get,
     tag = super::TAG,
-    path = super::AuthPaths::login_callback()
+    path = super::AuthPaths::login_callback(),
+    // ハンドラのエラーはクライアントのエラーページへのリダイレクトになる。
+    // セッションの読み込みなど、ハンドラの外で失敗した場合だけエラーを返す
+    responses(
+        (status = 303),
+        (status = 500, response = ProblemDetails)
+    )
 )]
 pub async fn handler(
     mut auth_session: AuthSession<Auth>,
     session: Session,
+    client: ClientInfo,
     Query(AuthzResp {
         code,
         state: new_state,
     }): Query<AuthzResp>,
 ) -> AppResult<impl IntoResponse> {
+    let Ok(Some(provider)) = session.get::<String>(PROVIDER_KEY).await else {
+        return Err(AppError::with_code(ErrorCode::LoginSessionNotFound));
+    };
     let Ok(Some(old_state)) = session.get::<CsrfToken>(CSRF_STATE_KEY).await else {
-        return Err(AppError::new(
-            StatusCode::BAD_REQUEST,
-            Some("CsrfToken not found"),
-        ));
+        return Err(AppError::with_code(ErrorCode::LoginSessionNotFound));
     };
     let Ok(Some(nonce)) = session.get::<Nonce>(NONCE_KEY).await else {
-        return Err(AppError::new(
-            StatusCode::BAD_REQUEST,
-            Some("None not found"),
-        ));
+        return Err(AppError::with_code(ErrorCode::LoginSessionNotFound));
     };
     let Ok(Some(after_login_redirect)) = session.get::<String>(AFTER_LOGIN_REDIRECT_KEY).await
     else {
-        return Err(AppError::new(
-            StatusCode::BAD_REQUEST,
-            Some("After_login_redirect not found"),
-        ));
+        return Err(AppError::with_code(ErrorCode::LoginSessionNotFound));
     };
 
     let creds = Credentials {
+        provider,
         code,
         old_state,
         new_state,
@@ -68,35 +72,248 @@ pub async fn handler(
     let user = match auth_session.authenticate(creds).await {
         Ok(Some(user)) => user,
         // 認証は通っているがユーザーが存在しない場合は新規登録フローに移行させる
-        Err(axum_login::Error::Backend(AuthError::UserNotFound(user_id))) => {
+        Err(axum_login::Error::Backend(AuthError::UserNotFound(identity))) => {
             // クリーンな新規登録セッションを作る
             session.flush().await?;
-            session.insert(SIGNUP_USER_ID_KEY, user_id).await?;
-            return Ok(
-                Redirect::to(&format!("{}{}", Env::client_url(), Env::signup_page()))
-                    .into_response(),
-            );
+            session.insert(SIGNUP_IDENTITY_KEY, identity).await?;
+            return Ok(auth_session
+                .backend
+                .redirect_policy
+                .to_client_page(&auth_session.backend.client.signup_page)
+                .into_response());
         }
         Ok(None) => return Err(AppError::unauthorized()),
+        Err(axum_login::Error::Backend(AuthError::ProviderUnavailable(_))) => {
+            return Err(AppError::with_code(ErrorCode::AuthProviderUnavailable));
+        }
         Err(_) => {
             return Err(AppError::new(StatusCode::INTERNAL_SERVER_ERROR, None));
         }
     };
 
-    auth_session.login(&user).await?;
+    session_metadata::login(&mut auth_session, &session, &user, &client).await?;
 
-    Ok(Redirect::to(&format!("{}{}", Env::client_url(), after_login_redirect)).into_response())
+    // セッションに保存する前にも確認しているが、リダイレクトする前にもう一度確認する
+    Ok(auth_session
+        .backend
+        .redirect_policy
+        .to_client(&after_login_redirect)
+        .into_response())
 }
 
 /// このハンドラで発生したエラーはフロントエンド側で補足できないのでリダイレクトさせる
-pub async fn handle_all_error(request: Request, next: Next) -> impl IntoResponse {
+pub async fn handle_all_error(
+    auth_session: AuthSession<Auth>,
+    request: Request,
+    next: Next,
+) -> impl IntoResponse {
     let response = next.run(request).await;
     let status = response.status();
 
     if status.is_client_error() | status.is_server_error() {
-        return Redirect::to(&format!("{}{}", Env::client_url(), Env::auth_error_page()))
+        return auth_session
+            .backend
+            .redirect_policy
+            .to_client_page(&auth_session.backend.client.auth_error_page)
             .into_response();
     }
 
     response
 }
+
+#[cfg(test)]
+mod tests {
+    use axum_test::TestResponse;
+    use http::{header::LOCATION, StatusCode};
+    use url::Url;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            auth::{
+                mock_idp::MockIdp,
+                routes::{
+                    session::SessionResponse, signup::CreateUser,
+                    signup_session::SignupSessionResponse, AuthPaths,
+                },
+            },
+            user::User,
+        },
+    };
+
+    fn location(res: &TestResponse) -> String {
+        res.header(LOCATION).to_str().unwrap().to_string()
+    }
+
+    /// 発行者がリダイレクトしたコールバックのURLにアクセスする。
+    /// パスにクエリを含めるとエンコードされてしまうので、クエリはパラメーターとして渡す
+    async fn callback(test: &AppTest, url: &Url) -> TestResponse {
+        let mut req = test.server().get(url.path());
+        for (key, value) in url.query_pairs() {
+            req = req.add_query_param(&key, value.as_ref());
+        }
+        req.await
+    }
+
+    fn replace_query_param(url: &Url, key: &str, value: &str) -> Url {
+        let mut replaced = url.clone();
+        replaced
+            .query_pairs_mut()
+            .clear()
+            .extend_pairs(url.query_pairs().map(
+                |(k, v)| {
+                    if k == key {
+                        (k, value.into())
+                    } else {
+                        (k, v)
+                    }
+                },
+            ));
+        replaced
+    }
+
+    /// ログインを開始して、アプリが作った認可リクエストのURLを返す
+    async fn start_login(test: &AppTest) -> AppResult<Url> {
+        let res = test
+            .server()
+            .get(&AuthPaths::login())
+            .add_query_param("after_login_redirect", "/tasks")
+            .await;
+        assert_eq!(res.status_code(), StatusCode::SEE_OTHER);
+
+        Ok(Url::parse(&location(&res))?)
+    }
+
+    /// モックの発行者で指定したユーザーとしてログインして、コールバックのパスを返す
+    async fn login_with_idp(test: &AppTest, idp: &MockIdp, subject: &str) -> AppResult<Url> {
+        let authorize_url = start_login(test).await?;
+        let callback_url = idp.authorize_by_url(&authorize_url, Some(subject))?;
+
+        Ok(callback_url)
+    }
+
+    #[sqlx::test]
+    async fn 新規登録したユーザーは次から同じプロバイダーでログインできる(
+        db: Db,
+    ) -> AppResult<()> {
+        let (test, idp) = AppTest::with_mock_idp(&db).await?;
+
+        // 初めてログインしたユーザーは新規登録ページにリダイレクトされる
+        let callback_url = login_with_idp(&test, &idp, "subject").await?;
+        let res = callback(&test, &callback_url).await;
+        assert_eq!(
+            location(&res),
+            format!(
+                "{}{}",
+                test.config().client.url,
+                test.config().client.signup_page
+            )
+        );
+
+        let signup_session: SignupSessionResponse =
+            test.server().get(&AuthPaths::signup_session()).await.json();
+        assert!(signup_session.session_exists);
+
+        let res = test
+            .server()
+            .post(&AuthPaths::signup())
+            .json(&CreateUser::default())
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+        let user: User = res.json();
+
+        test.server()
+            .post(&AuthPaths::logout())
+            .await
+            .assert_status_ok();
+
+        // 2回目からはログイン前に指定したページにリダイレクトされる
+        let callback_url = login_with_idp(&test, &idp, "subject").await?;
+        let res = callback(&test, &callback_url).await;
+        assert_eq!(
+            location(&res),
+            format!("{}/tasks", test.config().client.url)
+        );
+
+        let session: SessionResponse = test.server().get(&AuthPaths::session()).await.json();
+        assert_eq!(session.session.map(|s| s.user.id), Some(user.id));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn stateが一致しない場合はログインできない(db: Db) -> AppResult<()> {
+        let (test, idp) = AppTest::with_mock_idp(&db).await?;
+
+        let authorize_url = start_login(&test).await?;
+        let callback_url = idp.authorize_by_url(&authorize_url, Some("subject"))?;
+        let callback_url = replace_query_param(&callback_url, "state", "invalid");
+        let res = callback(&test, &callback_url).await;
+        assert_eq!(
+            location(&res),
+            format!(
+                "{}{}",
+                test.config().client.url,
+                test.config().client.auth_error_page
+            )
+        );
+
+        let signup_session: SignupSessionResponse =
+            test.server().get(&AuthPaths::signup_session()).await.json();
+        assert!(!signup_session.session_exists);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn nonceが一致しない場合はログインできない(db: Db) -> AppResult<()> {
+        let (test, idp) = AppTest::with_mock_idp(&db).await?;
+
+        let authorize_url = start_login(&test).await?;
+        let authorize_url = replace_query_param(&authorize_url, "nonce", "invalid");
+        let callback_url = idp.authorize_by_url(&authorize_url, Some("subject"))?;
+        let res = callback(&test, &callback_url).await;
+        assert_eq!(
+            location(&res),
+            format!(
+                "{}{}",
+                test.config().client.url,
+                test.config().client.auth_error_page
+            )
+        );
+
+        let signup_session: SignupSessionResponse =
+            test.server().get(&AuthPaths::signup_session()).await.json();
+        assert!(!signup_session.session_exists);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 新規登録をキャンセルすると新規登録できなくなる(
+        db: Db,
+    ) -> AppResult<()> {
+        let (test, idp) = AppTest::with_mock_idp(&db).await?;
+
+        let callback_url = login_with_idp(&test, &idp, "subject").await?;
+        callback(&test, &callback_url).await;
+
+        test.server()
+            .post(&AuthPaths::cancel_signup())
+            .await
+            .assert_status_ok();
+
+        let signup_session: SignupSessionResponse =
+            test.server().get(&AuthPaths::signup_session()).await.json();
+        assert!(!signup_session.session_exists);
+
+        let res = test
+            .server()
+            .post(&AuthPaths::signup())
+            .json(&CreateUser::default())
+            .await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+}
@@ -1,7 +1,14 @@
 use crate::app::AppResult;
-use crate::features::auth::Auth;
+use crate::error::ProblemDetails;
+use crate::features::auth::{
+    session_metadata::{
+        db::{delete_session_metadata, DeleteSessionMetadataArgs},
+        SESSION_METADATA_KEY,
+    },
+    Auth,
+};
 use axum::response::IntoResponse;
-use axum_login::AuthSession;
+use axum_login::{tower_sessions::Session, AuthSession};
 use http::StatusCode;
 
 #[tracing::instrument(err)]
@@ -9,9 +16,32 @@ use http::StatusCode;
     post,
     tag = super::TAG,
     path = super::AuthPaths::logout(),
-    responses ((status = 200))
+    responses(
+        (status = 200),
+        (status = 403, response = ProblemDetails),
+        (status = 500, response = ProblemDetails)
+    )
 )]
-pub async fn handler(mut auth_session: AuthSession<Auth>) -> AppResult<impl IntoResponse> {
+pub async fn handler(
+    mut auth_session: AuthSession<Auth>,
+    session: Session,
+) -> AppResult<impl IntoResponse> {
+    // ログアウトしたセッションは一覧に出さない
+    if let (Some(user), Some(id)) = (
+        &auth_session.user,
+        session.get::<String>(SESSION_METADATA_KEY).await?,
+    ) {
+        let mut conn = auth_session.backend.db.acquire().await?;
+        delete_session_metadata(
+            &mut conn,
+            DeleteSessionMetadataArgs {
+                id: &id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+    }
+
     auth_session.logout().await?;
 
     Ok(StatusCode::OK.into_response())
@@ -0,0 +1,82 @@
+use axum::Json;
+use axum_login::{tower_sessions::Session, AuthSession};
+
+use crate::{
+    app::AppResult,
+    error::{AppError, ProblemDetails},
+    features::auth::{
+        session_metadata::{
+            db::{delete_other_session_metadata, DeleteOtherSessionMetadataArgs},
+            RevokeOtherSessionsResponse, SESSION_METADATA_KEY,
+        },
+        Auth,
+    },
+};
+
+/// 今のセッション以外のすべてのセッションを取り消す
+#[tracing::instrument(err)]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::AuthPaths::revoke_other_sessions(),
+    responses(
+        (status = 200, body = RevokeOtherSessionsResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    session: Session,
+) -> AppResult<Json<RevokeOtherSessionsResponse>> {
+    let Some(user) = &auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+    let current_id = session.get::<String>(SESSION_METADATA_KEY).await?;
+
+    let mut tx = auth_session.backend.db.begin().await?;
+    let revoked = delete_other_session_metadata(
+        &mut tx,
+        DeleteOtherSessionMetadataArgs {
+            current_id: current_id.as_deref(),
+            user_id: &user.id,
+        },
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(RevokeOtherSessionsResponse { revoked }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::auth::{
+            routes::AuthPaths,
+            session_metadata::{RevokeOtherSessionsResponse, SessionMetadata},
+            test::session_metadata_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 他のすべてのセッションを取り消せる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+        session_metadata_factory::create(&db, &user.id, None).await?;
+        session_metadata_factory::create(&db, &user.id, None).await?;
+
+        let res: RevokeOtherSessionsResponse = test
+            .server()
+            .post(&AuthPaths::revoke_other_sessions())
+            .await
+            .json();
+        assert_eq!(res.revoked, 2);
+
+        let sessions: Vec<SessionMetadata> = test.server().get(&AuthPaths::sessions()).await.json();
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].current);
+
+        Ok(())
+    }
+}
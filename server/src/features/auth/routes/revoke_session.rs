@@ -0,0 +1,173 @@
+use axum::{extract::Path, Json};
+use axum_login::{tower_sessions::Session, AuthSession};
+
+use crate::{
+    app::AppResult,
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::auth::{
+        session_metadata::{
+            db::{delete_session_metadata, DeleteSessionMetadataArgs},
+            RevokeSessionResponse, SESSION_METADATA_KEY,
+        },
+        Auth,
+    },
+};
+
+/// セッションを取り消す。取り消されたセッションは削除され、次のリクエストからログアウトした状態になる
+#[tracing::instrument(err)]
+#[utoipa::path(
+    delete,
+    tag = super::TAG,
+    path = super::AuthPaths::login_session_open_api(),
+    responses(
+        (status = 200, body = RevokeSessionResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    mut auth_session: AuthSession<Auth>,
+    session: Session,
+    Path(id): Path<String>,
+) -> AppResult<Json<RevokeSessionResponse>> {
+    let Some(user) = auth_session.user.clone() else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = auth_session.backend.db.begin().await?;
+    let Some(session_id) = delete_session_metadata(
+        &mut tx,
+        DeleteSessionMetadataArgs {
+            id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::with_code(ErrorCode::SessionNotFound));
+    };
+    tx.commit().await?;
+
+    // 今のセッションを取り消した場合は、すぐにログアウトする
+    let current_id = session.get::<String>(SESSION_METADATA_KEY).await?;
+    if current_id.as_deref() == Some(session_id.as_str()) {
+        auth_session.logout().await?;
+    }
+
+    Ok(Json(RevokeSessionResponse { session_id }))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        error::{ErrorCode, ProblemDetails},
+        features::{
+            auth::{
+                routes::{session::SessionResponse, AuthPaths},
+                session_metadata::SessionMetadata,
+                test::session_metadata_factory,
+            },
+            task::routes::TaskPaths,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 他の端末のセッションを取り消せる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+        let other_id = session_metadata_factory::create(&db, &user.id, None).await?;
+
+        test.server()
+            .delete(&AuthPaths::one_login_session(&other_id))
+            .await
+            .assert_status_ok();
+
+        let sessions: Vec<SessionMetadata> = test.server().get(&AuthPaths::sessions()).await.json();
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].current);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 取り消したセッションはセッションストアからも削除される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+        let other_device = AppTest::new(&db).await?;
+        other_device.login_as(&user.id).await?;
+
+        let sessions: Vec<SessionMetadata> = test.server().get(&AuthPaths::sessions()).await.json();
+        let other = sessions.iter().find(|s| !s.current).unwrap();
+        test.server()
+            .delete(&AuthPaths::one_login_session(&other.id))
+            .await
+            .assert_status_ok();
+
+        let count = sqlx::query_scalar!("SELECT COUNT(*) FROM sessions;")
+            .fetch_one(&db)
+            .await?;
+        assert_eq!(count, 1);
+
+        let res = other_device.server().get(&TaskPaths::tasks()).await;
+        assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+        test.server()
+            .get(&TaskPaths::tasks())
+            .await
+            .assert_status_ok();
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 取り消されたセッションではログアウトした状態になる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        let sessions: Vec<SessionMetadata> = test.server().get(&AuthPaths::sessions()).await.json();
+
+        // 別の端末から取り消されたことにする
+        sqlx::query!(
+            "DELETE FROM session_metadata WHERE id = $1;",
+            sessions[0].id
+        )
+        .execute(&db)
+        .await?;
+
+        let res = test.server().get(&TaskPaths::tasks()).await;
+        assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+
+        let session: SessionResponse = test.server().get(&AuthPaths::session()).await.json();
+        assert!(session.session.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のセッションは取り消せない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        // ログインするたびに新しいユーザーが作られるので、一人目を他人として扱う
+        let other_user = test.login(None).await?;
+        let other_id = session_metadata_factory::create(&db, &other_user.id, None).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .delete(&AuthPaths::one_login_session(&other_id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+        let problem: ProblemDetails = res.json();
+        assert_eq!(problem.code, ErrorCode::SessionNotFound);
+
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use crate::app::AppResult;
+use crate::error::ProblemDetails;
 use crate::features::auth::{Auth, Session};
 use axum::Json;
 use axum_login::AuthSession;
@@ -16,7 +17,10 @@ pub struct SessionResponse {
     get,
     tag = super::TAG,
     path = super::AuthPaths::session(),
-    responses((status = 200, body = SessionResponse))
+    responses(
+        (status = 200, body = SessionResponse),
+        (status = 500, response = ProblemDetails)
+    )
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
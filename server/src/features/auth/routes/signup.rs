@@ -1,9 +1,19 @@
-use super::login_callback::SIGNUP_USER_ID_KEY;
+use super::login_callback::SIGNUP_IDENTITY_KEY;
 use crate::app::AppResult;
-use crate::features::user::db::{insert_user, InsertUserArgs};
-use crate::{app::AppState, error::AppError, features::auth::Auth};
+use crate::features::auth::{
+    session_metadata::{self, ClientInfo},
+    Identity,
+};
+use crate::features::user::db::{
+    insert_user, insert_user_identity, InsertUserArgs, InsertUserIdentityArgs,
+};
+use crate::{
+    app::AppState,
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::auth::Auth,
+    validation::{self, WithValidation},
+};
 use axum::{extract::State, response::IntoResponse, Json};
-use axum_garde::WithValidation;
 use axum_login::{tower_sessions::Session, AuthSession};
 use garde::Validate;
 use http::StatusCode;
@@ -12,7 +22,7 @@ use utoipa::ToSchema;
 
 #[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
 pub struct CreateUser {
-    #[garde(length(min = 1, max = 100))]
+    #[garde(custom(validation::length(1, 100)))]
     #[schema(min_length = 1, max_length = 100)]
     pub name: String,
 
@@ -21,28 +31,35 @@ pub struct CreateUser {
     pub profile: String,
 }
 
-#[tracing::instrument(err, skip(auth_session, session, db))]
+#[tracing::instrument(err, skip(auth_session, session, client, db))]
 #[utoipa::path(
     post,
     tag = super::TAG,
     path = super::AuthPaths::signup(),
     request_body = CreateUser,
-    responses((status = 201, body = User))
+    responses(
+        (status = 201, body = User),
+        (status = 400, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 422, response = ProblemDetails),
+        (status = 500, response = ProblemDetails)
+    )
 )]
 pub async fn handler(
     mut auth_session: AuthSession<Auth>,
     session: Session,
-    State(AppState { db }): State<AppState>,
+    client: ClientInfo,
+    State(AppState { db, .. }): State<AppState>,
     WithValidation(payload): WithValidation<Json<CreateUser>>,
 ) -> AppResult<impl IntoResponse> {
-    let Ok(Some(user_id)) = session.get::<String>(SIGNUP_USER_ID_KEY).await else {
-        return Err(AppError::new(
-            StatusCode::BAD_REQUEST,
-            Some("Signup session not found"),
-        ));
+    let Ok(Some(identity)) = session.get::<Identity>(SIGNUP_IDENTITY_KEY).await else {
+        return Err(AppError::with_code(ErrorCode::SignupSessionNotFound));
     };
 
     let mut conn = db.begin().await?;
+
+    // プロバイダーのsubjectはプロバイダーをまたぐと重複する可能性があるので、ユーザーのIDには使わない
+    let user_id = uuid::Uuid::new_v4().to_string();
     let user = insert_user(
         &mut conn,
         InsertUserArgs {
@@ -52,11 +69,20 @@ pub async fn handler(
         },
     )
     .await?;
+    insert_user_identity(
+        &mut conn,
+        InsertUserIdentityArgs {
+            user_id: &user_id,
+            provider: &identity.provider,
+            subject: &identity.subject,
+        },
+    )
+    .await?;
 
     conn.commit().await?;
 
     session.flush().await?;
-    auth_session.login(&user).await?;
+    session_metadata::login(&mut auth_session, &session, &user, &client).await?;
 
     Ok((StatusCode::CREATED, Json(user)).into_response())
 }
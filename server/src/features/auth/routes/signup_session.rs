@@ -1,16 +1,16 @@
 use axum::Json;
 use axum_login::tower_sessions::Session;
 use http::StatusCode;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::app::AppResult;
+use crate::{app::AppResult, error::ProblemDetails, features::auth::Identity};
 
-use super::login_callback::SIGNUP_USER_ID_KEY;
+use super::login_callback::SIGNUP_IDENTITY_KEY;
 
-#[derive(Serialize, ToSchema, Debug)]
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
 pub struct SignupSessionResponse {
-    session_exists: bool,
+    pub session_exists: bool,
 }
 
 #[tracing::instrument(err)]
@@ -18,10 +18,16 @@ pub struct SignupSessionResponse {
     get,
     tag = super::TAG,
     path = super::AuthPaths::signup_session(),
-    responses((status = 200, body = SignupSessionResponse))
+    responses(
+        (status = 200, body = SignupSessionResponse),
+        (status = 500, response = ProblemDetails)
+    )
 )]
 pub async fn handler(session: Session) -> AppResult<(StatusCode, Json<SignupSessionResponse>)> {
-    let session_exists = matches!(session.get::<String>(SIGNUP_USER_ID_KEY).await, Ok(Some(_)));
+    let session_exists = matches!(
+        session.get::<Identity>(SIGNUP_IDENTITY_KEY).await,
+        Ok(Some(_))
+    );
 
     Ok((
         StatusCode::OK,
@@ -0,0 +1,188 @@
+pub mod db;
+
+use std::net::SocketAddr;
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts, Request},
+    middleware::Next,
+    response::Response,
+};
+use axum_login::{tower_sessions::Session, AuthSession};
+use http::{header::USER_AGENT, request::Parts, Extensions, HeaderMap};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use self::db::{
+    insert_session_metadata, touch_session_metadata, InsertSessionMetadataArgs,
+    TouchSessionMetadataArgs,
+};
+use super::Auth;
+use crate::{
+    app::AppResult,
+    error::AppError,
+    features::{personal_access_token::middleware::TokenScopes, user::User},
+};
+
+/// セッションに保存する、session_metadataテーブルのID
+pub const SESSION_METADATA_KEY: &str = "auth.session_metadata_id";
+
+/// 最終アクセス日時を更新する間隔
+pub const TOUCH_INTERVAL_MINUTES: i64 = 5;
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct SessionMetadata {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: String,
+    pub last_seen_at: String,
+    /// リクエストを送ったセッションかどうか
+    pub current: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct RevokeSessionResponse {
+    pub session_id: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct RevokeOtherSessionsResponse {
+    /// 取り消したセッションの数
+    pub revoked: u64,
+}
+
+/// セッションの情報として記録する、リクエストを送ったクライアントの情報
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl ClientInfo {
+    pub fn new(headers: &HeaderMap, extensions: &Extensions) -> Self {
+        let user_agent = headers
+            .get(USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+
+        // 表示するためだけに使うので、プロキシを経由している場合はX-Forwarded-Forをそのまま信じる
+        let ip_address = headers
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .or_else(|| {
+                extensions
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(addr)| addr.ip().to_string())
+            });
+
+        ClientInfo {
+            user_agent,
+            ip_address,
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(ClientInfo::new(&parts.headers, &parts.extensions))
+    }
+}
+
+/// ログインして、セッションの情報を記録する。セッションを一覧できるように`auth_session.login`の代わりに使う
+pub async fn login(
+    auth_session: &mut AuthSession<Auth>,
+    session: &Session,
+    user: &User,
+    client: &ClientInfo,
+) -> AppResult<()> {
+    auth_session.login(user).await?;
+    record(auth_session, session, &user.id, client, true).await?;
+
+    Ok(())
+}
+
+async fn record(
+    auth_session: &AuthSession<Auth>,
+    session: &Session,
+    user_id: &str,
+    client: &ClientInfo,
+    authenticated: bool,
+) -> AppResult<()> {
+    let id = uuid::Uuid::new_v4().to_string();
+    // ログインしたときはIDが振り直されているので、振り直したあとのIDを記録する
+    let session_id = session.id().map(|id| id.to_string());
+    let mut conn = auth_session.backend.db.acquire().await?;
+    insert_session_metadata(
+        &mut conn,
+        InsertSessionMetadataArgs {
+            id: &id,
+            user_id,
+            session_id: session_id.as_deref(),
+            user_agent: client.user_agent.as_deref(),
+            ip_address: client.ip_address.as_deref(),
+            authenticated,
+        },
+    )
+    .await?;
+    session.insert(SESSION_METADATA_KEY, &id).await?;
+
+    Ok(())
+}
+
+/// ログインしているセッションの最終アクセス日時を、`TOUCH_INTERVAL_MINUTES`ごとに更新する。
+/// 取り消されたセッションの場合は、このリクエストからログアウトさせる。
+pub async fn track_session(mut req: Request, next: Next) -> AppResult<Response> {
+    // トークンで認証したリクエストはセッションを使わない
+    if req.extensions().get::<TokenScopes>().is_some() {
+        return Ok(next.run(req).await);
+    }
+
+    let client = ClientInfo::new(req.headers(), req.extensions());
+    let Some(session) = req.extensions().get::<Session>().cloned() else {
+        return Err(AppError::from(anyhow::anyhow!("Session not found")));
+    };
+    let Some(auth_session) = req.extensions_mut().get_mut::<AuthSession<Auth>>() else {
+        return Err(AppError::from(anyhow::anyhow!("AuthSession not found")));
+    };
+    let Some(user) = auth_session.user.clone() else {
+        return Ok(next.run(req).await);
+    };
+
+    match session.get::<String>(SESSION_METADATA_KEY).await? {
+        Some(id) => {
+            let session_id = session.id().map(|id| id.to_string());
+            let mut conn = auth_session.backend.db.acquire().await?;
+            let touched = touch_session_metadata(
+                &mut conn,
+                TouchSessionMetadataArgs {
+                    id: &id,
+                    user_id: &user.id,
+                    session_id: session_id.as_deref(),
+                    interval_minutes: TOUCH_INTERVAL_MINUTES,
+                },
+            )
+            .await?;
+            drop(conn);
+
+            if !touched {
+                // logoutはセッションを消すだけなので、このリクエストのユーザーも消す
+                auth_session.logout().await?;
+                auth_session.user = None;
+            }
+        }
+        // セッションの情報を記録する前にログインしたセッションは、ここで記録する
+        None => record(auth_session, &session, &user.id, &client, false).await?,
+    }
+
+    Ok(next.run(req).await)
+}
@@ -0,0 +1,247 @@
+use crate::app::Connection;
+
+use super::SessionMetadata;
+
+pub struct InsertSessionMetadataArgs<'a> {
+    pub id: &'a str,
+    pub user_id: &'a str,
+    /// 対応するsessionsテーブルのID
+    pub session_id: Option<&'a str>,
+    pub user_agent: Option<&'a str>,
+    pub ip_address: Option<&'a str>,
+    /// プロバイダーで認証してログインしたときはtrue
+    pub authenticated: bool,
+}
+pub async fn insert_session_metadata<'a>(
+    db: &mut Connection,
+    args: InsertSessionMetadataArgs<'a>,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO session_metadata(id, user_id, session_id, user_agent, ip_address, authenticated_at)
+        VALUES(
+            $1,
+            $2,
+            $3,
+            $4,
+            $5,
+            CASE WHEN $6 THEN strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime') END
+        );
+        "#,
+        args.id,
+        args.user_id,
+        args.session_id,
+        args.user_agent,
+        args.ip_address,
+        args.authenticated,
+    )
+    .execute(&mut *db)
+    .await?;
+
+    Ok(())
+}
+
+pub struct TouchSessionMetadataArgs<'a> {
+    pub id: &'a str,
+    pub user_id: &'a str,
+    pub session_id: Option<&'a str>,
+    /// 最終アクセス日時を更新する間隔
+    pub interval_minutes: i64,
+}
+/// 最終アクセス日時を更新する。リクエストのたびに書き込まないように、前回の更新から
+/// `interval_minutes`が経っていない場合は更新しない。取り消されている場合はfalseを返す
+pub async fn touch_session_metadata<'a>(
+    db: &mut Connection,
+    args: TouchSessionMetadataArgs<'a>,
+) -> anyhow::Result<bool> {
+    let modifier = format!("-{} minutes", args.interval_minutes);
+    let Some(row) = sqlx::query!(
+        r#"
+        SELECT
+            last_seen_at <= strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime', $3)
+            OR session_id IS NOT $4 as "stale!: bool"
+        FROM session_metadata
+        WHERE id = $1 AND user_id = $2;
+        "#,
+        args.id,
+        args.user_id,
+        modifier,
+        args.session_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?
+    else {
+        return Ok(false);
+    };
+
+    if row.stale {
+        sqlx::query!(
+            r#"
+            UPDATE session_metadata
+            SET
+                last_seen_at = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime'),
+                session_id = $3
+            WHERE id = $1 AND user_id = $2;
+            "#,
+            args.id,
+            args.user_id,
+            args.session_id,
+        )
+        .execute(&mut *db)
+        .await?;
+    }
+
+    Ok(true)
+}
+
+pub struct IsRecentlyAuthenticatedArgs<'a> {
+    pub id: &'a str,
+    pub user_id: &'a str,
+    pub within_minutes: i64,
+}
+/// セッションが指定した時間内にプロバイダーで認証してログインしたものかを返す
+pub async fn is_recently_authenticated<'a>(
+    db: &mut Connection,
+    args: IsRecentlyAuthenticatedArgs<'a>,
+) -> anyhow::Result<bool> {
+    let modifier = format!("-{} minutes", args.within_minutes);
+    let result = sqlx::query!(
+        r#"
+        SELECT id
+        FROM session_metadata
+        WHERE
+            id = $1
+            AND user_id = $2
+            AND authenticated_at >= strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime', $3);
+        "#,
+        args.id,
+        args.user_id,
+        modifier,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.is_some())
+}
+
+/// 有効期限が切れていないセッションを、最後に使われた順に返す
+pub async fn find_session_metadata_list(
+    db: &mut Connection,
+    user_id: &str,
+    current_id: Option<&str>,
+    expiry_days: i64,
+) -> anyhow::Result<Vec<SessionMetadata>> {
+    // セッションは最後に使われてから一定期間で期限切れになる
+    let expiry_modifier = format!("-{} days", expiry_days);
+    let sessions = sqlx::query_as!(
+        SessionMetadata,
+        r#"
+        SELECT
+            id,
+            user_agent,
+            ip_address,
+            created_at,
+            last_seen_at,
+            id IS $2 as "current!: bool"
+        FROM session_metadata
+        WHERE
+            user_id = $1
+            AND last_seen_at > strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime', $3)
+        ORDER BY last_seen_at DESC, rowid DESC;
+        "#,
+        user_id,
+        current_id,
+        expiry_modifier,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    Ok(sessions)
+}
+
+pub struct DeleteSessionMetadataArgs<'a> {
+    pub id: &'a str,
+    pub user_id: &'a str,
+}
+/// セッションの情報と、対応するsessionsテーブルのセッションを削除する。トランザクションの中で呼び出す。
+/// 削除したセッションのIDを返す。セッションが存在しない場合はNoneを返す
+pub async fn delete_session_metadata<'a>(
+    db: &mut Connection,
+    args: DeleteSessionMetadataArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    sqlx::query!(
+        r#"
+        DELETE FROM sessions
+        WHERE id IN (
+            SELECT session_id FROM session_metadata WHERE id = $1 AND user_id = $2
+        );
+        "#,
+        args.id,
+        args.user_id,
+    )
+    .execute(&mut *db)
+    .await?;
+
+    let result = sqlx::query!(
+        "DELETE FROM session_metadata WHERE id = $1 AND user_id = $2 RETURNING id;",
+        args.id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+/// ユーザーのすべてのセッションを、sessionsテーブルのセッションとともに削除する
+pub async fn delete_user_sessions(db: &mut Connection, user_id: &str) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM sessions
+        WHERE id IN (SELECT session_id FROM session_metadata WHERE user_id = $1);
+        "#,
+        user_id,
+    )
+    .execute(&mut *db)
+    .await?;
+
+    sqlx::query!("DELETE FROM session_metadata WHERE user_id = $1;", user_id)
+        .execute(&mut *db)
+        .await?;
+
+    Ok(())
+}
+
+pub struct DeleteOtherSessionMetadataArgs<'a> {
+    pub current_id: Option<&'a str>,
+    pub user_id: &'a str,
+}
+/// 指定したセッション以外のセッションを、sessionsテーブルのセッションとともに削除して、削除した数を返す。
+/// トランザクションの中で呼び出す。
+pub async fn delete_other_session_metadata<'a>(
+    db: &mut Connection,
+    args: DeleteOtherSessionMetadataArgs<'a>,
+) -> anyhow::Result<u64> {
+    sqlx::query!(
+        r#"
+        DELETE FROM sessions
+        WHERE id IN (
+            SELECT session_id FROM session_metadata WHERE user_id = $1 AND id IS NOT $2
+        );
+        "#,
+        args.user_id,
+        args.current_id,
+    )
+    .execute(&mut *db)
+    .await?;
+
+    let result = sqlx::query!(
+        "DELETE FROM session_metadata WHERE user_id = $1 AND id IS NOT $2;",
+        args.user_id,
+        args.current_id,
+    )
+    .execute(&mut *db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -1,23 +1,77 @@
+#[cfg(test)]
+pub mod session_metadata_factory {
+    use crate::{
+        app::{AppResult, Db},
+        features::auth::session_metadata::db::{
+            insert_session_metadata, InsertSessionMetadataArgs,
+        },
+    };
+
+    /// 別の端末でログインしているセッションとして、セッションの情報だけを作成してIDを返す
+    pub async fn create(db: &Db, user_id: &str, user_agent: Option<&str>) -> AppResult<String> {
+        let mut conn = db.acquire().await?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        insert_session_metadata(
+            &mut conn,
+            InsertSessionMetadataArgs {
+                id: &id,
+                user_id,
+                session_id: None,
+                user_agent,
+                ip_address: None,
+                authenticated: false,
+            },
+        )
+        .await?;
+
+        Ok(id)
+    }
+}
+
 #[cfg(test)]
 pub mod routes {
     use crate::app::AppResult;
     use crate::{
         app::AppState,
         features::{
-            auth::{routes::signup::CreateUser, Auth},
+            auth::{
+                routes::signup::CreateUser,
+                session_metadata::{self, ClientInfo},
+                Auth,
+            },
             user::User,
         },
     };
-    use axum::{extract::State, routing::post, Json, Router};
-    use axum_login::AuthSession;
+    use axum::{
+        extract::{Path, State},
+        routing::post,
+        Json, Router,
+    };
+    use axum_login::{tower_sessions::Session, AuthSession};
     use http::StatusCode;
 
+    impl crate::features::auth::routes::AuthPaths {
+        pub fn one_login_session(id: &str) -> String {
+            Self::sessions() + "/" + id
+        }
+    }
+
     pub struct Paths;
     impl Paths {
         /// 指定したユーザーでログインセッションを作成する
         pub fn test_login() -> String {
             "/test/login".into()
         }
+
+        /// 作成済みのユーザーでログインセッションを作成する
+        pub fn test_login_as(user_id: &str) -> String {
+            Self::test_login() + "/" + user_id
+        }
+
+        fn test_login_as_route() -> String {
+            Self::test_login() + "/:user_id"
+        }
     }
 
     impl Default for CreateUser {
@@ -31,7 +85,9 @@ pub mod routes {
 
     async fn test_login_handler(
         mut auth_session: AuthSession<Auth>,
-        State(AppState { db }): State<AppState>,
+        session: Session,
+        client: ClientInfo,
+        State(AppState { db, .. }): State<AppState>,
         Json(payload): Json<CreateUser>,
     ) -> AppResult<(StatusCode, Json<User>)> {
         let id = uuid::Uuid::new_v4().to_string();
@@ -45,12 +101,30 @@ pub mod routes {
         .fetch_one(&db)
         .await?;
 
-        auth_session.login(&user).await?;
+        session_metadata::login(&mut auth_session, &session, &user, &client).await?;
+
+        Ok((StatusCode::OK, Json(user)))
+    }
+
+    async fn test_login_as_handler(
+        mut auth_session: AuthSession<Auth>,
+        session: Session,
+        client: ClientInfo,
+        State(AppState { db, .. }): State<AppState>,
+        Path(user_id): Path<String>,
+    ) -> AppResult<(StatusCode, Json<User>)> {
+        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = $1;", user_id)
+            .fetch_one(&db)
+            .await?;
+
+        session_metadata::login(&mut auth_session, &session, &user, &client).await?;
 
         Ok((StatusCode::OK, Json(user)))
     }
 
     pub fn router() -> Router<AppState> {
-        Router::new().route(&Paths::test_login(), post(test_login_handler))
+        Router::new()
+            .route(&Paths::test_login(), post(test_login_handler))
+            .route(&Paths::test_login_as_route(), post(test_login_as_handler))
     }
 }
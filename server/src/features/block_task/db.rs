@@ -8,6 +8,7 @@ use crate::{
                 DetectCircularConnectionArgs, ExistsTasksArg, ExistsTasksError,
                 UpdateTaskStatusArgs, UpdateTasksStatusArgs,
             },
+            graph::TaskGraph,
             TaskStatus,
         },
     },
@@ -53,11 +54,18 @@ pub async fn delete_block_task_connection<'a>(
     Ok(())
 }
 
+/// `graph`が渡された場合は、更新する子孫サブタスクをデータベースではなくメモリ上で求める
 pub async fn update_all_unblocked_descendant_sub_tasks<'a>(
     db: &mut Connection,
+    graph: Option<&TaskGraph>,
     args: UpdateTaskStatusArgs<'a>,
 ) -> anyhow::Result<()> {
-    let descendant_ids: Vec<String> = if args.status == &TaskStatus::Todo {
+    let descendant_ids: Vec<String> = if let Some(graph) = graph {
+        match args.status {
+            TaskStatus::Todo => graph.descendant_sub_task_ids(args.id),
+            TaskStatus::Done => graph.unblocked_descendant_sub_task_ids(args.id),
+        }
+    } else if args.status == &TaskStatus::Todo {
         // TODOに変更する場合は何もチェックしない
         let result = sqlx::query!(
             r#"
@@ -147,8 +155,10 @@ pub enum BlockTaskConnectionError {
     Unknown(anyhow::Error),
 }
 
+/// `graph`が渡された場合は、タスクのつながりの確認をデータベースではなくメモリ上で行う
 pub async fn check_insert_block_task_connection<'a>(
     db: &mut Connection,
+    graph: Option<&TaskGraph>,
     args: &InsertBlockTaskConnectionArgs<'a>,
 ) -> Result<(), BlockTaskConnectionError> {
     exists_tasks(
@@ -168,32 +178,38 @@ pub async fn check_insert_block_task_connection<'a>(
         }
     })?;
 
-    if is_sub_task(
-        &mut *db,
-        IsSubTaskArgs {
-            main_task_id: args.blocking_task_id,
-            task_id: args.blocked_task_id,
-            user_id: args.user_id,
-        },
-    )
-    .await
-    .map_err(BlockTaskConnectionError::Unknown)?
-    {
+    let is_sub_task = match graph {
+        Some(graph) => graph.is_descendant_sub_task(args.blocking_task_id, args.blocked_task_id),
+        None => is_sub_task(
+            &mut *db,
+            IsSubTaskArgs {
+                main_task_id: args.blocking_task_id,
+                task_id: args.blocked_task_id,
+                user_id: args.user_id,
+            },
+        )
+        .await
+        .map_err(BlockTaskConnectionError::Unknown)?,
+    };
+    if is_sub_task {
         return Err(BlockTaskConnectionError::IsSubTask);
     };
 
     // タスク同士が循環していないかを確認する。
-    if detect_circular_connection(
-        &mut *db,
-        DetectCircularConnectionArgs {
-            parent_task_id: args.blocking_task_id,
-            child_task_id: args.blocked_task_id,
-            user_id: args.user_id,
-        },
-    )
-    .await
-    .map_err(BlockTaskConnectionError::Unknown)?
-    {
+    let is_circular = match graph {
+        Some(graph) => graph.is_ancestor(args.blocking_task_id, args.blocked_task_id),
+        None => detect_circular_connection(
+            &mut *db,
+            DetectCircularConnectionArgs {
+                parent_task_id: args.blocking_task_id,
+                child_task_id: args.blocked_task_id,
+                user_id: args.user_id,
+            },
+        )
+        .await
+        .map_err(BlockTaskConnectionError::Unknown)?,
+    };
+    if is_circular {
         return Err(BlockTaskConnectionError::CircularTask);
     }
 
@@ -1,5 +1,9 @@
-use crate::{app::AppState, features::auth::Auth};
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope},
+};
 use axum::{
+    middleware,
     routing::{delete, post, put},
     Router,
 };
@@ -43,5 +47,6 @@ pub fn router() -> Router<AppState> {
             &BlockTaskPaths::reconnect_block_task(),
             put(reconnect_block_task::handler),
         )
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
         .route_layer(login_required!(Auth))
 }
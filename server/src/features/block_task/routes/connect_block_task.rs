@@ -1,13 +1,9 @@
-use anyhow::anyhow;
 use axum::{extract::State, Json};
 use axum_login::AuthSession;
-use http::StatusCode;
-use serde::Serialize;
-use utoipa::ToSchema;
 
 use crate::{
     app::{AppResult, AppState},
-    error::AppError,
+    error::{AppError, ErrorCode, ProblemDetails},
     features::{
         auth::Auth,
         block_task::{
@@ -18,18 +14,6 @@ use crate::{
     },
 };
 
-#[derive(Debug, Serialize, ToSchema)]
-pub enum ConnectBlockTaskErrorType {
-    TaskNotFound,
-    IsSubTask,
-    CircularTask,
-}
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct ConnectBlockTaskErrorBody {
-    error_type: ConnectBlockTaskErrorType,
-}
-
 #[tracing::instrument(err)]
 #[utoipa::path(
     post,
@@ -37,12 +21,15 @@ pub struct ConnectBlockTaskErrorBody {
     path = super::BlockTaskPaths::connect_block_task(),
     responses(
         (status = 200),
-        (status = 400, body = ConnectBlockTaskErrorBody)
+        (status = 400, response = ProblemDetails),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
     )
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, task_graph, .. }): State<AppState>,
     Json(payload): Json<ConnectBlockTask>,
 ) -> AppResult<()> {
     let Some(user) = auth_session.user else {
@@ -50,6 +37,7 @@ pub async fn handler(
     };
 
     let mut tx = db.begin().await?;
+    let graph = task_graph.get_or_load(&mut tx, &user.id).await?;
 
     if let Err(e) = connect_block_task::action(
         &mut tx,
@@ -57,26 +45,24 @@ pub async fn handler(
             blocking_task_id: &payload.blocking_task_id,
             blocked_task_id: &payload.blocked_task_id,
             user_id: &user.id,
+            graph: Some(&graph),
         },
     )
     .await
     {
         use ConnectBlockTaskError::{CheckError, Unknown};
-        use ConnectBlockTaskErrorType::{CircularTask, IsSubTask, TaskNotFound};
+        use ErrorCode::{CircularConnection, IsSubTask, TaskNotFound};
 
-        let error_type = match e {
+        let code = match e {
             CheckError(BlockTaskConnectionError::TaskNotFound) => TaskNotFound,
             CheckError(BlockTaskConnectionError::IsSubTask) => IsSubTask,
-            CheckError(BlockTaskConnectionError::CircularTask) => CircularTask,
-            CheckError(BlockTaskConnectionError::Unknown(_)) | Unknown(_) => {
-                return Err(anyhow!("Unknown").into())
+            CheckError(BlockTaskConnectionError::CircularTask) => CircularConnection,
+            CheckError(BlockTaskConnectionError::Unknown(e)) | Unknown(e) => {
+                return Err(e.into());
             }
         };
 
-        return Err(AppError::with_json(
-            StatusCode::BAD_REQUEST,
-            ConnectBlockTaskErrorBody { error_type },
-        ));
+        return Err(AppError::with_code(code));
     };
 
     tx.commit().await?;
@@ -3,7 +3,7 @@ use axum_login::AuthSession;
 
 use crate::{
     app::{AppResult, AppState},
-    error::AppError,
+    error::{AppError, ProblemDetails},
     features::{
         auth::Auth,
         block_task::{
@@ -18,11 +18,15 @@ use crate::{
     delete,
     tag = super::TAG,
     path = super::BlockTaskPaths::disconnect_block_task(),
-    responses(( status = 200))
+    responses(
+        ( status = 200),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, .. }): State<AppState>,
     Json(payload): Json<DisconnectBlockTask>,
 ) -> AppResult<()> {
     let Some(user) = auth_session.user else {
@@ -1,13 +1,9 @@
-use anyhow::anyhow;
 use axum::{extract::State, Json};
 use axum_login::AuthSession;
-use http::StatusCode;
-use serde::Serialize;
-use utoipa::ToSchema;
 
 use crate::{
     app::{AppResult, AppState},
-    error::AppError,
+    error::{AppError, ErrorCode, ProblemDetails},
     features::{
         auth::Auth,
         block_task::{
@@ -20,18 +16,6 @@ use crate::{
     },
 };
 
-#[derive(Debug, Serialize, ToSchema)]
-pub enum ReconnectBlockTaskErrorType {
-    TaskNotFound,
-    IsSubTask,
-    CircularTask,
-}
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct ReconnectBlockTaskErrorBody {
-    error_type: ReconnectBlockTaskErrorType,
-}
-
 #[tracing::instrument(err)]
 #[utoipa::path(
     put,
@@ -39,12 +23,15 @@ pub struct ReconnectBlockTaskErrorBody {
     path = super::BlockTaskPaths::reconnect_block_task(),
     responses(
         (status = 200),
-        (status = 400, body = ReconnectBlockTaskErrorBody)
+        (status = 400, response = ProblemDetails),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
     )
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, task_graph, .. }): State<AppState>,
     Json(payload): Json<ReconnectBlockTask>,
 ) -> AppResult<()> {
     let Some(user) = auth_session.user else {
@@ -52,6 +39,7 @@ pub async fn handler(
     };
 
     let mut tx = db.begin().await?;
+    let graph = task_graph.get_or_load(&mut tx, &user.id).await?;
 
     let result = reconnect_block_task::action(
         &mut tx,
@@ -61,26 +49,24 @@ pub async fn handler(
             new_blocking_task_id: &payload.new_blocking_task_id,
             new_blocked_task_id: &payload.new_blocked_task_id,
             user_id: &user.id,
+            graph: Some(&graph),
         },
     )
     .await;
     if let Err(e) = result {
+        use ErrorCode::{CircularConnection, IsSubTask, TaskNotFound};
         use ReconnectBlockTaskError::{Connect, Unknown};
-        use ReconnectBlockTaskErrorType::{CircularTask, IsSubTask, TaskNotFound};
 
-        let error_type = match e {
+        let code = match e {
             Connect(BlockTaskConnectionError::TaskNotFound) => TaskNotFound,
-            Connect(BlockTaskConnectionError::CircularTask) => CircularTask,
+            Connect(BlockTaskConnectionError::CircularTask) => CircularConnection,
             Connect(BlockTaskConnectionError::IsSubTask) => IsSubTask,
-            Connect(BlockTaskConnectionError::Unknown(_)) | Unknown(_) => {
-                return Err(anyhow!("Unknown").into());
+            Connect(BlockTaskConnectionError::Unknown(e)) | Unknown(e) => {
+                return Err(e.into());
             }
         };
 
-        return Err(AppError::with_json(
-            StatusCode::BAD_REQUEST,
-            ReconnectBlockTaskErrorBody { error_type },
-        ));
+        return Err(AppError::with_code(code));
     };
 
     tx.commit().await?;
@@ -1,8 +1,11 @@
 use crate::{
     app::Connection,
-    features::block_task::db::{
-        check_insert_block_task_connection, insert_block_task_connection, BlockTaskConnectionError,
-        InsertBlockTaskConnectionArgs,
+    features::{
+        block_task::db::{
+            check_insert_block_task_connection, insert_block_task_connection,
+            BlockTaskConnectionError, InsertBlockTaskConnectionArgs,
+        },
+        task::graph::TaskGraph,
     },
 };
 
@@ -10,6 +13,8 @@ pub struct ConnectBlockTaskArgs<'a> {
     pub blocking_task_id: &'a str,
     pub blocked_task_id: &'a str,
     pub user_id: &'a str,
+    /// 接続前のタスクのグラフ
+    pub graph: Option<&'a TaskGraph>,
 }
 
 pub enum ConnectBlockTaskError {
@@ -27,7 +32,7 @@ pub async fn action<'a>(
         user_id: args.user_id,
     };
 
-    check_insert_block_task_connection(db, &insert_args)
+    check_insert_block_task_connection(db, args.graph, &insert_args)
         .await
         .map_err(ConnectBlockTaskError::CheckError)?;
 
@@ -1,4 +1,7 @@
-use crate::{app::Connection, features::block_task::db::BlockTaskConnectionError};
+use crate::{
+    app::Connection,
+    features::{block_task::db::BlockTaskConnectionError, task::graph::TaskGraph},
+};
 
 use super::{
     connect_block_task::{self, ConnectBlockTaskArgs, ConnectBlockTaskError},
@@ -11,6 +14,8 @@ pub struct ReconnectBlockTaskArgs<'a> {
     pub new_blocking_task_id: &'a str,
     pub new_blocked_task_id: &'a str,
     pub user_id: &'a str,
+    /// つなぎ直す前のタスクのグラフ
+    pub graph: Option<&'a TaskGraph>,
 }
 
 pub enum ReconnectBlockTaskError {
@@ -33,12 +38,20 @@ pub async fn action<'a>(
     .await
     .map_err(ReconnectBlockTaskError::Unknown)?;
 
+    // 接続を切り離したあとのグラフで、新しい接続を確認する
+    let graph = args.graph.map(|graph| {
+        let mut graph = graph.clone();
+        graph.remove_blocking_edge(args.old_blocking_task_id, args.old_blocked_task_id);
+        graph
+    });
+
     connect_block_task::action(
         db,
         ConnectBlockTaskArgs {
             blocking_task_id: args.new_blocking_task_id,
             blocked_task_id: args.new_blocked_task_id,
             user_id: args.user_id,
+            graph: graph.as_ref(),
         },
     )
     .await
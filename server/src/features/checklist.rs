@@ -0,0 +1,51 @@
+pub mod db;
+pub mod routes;
+pub mod test;
+pub mod usecases;
+
+use crate::validation;
+use garde::Validate;
+pub use routes::router;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// タスクの中のチェックリストの項目
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct ChecklistItem {
+    pub id: String,
+    pub task_id: String,
+    pub text: String,
+    pub checked: bool,
+    /// タスクの中での並び順
+    pub sort_order: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct CreateChecklistItem {
+    #[garde(custom(validation::length(1, 200)))]
+    #[schema(min_length = 1, max_length = 200)]
+    pub text: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct UpdateChecklistItem {
+    #[garde(custom(validation::length(1, 200)))]
+    #[schema(min_length = 1, max_length = 200)]
+    pub text: String,
+
+    #[garde(skip)]
+    pub checked: bool,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug)]
+pub struct ReorderChecklistItems {
+    /// タスクのすべての項目のIDを、新しい並び順で指定する
+    pub item_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct DeleteChecklistItemResponse {
+    pub item_id: String,
+}
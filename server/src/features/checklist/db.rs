@@ -0,0 +1,155 @@
+use crate::app::Connection;
+
+use super::ChecklistItem;
+
+pub struct InsertChecklistItemArgs<'a> {
+    pub id: &'a str,
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+    pub text: &'a str,
+}
+/// タスクのチェックリストの最後に項目を追加する。
+/// 追加した項目のIDを返す。タスクが存在しない場合はNoneを返す
+pub async fn insert_checklist_item<'a>(
+    db: &mut Connection,
+    args: InsertChecklistItemArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO checklist_items(id, task_id, user_id, text, sort_order)
+        SELECT
+            $1,
+            t.id,
+            t.user_id,
+            $2,
+            COALESCE((SELECT MAX(c.sort_order) + 1 FROM checklist_items c WHERE c.task_id = t.id), 0)
+        FROM tasks t
+        WHERE t.id = $3 AND t.user_id = $4
+        RETURNING id;
+        "#,
+        args.id,
+        args.text,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+pub struct FindChecklistItemArgs<'a> {
+    pub item_id: &'a str,
+    pub user_id: &'a str,
+}
+/// 項目が存在しない場合はNoneを返す
+pub async fn find_checklist_item<'a>(
+    db: &mut Connection,
+    args: FindChecklistItemArgs<'a>,
+) -> anyhow::Result<Option<ChecklistItem>> {
+    let item = sqlx::query_as!(
+        ChecklistItem,
+        r#"
+        SELECT id, task_id, text, checked as "checked: bool", sort_order, created_at, updated_at
+        FROM checklist_items
+        WHERE id = $1 AND user_id = $2;
+        "#,
+        args.item_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(item)
+}
+
+pub struct FindChecklistItemsArgs<'a> {
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+}
+/// タスクのチェックリストの項目を並び順で返す
+pub async fn find_checklist_items<'a>(
+    db: &mut Connection,
+    args: FindChecklistItemsArgs<'a>,
+) -> anyhow::Result<Vec<ChecklistItem>> {
+    let items = sqlx::query_as!(
+        ChecklistItem,
+        r#"
+        SELECT id, task_id, text, checked as "checked: bool", sort_order, created_at, updated_at
+        FROM checklist_items
+        WHERE task_id = $1 AND user_id = $2
+        ORDER BY sort_order, id;
+        "#,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    Ok(items)
+}
+
+pub struct UpdateChecklistItemArgs<'a> {
+    pub item_id: &'a str,
+    pub text: &'a str,
+    pub checked: bool,
+    pub user_id: &'a str,
+}
+/// 更新した項目のIDを返す。項目が存在しない場合はNoneを返す
+pub async fn update_checklist_item<'a>(
+    db: &mut Connection,
+    args: UpdateChecklistItemArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        "UPDATE checklist_items SET text = $1, checked = $2 WHERE id = $3 AND user_id = $4 RETURNING id;",
+        args.text,
+        args.checked,
+        args.item_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+pub struct UpdateChecklistItemSortOrderArgs<'a> {
+    pub item_id: &'a str,
+    pub sort_order: i64,
+    pub user_id: &'a str,
+}
+pub async fn update_checklist_item_sort_order<'a>(
+    db: &mut Connection,
+    args: UpdateChecklistItemSortOrderArgs<'a>,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        "UPDATE checklist_items SET sort_order = $1 WHERE id = $2 AND user_id = $3 RETURNING id;",
+        args.sort_order,
+        args.item_id,
+        args.user_id,
+    )
+    .fetch_one(&mut *db)
+    .await?;
+
+    Ok(())
+}
+
+pub struct DeleteChecklistItemArgs<'a> {
+    pub item_id: &'a str,
+    pub user_id: &'a str,
+}
+/// 削除した項目のIDを返す。項目が存在しない場合はNoneを返す
+pub async fn delete_checklist_item<'a>(
+    db: &mut Connection,
+    args: DeleteChecklistItemArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        "DELETE FROM checklist_items WHERE id = $1 AND user_id = $2 RETURNING id;",
+        args.item_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
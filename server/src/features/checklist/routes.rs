@@ -0,0 +1,93 @@
+use axum::{
+    middleware,
+    routing::{get, post, put},
+    Router,
+};
+use axum_login::login_required;
+
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope, task::routes::TaskPaths},
+};
+
+pub mod create_checklist_item;
+pub mod delete_checklist_item;
+pub mod get_checklist;
+pub mod promote_checklist_item;
+pub mod reorder_checklist;
+pub mod update_checklist_item;
+
+pub const TAG: &str = "checklist";
+
+pub struct ChecklistPaths;
+impl ChecklistPaths {
+    pub fn task_checklist_base() -> String {
+        "/checklist".into()
+    }
+
+    pub fn task_checklist() -> String {
+        TaskPaths::task() + &Self::task_checklist_base()
+    }
+
+    pub fn task_checklist_open_api() -> String {
+        TaskPaths::task_open_api() + &Self::task_checklist_base()
+    }
+
+    pub fn reorder_checklist_base() -> String {
+        "/reorder".into()
+    }
+
+    pub fn reorder_checklist() -> String {
+        Self::task_checklist() + &Self::reorder_checklist_base()
+    }
+
+    pub fn reorder_checklist_open_api() -> String {
+        Self::task_checklist_open_api() + &Self::reorder_checklist_base()
+    }
+
+    pub fn checklist_items() -> String {
+        "/checklist-items".into()
+    }
+
+    pub fn checklist_item() -> String {
+        Self::checklist_items() + "/:id"
+    }
+
+    pub fn checklist_item_open_api() -> String {
+        Self::checklist_items() + "/{id}"
+    }
+
+    pub fn promote_checklist_item_base() -> String {
+        "/promote".into()
+    }
+
+    pub fn promote_checklist_item() -> String {
+        Self::checklist_item() + &Self::promote_checklist_item_base()
+    }
+
+    pub fn promote_checklist_item_open_api() -> String {
+        Self::checklist_item_open_api() + &Self::promote_checklist_item_base()
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            &ChecklistPaths::task_checklist(),
+            get(get_checklist::handler).post(create_checklist_item::handler),
+        )
+        .route(
+            &ChecklistPaths::reorder_checklist(),
+            put(reorder_checklist::handler),
+        )
+        .route(
+            &ChecklistPaths::checklist_item(),
+            put(update_checklist_item::handler).delete(delete_checklist_item::handler),
+        )
+        .route(
+            &ChecklistPaths::promote_checklist_item(),
+            post(promote_checklist_item::handler),
+        )
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
+        .route_layer(login_required!(Auth))
+}
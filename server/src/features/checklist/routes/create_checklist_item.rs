@@ -0,0 +1,163 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        auth::Auth,
+        checklist::{
+            db::{
+                find_checklist_item, insert_checklist_item, FindChecklistItemArgs,
+                InsertChecklistItemArgs,
+            },
+            CreateChecklistItem,
+        },
+    },
+    validation::WithValidation,
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::ChecklistPaths::task_checklist_open_api(),
+    request_body = CreateChecklistItem,
+    responses(
+        (status = 201, body = ChecklistItem),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<CreateChecklistItem>>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let item_id = uuid::Uuid::new_v4().to_string();
+    if insert_checklist_item(
+        &mut tx,
+        InsertChecklistItemArgs {
+            id: &item_id,
+            task_id: &id,
+            user_id: &user.id,
+            text: &payload.text,
+        },
+    )
+    .await?
+    .is_none()
+    {
+        return Err(AppError::with_code(ErrorCode::TaskNotFound));
+    }
+
+    let item = find_checklist_item(
+        &mut tx,
+        FindChecklistItemArgs {
+            item_id: &item_id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or_else(|| anyhow!("checklist item not found"))?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(item)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            checklist::{routes::ChecklistPaths, ChecklistItem, CreateChecklistItem},
+            task::{routes::TaskPaths, test::task_factory, Task},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn チェックリストに項目を追加できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .post(&ChecklistPaths::one_task_checklist(&task.id))
+            .json(&CreateChecklistItem {
+                text: "item".into(),
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+        let item: ChecklistItem = res.json();
+        assert_eq!(item.task_id, task.id);
+        assert_eq!(item.text, "item");
+        assert!(!item.checked);
+
+        let task: Task = test
+            .server()
+            .get(&TaskPaths::one_task(&task.id))
+            .await
+            .json();
+        assert_eq!(task.checklist_count, 1);
+        assert_eq!(task.checked_checklist_count, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 空の項目は追加できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .post(&ChecklistPaths::one_task_checklist(&task.id))
+            .json(&CreateChecklistItem { text: "".into() })
+            .await;
+        assert!(res.status_code().is_client_error());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクには項目を追加できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .post(&ChecklistPaths::one_task_checklist(&other_user_task.id))
+            .json(&CreateChecklistItem {
+                text: "item".into(),
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        checklist::{
+            db::{delete_checklist_item, DeleteChecklistItemArgs},
+            DeleteChecklistItemResponse,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    delete,
+    tag = super::TAG,
+    path = super::ChecklistPaths::checklist_item_open_api(),
+    responses(
+        (status = 200, body = DeleteChecklistItemResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<DeleteChecklistItemResponse>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(item_id) = delete_checklist_item(
+        &mut tx,
+        DeleteChecklistItemArgs {
+            item_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    tx.commit().await?;
+
+    Ok(Json(DeleteChecklistItemResponse { item_id }))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            checklist::{routes::ChecklistPaths, test::checklist_factory, ChecklistItem},
+            task::test::task_factory,
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 項目を削除できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let item = checklist_factory::create(&db, &user.id, &task.id, "item").await?;
+
+        test.server()
+            .delete(&ChecklistPaths::one_checklist_item(&item.id))
+            .await
+            .assert_status_ok();
+
+        let items: Vec<ChecklistItem> = test
+            .server()
+            .get(&ChecklistPaths::one_task_checklist(&task.id))
+            .await
+            .json();
+        assert!(items.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人の項目は削除できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+        let item =
+            checklist_factory::create(&db, &other_user.id, &other_user_task.id, "item").await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .delete(&ChecklistPaths::one_checklist_item(&item.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        checklist::{
+            db::{find_checklist_items, FindChecklistItemsArgs},
+            ChecklistItem,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::ChecklistPaths::task_checklist_open_api(),
+    responses(
+        (status = 200, body = [ChecklistItem]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<Vec<ChecklistItem>>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let items = find_checklist_items(
+        &mut tx,
+        FindChecklistItemsArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(items))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            checklist::{routes::ChecklistPaths, test::checklist_factory, ChecklistItem},
+            task::test::task_factory,
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn チェックリストを追加した順に取得できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let other_task = task_factory::create_with_user(&db, &user.id).await?;
+        for text in ["1", "2", "3"] {
+            checklist_factory::create(&db, &user.id, &task.id, text).await?;
+        }
+        checklist_factory::create(&db, &user.id, &other_task.id, "other").await?;
+
+        let items: Vec<ChecklistItem> = test
+            .server()
+            .get(&ChecklistPaths::one_task_checklist(&task.id))
+            .await
+            .json();
+        let texts: Vec<_> = items.iter().map(|i| i.text.as_str()).collect();
+        assert_eq!(texts, vec!["1", "2", "3"]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクのチェックリストは取得できない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+        checklist_factory::create(&db, &other_user.id, &other_user_task.id, "item").await?;
+
+        test.login(None).await?;
+        let items: Vec<ChecklistItem> = test
+            .server()
+            .get(&ChecklistPaths::one_task_checklist(&other_user_task.id))
+            .await
+            .json();
+        assert!(items.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        checklist::usecases::promote_checklist_item::{
+            self, PromoteChecklistItemArgs, PromoteChecklistItemError,
+        },
+        task::db::{find_task, FindTaskArgs},
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::ChecklistPaths::promote_checklist_item_open_api(),
+    responses(
+        (status = 201, body = Task),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let task_id = uuid::Uuid::new_v4().to_string();
+    let result = promote_checklist_item::action(
+        &mut tx,
+        PromoteChecklistItemArgs {
+            item_id: &id,
+            new_task_id: &task_id,
+            user_id: &user.id,
+        },
+    )
+    .await;
+    match result {
+        Ok(()) => {}
+        Err(PromoteChecklistItemError::ItemNotFound) => {
+            return Err(AppError::new(StatusCode::NOT_FOUND, None));
+        }
+        Err(PromoteChecklistItemError::Unknown(e)) => return Err(e.into()),
+    }
+
+    let task = find_task(
+        &mut tx,
+        FindTaskArgs {
+            task_id: &task_id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(task)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            checklist::{
+                routes::ChecklistPaths, test::checklist_factory,
+                usecases::promote_checklist_item::PROMOTED_NODE_OFFSET_Y, ChecklistItem,
+                UpdateChecklistItem,
+            },
+            task::{routes::TaskPaths, test::task_factory, Task, TaskStatus},
+            task_node::{routes::TaskNodePaths, test::task_node_factory, TaskNode, TaskNodeInfo},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 項目をサブタスクにできる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = Task {
+            user_id: user.id.clone(),
+            ..Default::default()
+        };
+        let main = task_node_factory::create(
+            &db,
+            TaskNode {
+                task: task.clone(),
+                node_info: TaskNodeInfo {
+                    task_id: task.id.clone(),
+                    user_id: user.id.clone(),
+                    x: 10.0,
+                    y: 20.0,
+                },
+            },
+        )
+        .await?;
+        let item = checklist_factory::create(&db, &user.id, &main.task.id, "item").await?;
+
+        let res = test
+            .server()
+            .post(&ChecklistPaths::one_promote_checklist_item(&item.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+        let promoted: Task = res.json();
+        assert_eq!(promoted.title, "item");
+        assert_eq!(promoted.status, TaskStatus::Todo);
+
+        let main_task: Task = test
+            .server()
+            .get(&TaskPaths::one_task(&main.task.id))
+            .await
+            .json();
+        assert_eq!(main_task.sub_task_ids, vec![promoted.id.clone()]);
+        assert_eq!(main_task.checklist_count, 0);
+
+        let nodes: Vec<TaskNode> = test.server().get(&TaskNodePaths::task_nodes()).await.json();
+        let node = nodes
+            .iter()
+            .find(|n| n.task.id == promoted.id)
+            .expect("ノードが作成されていない");
+        assert_eq!(node.node_info.x, 10.0);
+        assert_eq!(node.node_info.y, 20.0 + PROMOTED_NODE_OFFSET_Y);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 完了したメインタスクに未完了の項目をサブタスクにすると未完了になる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create(
+            &db,
+            Task {
+                status: TaskStatus::Done,
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let todo = checklist_factory::create(&db, &user.id, &main.id, "todo").await?;
+        let checked = checklist_factory::create(&db, &user.id, &main.id, "checked").await?;
+        test.server()
+            .put(&ChecklistPaths::one_checklist_item(&checked.id))
+            .json(&UpdateChecklistItem {
+                text: checked.text.clone(),
+                checked: true,
+            })
+            .await
+            .assert_status_ok();
+
+        // チェック済みの項目は完了状態のタスクになる
+        let promoted: Task = test
+            .server()
+            .post(&ChecklistPaths::one_promote_checklist_item(&checked.id))
+            .await
+            .json();
+        assert_eq!(promoted.status, TaskStatus::Done);
+        let main_task: Task = test
+            .server()
+            .get(&TaskPaths::one_task(&main.id))
+            .await
+            .json();
+        assert_eq!(main_task.status, TaskStatus::Done);
+
+        let res = test
+            .server()
+            .post(&ChecklistPaths::one_promote_checklist_item(&todo.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+        let main_task: Task = test
+            .server()
+            .get(&TaskPaths::one_task(&main.id))
+            .await
+            .json();
+        assert_eq!(main_task.status, TaskStatus::Todo);
+
+        let items: Vec<ChecklistItem> = test
+            .server()
+            .get(&ChecklistPaths::one_task_checklist(&main.id))
+            .await
+            .json();
+        assert!(items.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人の項目はサブタスクにできない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+        let item =
+            checklist_factory::create(&db, &other_user.id, &other_user_task.id, "item").await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .post(&ChecklistPaths::one_promote_checklist_item(&item.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        auth::Auth,
+        checklist::{
+            db::{
+                find_checklist_items, update_checklist_item_sort_order, FindChecklistItemsArgs,
+                UpdateChecklistItemSortOrderArgs,
+            },
+            ChecklistItem, ReorderChecklistItems,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    put,
+    tag = super::TAG,
+    path = super::ChecklistPaths::reorder_checklist_open_api(),
+    request_body = ReorderChecklistItems,
+    responses(
+        (status = 200, body = [ChecklistItem]),
+        (status = 400, response = ProblemDetails),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+    Json(payload): Json<ReorderChecklistItems>,
+) -> AppResult<Json<Vec<ChecklistItem>>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let items = find_checklist_items(
+        &mut tx,
+        FindChecklistItemsArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    // 一部の項目だけを並び替えると順番が重複してしまうので、すべての項目を指定させる
+    let mut current_ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+    let mut new_ids: Vec<&str> = payload.item_ids.iter().map(|id| id.as_str()).collect();
+    current_ids.sort();
+    new_ids.sort();
+    if current_ids != new_ids {
+        return Err(AppError::with_code(ErrorCode::ChecklistItemsMismatch));
+    }
+
+    for (sort_order, item_id) in payload.item_ids.iter().enumerate() {
+        update_checklist_item_sort_order(
+            &mut tx,
+            UpdateChecklistItemSortOrderArgs {
+                item_id,
+                sort_order: sort_order as i64,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+    }
+
+    let items = find_checklist_items(
+        &mut tx,
+        FindChecklistItemsArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(items))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            checklist::{
+                routes::ChecklistPaths, test::checklist_factory, ChecklistItem,
+                ReorderChecklistItems,
+            },
+            task::test::task_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn チェックリストを並び替えられる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let item1 = checklist_factory::create(&db, &user.id, &task.id, "1").await?;
+        let item2 = checklist_factory::create(&db, &user.id, &task.id, "2").await?;
+        let item3 = checklist_factory::create(&db, &user.id, &task.id, "3").await?;
+
+        let items: Vec<ChecklistItem> = test
+            .server()
+            .put(&ChecklistPaths::one_reorder_checklist(&task.id))
+            .json(&ReorderChecklistItems {
+                item_ids: vec![item3.id, item1.id, item2.id],
+            })
+            .await
+            .json();
+        let texts: Vec<_> = items.iter().map(|i| i.text.as_str()).collect();
+        assert_eq!(texts, vec!["3", "1", "2"]);
+
+        // 並び替えたあとに追加した項目は最後になる
+        checklist_factory::create(&db, &user.id, &task.id, "4").await?;
+        let items: Vec<ChecklistItem> = test
+            .server()
+            .get(&ChecklistPaths::one_task_checklist(&task.id))
+            .await
+            .json();
+        let texts: Vec<_> = items.iter().map(|i| i.text.as_str()).collect();
+        assert_eq!(texts, vec!["3", "1", "2", "4"]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 一部の項目だけでは並び替えられない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let item1 = checklist_factory::create(&db, &user.id, &task.id, "1").await?;
+        checklist_factory::create(&db, &user.id, &task.id, "2").await?;
+
+        let res = test
+            .server()
+            .put(&ChecklistPaths::one_reorder_checklist(&task.id))
+            .json(&ReorderChecklistItems {
+                item_ids: vec![item1.id],
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        checklist::{
+            db::{
+                find_checklist_item, update_checklist_item, FindChecklistItemArgs,
+                UpdateChecklistItemArgs,
+            },
+            ChecklistItem, UpdateChecklistItem,
+        },
+    },
+    validation::WithValidation,
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    put,
+    tag = super::TAG,
+    path = super::ChecklistPaths::checklist_item_open_api(),
+    request_body = UpdateChecklistItem,
+    responses(
+        (status = 200, body = ChecklistItem),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<UpdateChecklistItem>>,
+) -> AppResult<Json<ChecklistItem>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    if update_checklist_item(
+        &mut tx,
+        UpdateChecklistItemArgs {
+            item_id: &id,
+            text: &payload.text,
+            checked: payload.checked,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .is_none()
+    {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    }
+
+    let item = find_checklist_item(
+        &mut tx,
+        FindChecklistItemArgs {
+            item_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or_else(|| anyhow!("checklist item not found"))?;
+
+    tx.commit().await?;
+
+    Ok(Json(item))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            checklist::{
+                routes::ChecklistPaths, test::checklist_factory, ChecklistItem, UpdateChecklistItem,
+            },
+            task::{routes::TaskPaths, test::task_factory, Task},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 項目にチェックを付けるとタスクの進捗に反映される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let item = checklist_factory::create(&db, &user.id, &task.id, "item").await?;
+        checklist_factory::create(&db, &user.id, &task.id, "other").await?;
+
+        let updated: ChecklistItem = test
+            .server()
+            .put(&ChecklistPaths::one_checklist_item(&item.id))
+            .json(&UpdateChecklistItem {
+                text: "updated".into(),
+                checked: true,
+            })
+            .await
+            .json();
+        assert_eq!(updated.text, "updated");
+        assert!(updated.checked);
+
+        let task: Task = test
+            .server()
+            .get(&TaskPaths::one_task(&task.id))
+            .await
+            .json();
+        assert_eq!(task.checklist_count, 2);
+        assert_eq!(task.checked_checklist_count, 1);
+
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+        assert_eq!(tasks[0].checklist_count, 2);
+        assert_eq!(tasks[0].checked_checklist_count, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人の項目は更新できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+        let item =
+            checklist_factory::create(&db, &other_user.id, &other_user_task.id, "item").await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .put(&ChecklistPaths::one_checklist_item(&item.id))
+            .json(&UpdateChecklistItem {
+                text: "updated".into(),
+                checked: true,
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+pub mod checklist_factory {
+    use crate::{
+        app::{AppResult, Db},
+        features::checklist::{
+            db::{
+                find_checklist_item, insert_checklist_item, FindChecklistItemArgs,
+                InsertChecklistItemArgs,
+            },
+            ChecklistItem,
+        },
+    };
+
+    /// タスクのチェックリストの最後に項目を追加する
+    pub async fn create(
+        db: &Db,
+        user_id: &str,
+        task_id: &str,
+        text: &str,
+    ) -> AppResult<ChecklistItem> {
+        let mut conn = db.acquire().await?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        insert_checklist_item(
+            &mut conn,
+            InsertChecklistItemArgs {
+                id: &id,
+                task_id,
+                user_id,
+                text,
+            },
+        )
+        .await?
+        .ok_or(anyhow::anyhow!("task not found"))?;
+
+        let item = find_checklist_item(
+            &mut conn,
+            FindChecklistItemArgs {
+                item_id: &id,
+                user_id,
+            },
+        )
+        .await?
+        .ok_or(anyhow::anyhow!("checklist item not found"))?;
+
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+pub mod routes {
+    use crate::features::{checklist, task::routes::TaskPaths};
+
+    impl checklist::routes::ChecklistPaths {
+        pub fn one_task_checklist(task_id: &str) -> String {
+            TaskPaths::one_task(task_id) + &Self::task_checklist_base()
+        }
+
+        pub fn one_reorder_checklist(task_id: &str) -> String {
+            Self::one_task_checklist(task_id) + &Self::reorder_checklist_base()
+        }
+
+        pub fn one_checklist_item(id: &str) -> String {
+            Self::checklist_items() + "/" + id
+        }
+
+        pub fn one_promote_checklist_item(id: &str) -> String {
+            Self::one_checklist_item(id) + &Self::promote_checklist_item_base()
+        }
+    }
+}
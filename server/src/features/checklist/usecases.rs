@@ -0,0 +1 @@
+pub mod promote_checklist_item;
@@ -0,0 +1,118 @@
+use crate::{
+    app::Connection,
+    features::{
+        checklist::db::{
+            delete_checklist_item, find_checklist_item, DeleteChecklistItemArgs,
+            FindChecklistItemArgs,
+        },
+        sub_task::{
+            db::{insert_sub_task_connection, InsertSubTaskConnectionArgs, TaskAndUser},
+            usecases::update_task_and_ancestors_status,
+        },
+        task::{
+            db::{insert_task, InsertTaskArgs},
+            TaskStatus,
+        },
+        task_node::db::{copy_task_node_info, CopyTaskNodeInfoArgs},
+    },
+};
+
+/// サブタスクにしたタスクのノードを、メインタスクのノードから下にずらす量
+pub const PROMOTED_NODE_OFFSET_Y: f64 = 120.0;
+
+pub struct PromoteChecklistItemArgs<'a> {
+    pub item_id: &'a str,
+    pub new_task_id: &'a str,
+    pub user_id: &'a str,
+}
+
+pub enum PromoteChecklistItemError {
+    ItemNotFound,
+    Unknown(anyhow::Error),
+}
+
+/// チェックリストの項目を、項目があったタスクのサブタスクにする。
+/// チェック済みの項目は完了状態のタスクになり、サブタスクにした項目はチェックリストから削除される。
+pub async fn action<'a>(
+    db: &mut Connection,
+    args: PromoteChecklistItemArgs<'a>,
+) -> Result<(), PromoteChecklistItemError> {
+    let Some(item) = find_checklist_item(
+        &mut *db,
+        FindChecklistItemArgs {
+            item_id: args.item_id,
+            user_id: args.user_id,
+        },
+    )
+    .await
+    .map_err(PromoteChecklistItemError::Unknown)?
+    else {
+        return Err(PromoteChecklistItemError::ItemNotFound);
+    };
+
+    let status = if item.checked {
+        TaskStatus::Done
+    } else {
+        TaskStatus::Todo
+    };
+    insert_task(
+        &mut *db,
+        InsertTaskArgs {
+            id: args.new_task_id,
+            title: &item.text,
+            description: "",
+            user_id: args.user_id,
+            status: &status,
+        },
+    )
+    .await
+    .map_err(PromoteChecklistItemError::Unknown)?;
+
+    // メインタスクにノードがない場合は、ノードも作らない
+    copy_task_node_info(
+        &mut *db,
+        CopyTaskNodeInfoArgs {
+            source_task_id: &item.task_id,
+            task_id: args.new_task_id,
+            user_id: args.user_id,
+            offset_x: 0.0,
+            offset_y: PROMOTED_NODE_OFFSET_Y,
+        },
+    )
+    .await
+    .map_err(PromoteChecklistItemError::Unknown)?;
+
+    insert_sub_task_connection(
+        &mut *db,
+        InsertSubTaskConnectionArgs {
+            main_task_id: &item.task_id,
+            sub_task_id: args.new_task_id,
+            user_id: args.user_id,
+        },
+    )
+    .await
+    .map_err(PromoteChecklistItemError::Unknown)?;
+
+    delete_checklist_item(
+        &mut *db,
+        DeleteChecklistItemArgs {
+            item_id: args.item_id,
+            user_id: args.user_id,
+        },
+    )
+    .await
+    .map_err(PromoteChecklistItemError::Unknown)?;
+
+    // 未完了のサブタスクが増えると、メインタスクが未完了になることがある
+    update_task_and_ancestors_status::action(
+        &mut *db,
+        TaskAndUser {
+            task_id: &item.task_id,
+            user_id: args.user_id,
+        },
+    )
+    .await
+    .map_err(PromoteChecklistItemError::Unknown)?;
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+pub mod db;
+pub mod routes;
+pub mod test;
+
+use crate::validation;
+use garde::Validate;
+pub use routes::router;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// タスクへのコメント。
+/// 返信は`parent_comment_id`で返信先のコメントを指すので、スレッドはクライアントで組み立てる。
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct TaskComment {
+    pub id: String,
+    pub task_id: String,
+    /// 返信の場合は返信先のコメントのID
+    pub parent_comment_id: Option<String>,
+    pub author_id: String,
+    pub author_name: String,
+    /// 削除されたコメントの場合は空になる
+    pub body: String,
+    pub created_at: String,
+    /// 編集されていない場合はNone
+    pub edited_at: Option<String>,
+    /// 削除されていない場合はNone
+    pub deleted_at: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct CreateTaskComment {
+    #[garde(custom(validation::length(1, 2000)))]
+    #[schema(min_length = 1, max_length = 2000)]
+    pub body: String,
+
+    /// 返信する場合は返信先のコメントのID
+    #[garde(skip)]
+    #[serde(default)]
+    pub parent_comment_id: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct UpdateTaskComment {
+    #[garde(custom(validation::length(1, 2000)))]
+    #[schema(min_length = 1, max_length = 2000)]
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct DeleteTaskCommentResponse {
+    pub comment_id: String,
+}
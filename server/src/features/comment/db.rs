@@ -0,0 +1,166 @@
+use crate::app::Connection;
+
+use super::TaskComment;
+
+pub struct InsertTaskCommentArgs<'a> {
+    pub id: &'a str,
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+    pub parent_comment_id: Option<&'a str>,
+    pub body: &'a str,
+}
+/// 追加したコメントのIDを返す。タスクが存在しない場合はNoneを返す
+pub async fn insert_task_comment<'a>(
+    db: &mut Connection,
+    args: InsertTaskCommentArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO task_comments(id, task_id, user_id, parent_comment_id, body)
+        SELECT $1, id, user_id, $2, $3
+        FROM tasks
+        WHERE id = $4 AND user_id = $5
+        RETURNING id;
+        "#,
+        args.id,
+        args.parent_comment_id,
+        args.body,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+pub struct FindTaskCommentArgs<'a> {
+    pub comment_id: &'a str,
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+}
+/// 削除されたコメントも返す。コメントが存在しない場合はNoneを返す
+pub async fn find_task_comment<'a>(
+    db: &mut Connection,
+    args: FindTaskCommentArgs<'a>,
+) -> anyhow::Result<Option<TaskComment>> {
+    let comment = sqlx::query_as!(
+        TaskComment,
+        r#"
+        SELECT
+            c.id,
+            c.task_id,
+            c.parent_comment_id,
+            c.user_id as author_id,
+            u.name as author_name,
+            CASE WHEN c.deleted_at IS NULL THEN c.body ELSE '' END as "body!: String",
+            c.created_at,
+            c.edited_at,
+            c.deleted_at
+        FROM task_comments c
+        JOIN users u ON (c.user_id = u.id)
+        WHERE c.id = $1 AND c.task_id = $2 AND c.user_id = $3;
+        "#,
+        args.comment_id,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(comment)
+}
+
+pub struct FindTaskCommentsArgs<'a> {
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+}
+/// タスクのコメントを古い順に返す。返信を残すために、削除されたコメントも返す
+pub async fn find_task_comments<'a>(
+    db: &mut Connection,
+    args: FindTaskCommentsArgs<'a>,
+) -> anyhow::Result<Vec<TaskComment>> {
+    let comments = sqlx::query_as!(
+        TaskComment,
+        r#"
+        SELECT
+            c.id,
+            c.task_id,
+            c.parent_comment_id,
+            c.user_id as author_id,
+            u.name as author_name,
+            CASE WHEN c.deleted_at IS NULL THEN c.body ELSE '' END as "body!: String",
+            c.created_at,
+            c.edited_at,
+            c.deleted_at
+        FROM task_comments c
+        JOIN users u ON (c.user_id = u.id)
+        WHERE c.task_id = $1 AND c.user_id = $2
+        ORDER BY c.created_at, c.rowid;
+        "#,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    Ok(comments)
+}
+
+pub struct UpdateTaskCommentArgs<'a> {
+    pub comment_id: &'a str,
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+    pub body: &'a str,
+}
+/// 更新したコメントのIDを返す。コメントが存在しないか削除されている場合はNoneを返す
+pub async fn update_task_comment<'a>(
+    db: &mut Connection,
+    args: UpdateTaskCommentArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE task_comments
+        SET
+            body = $1,
+            edited_at = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')
+        WHERE id = $2 AND task_id = $3 AND user_id = $4 AND deleted_at IS NULL
+        RETURNING id;
+        "#,
+        args.body,
+        args.comment_id,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+pub struct DeleteTaskCommentArgs<'a> {
+    pub comment_id: &'a str,
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+}
+/// コメントを削除済みにして、そのIDを返す。コメントが存在しないかすでに削除されている場合はNoneを返す
+pub async fn delete_task_comment<'a>(
+    db: &mut Connection,
+    args: DeleteTaskCommentArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE task_comments
+        SET deleted_at = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')
+        WHERE id = $1 AND task_id = $2 AND user_id = $3 AND deleted_at IS NULL
+        RETURNING id;
+        "#,
+        args.comment_id,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
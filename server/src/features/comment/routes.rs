@@ -0,0 +1,55 @@
+use axum::{
+    middleware,
+    routing::{get, put},
+    Router,
+};
+use axum_login::login_required;
+
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope, task::routes::TaskPaths},
+};
+
+pub mod create_task_comment;
+pub mod delete_task_comment;
+pub mod get_task_comments;
+pub mod update_task_comment;
+
+pub const TAG: &str = "comment";
+
+pub struct CommentPaths;
+impl CommentPaths {
+    pub fn task_comments_base() -> String {
+        "/comments".into()
+    }
+
+    pub fn task_comments() -> String {
+        TaskPaths::task() + &Self::task_comments_base()
+    }
+
+    pub fn task_comments_open_api() -> String {
+        TaskPaths::task_open_api() + &Self::task_comments_base()
+    }
+
+    pub fn task_comment() -> String {
+        Self::task_comments() + "/:comment_id"
+    }
+
+    pub fn task_comment_open_api() -> String {
+        Self::task_comments_open_api() + "/{comment_id}"
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            &CommentPaths::task_comments(),
+            get(get_task_comments::handler).post(create_task_comment::handler),
+        )
+        .route(
+            &CommentPaths::task_comment(),
+            put(update_task_comment::handler).delete(delete_task_comment::handler),
+        )
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
+        .route_layer(login_required!(Auth))
+}
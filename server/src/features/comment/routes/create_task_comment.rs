@@ -0,0 +1,191 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        auth::Auth,
+        comment::{
+            db::{
+                find_task_comment, insert_task_comment, FindTaskCommentArgs, InsertTaskCommentArgs,
+            },
+            CreateTaskComment,
+        },
+    },
+    validation::WithValidation,
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::CommentPaths::task_comments_open_api(),
+    request_body = CreateTaskComment,
+    responses(
+        (status = 201, body = TaskComment),
+        (status = 400, response = ProblemDetails),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<CreateTaskComment>>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    // 返信先は同じタスクの削除されていないコメントでなければならない
+    if let Some(parent_comment_id) = &payload.parent_comment_id {
+        let parent = find_task_comment(
+            &mut tx,
+            FindTaskCommentArgs {
+                comment_id: parent_comment_id,
+                task_id: &id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        if parent.is_none_or(|c| c.deleted_at.is_some()) {
+            return Err(AppError::with_code(ErrorCode::ReplyTargetNotFound));
+        }
+    }
+
+    let comment_id = uuid::Uuid::new_v4().to_string();
+    if insert_task_comment(
+        &mut tx,
+        InsertTaskCommentArgs {
+            id: &comment_id,
+            task_id: &id,
+            user_id: &user.id,
+            parent_comment_id: payload.parent_comment_id.as_deref(),
+            body: &payload.body,
+        },
+    )
+    .await?
+    .is_none()
+    {
+        return Err(AppError::with_code(ErrorCode::TaskNotFound));
+    }
+
+    let comment = find_task_comment(
+        &mut tx,
+        FindTaskCommentArgs {
+            comment_id: &comment_id,
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or_else(|| anyhow!("comment not found"))?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(comment)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            comment::{
+                routes::CommentPaths, test::comment_factory, CreateTaskComment, TaskComment,
+            },
+            task::{routes::TaskPaths, test::task_factory, Task},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn コメントを追加できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .post(&CommentPaths::one_task_comments(&task.id))
+            .json(&CreateTaskComment {
+                body: "comment".into(),
+                parent_comment_id: None,
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+        let comment: TaskComment = res.json();
+        assert_eq!(comment.body, "comment");
+        assert_eq!(comment.author_id, user.id);
+        assert_eq!(comment.author_name, user.name);
+        assert!(comment.edited_at.is_none());
+
+        let task: Task = test
+            .server()
+            .get(&TaskPaths::one_task(&task.id))
+            .await
+            .json();
+        assert_eq!(task.comment_count, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他のタスクのコメントには返信できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let other_task = task_factory::create_with_user(&db, &user.id).await?;
+        let other_comment =
+            comment_factory::create(&db, &user.id, &other_task.id, None, "comment").await?;
+
+        let res = test
+            .server()
+            .post(&CommentPaths::one_task_comments(&task.id))
+            .json(&CreateTaskComment {
+                body: "reply".into(),
+                parent_comment_id: Some(other_comment.id),
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクにはコメントできない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .post(&CommentPaths::one_task_comments(&other_user_task.id))
+            .json(&CreateTaskComment {
+                body: "comment".into(),
+                parent_comment_id: None,
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
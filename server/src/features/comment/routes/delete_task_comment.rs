@@ -0,0 +1,142 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        comment::{
+            db::{delete_task_comment, DeleteTaskCommentArgs},
+            DeleteTaskCommentResponse,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    delete,
+    tag = super::TAG,
+    path = super::CommentPaths::task_comment_open_api(),
+    responses(
+        (status = 200, body = DeleteTaskCommentResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,), ("comment_id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path((id, comment_id)): Path<(String, String)>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<DeleteTaskCommentResponse>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(comment_id) = delete_task_comment(
+        &mut tx,
+        DeleteTaskCommentArgs {
+            comment_id: &comment_id,
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    tx.commit().await?;
+
+    Ok(Json(DeleteTaskCommentResponse { comment_id }))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            comment::{routes::CommentPaths, test::comment_factory, TaskComment},
+            task::{routes::TaskPaths, test::task_factory, Task},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 削除したコメントは本文が消えて返信は残る(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let comment = comment_factory::create(&db, &user.id, &task.id, None, "comment").await?;
+        let reply =
+            comment_factory::create(&db, &user.id, &task.id, Some(&comment.id), "reply").await?;
+
+        test.server()
+            .delete(&CommentPaths::one_task_comment(&task.id, &comment.id))
+            .await
+            .assert_status_ok();
+
+        let comments: Vec<TaskComment> = test
+            .server()
+            .get(&CommentPaths::one_task_comments(&task.id))
+            .await
+            .json();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].id, comment.id);
+        assert_eq!(comments[0].body, "");
+        assert!(comments[0].deleted_at.is_some());
+        assert_eq!(comments[1].id, reply.id);
+        assert_eq!(comments[1].body, "reply");
+
+        // 削除したコメントは数えない
+        let task: Task = test
+            .server()
+            .get(&TaskPaths::one_task(&task.id))
+            .await
+            .json();
+        assert_eq!(task.comment_count, 1);
+
+        // 削除済みのコメントはもう一度削除できない
+        let res = test
+            .server()
+            .delete(&CommentPaths::one_task_comment(&task.id, &comment.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のコメントは削除できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+        let comment =
+            comment_factory::create(&db, &other_user.id, &other_user_task.id, None, "comment")
+                .await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .delete(&CommentPaths::one_task_comment(
+                &other_user_task.id,
+                &comment.id,
+            ))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        comment::{
+            db::{find_task_comments, FindTaskCommentsArgs},
+            TaskComment,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::CommentPaths::task_comments_open_api(),
+    responses(
+        (status = 200, body = [TaskComment]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<Vec<TaskComment>>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let comments = find_task_comments(
+        &mut tx,
+        FindTaskCommentsArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(comments))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            comment::{routes::CommentPaths, test::comment_factory, TaskComment},
+            task::test::task_factory,
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 返信も含めてコメントを古い順に取得できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let other_task = task_factory::create_with_user(&db, &user.id).await?;
+        let first = comment_factory::create(&db, &user.id, &task.id, None, "1").await?;
+        comment_factory::create(&db, &user.id, &task.id, Some(&first.id), "2").await?;
+        comment_factory::create(&db, &user.id, &other_task.id, None, "other").await?;
+
+        let comments: Vec<TaskComment> = test
+            .server()
+            .get(&CommentPaths::one_task_comments(&task.id))
+            .await
+            .json();
+        let comments: Vec<_> = comments
+            .iter()
+            .map(|c| (c.body.as_str(), c.parent_comment_id.as_deref()))
+            .collect();
+        assert_eq!(comments, vec![("1", None), ("2", Some(first.id.as_str()))]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクのコメントは取得できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+        comment_factory::create(&db, &other_user.id, &other_user_task.id, None, "comment").await?;
+
+        test.login(None).await?;
+        let comments: Vec<TaskComment> = test
+            .server()
+            .get(&CommentPaths::one_task_comments(&other_user_task.id))
+            .await
+            .json();
+        assert!(comments.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        comment::{
+            db::{
+                find_task_comment, update_task_comment, FindTaskCommentArgs, UpdateTaskCommentArgs,
+            },
+            TaskComment, UpdateTaskComment,
+        },
+    },
+    validation::WithValidation,
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    put,
+    tag = super::TAG,
+    path = super::CommentPaths::task_comment_open_api(),
+    request_body = UpdateTaskComment,
+    responses(
+        (status = 200, body = TaskComment),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,), ("comment_id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path((id, comment_id)): Path<(String, String)>,
+    State(AppState { db, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<UpdateTaskComment>>,
+) -> AppResult<Json<TaskComment>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    if update_task_comment(
+        &mut tx,
+        UpdateTaskCommentArgs {
+            comment_id: &comment_id,
+            task_id: &id,
+            user_id: &user.id,
+            body: &payload.body,
+        },
+    )
+    .await?
+    .is_none()
+    {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    }
+
+    let comment = find_task_comment(
+        &mut tx,
+        FindTaskCommentArgs {
+            comment_id: &comment_id,
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or_else(|| anyhow!("comment not found"))?;
+
+    tx.commit().await?;
+
+    Ok(Json(comment))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            comment::{
+                routes::CommentPaths, test::comment_factory, TaskComment, UpdateTaskComment,
+            },
+            task::test::task_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn コメントを編集すると編集日時が記録される(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let comment = comment_factory::create(&db, &user.id, &task.id, None, "comment").await?;
+
+        let updated: TaskComment = test
+            .server()
+            .put(&CommentPaths::one_task_comment(&task.id, &comment.id))
+            .json(&UpdateTaskComment {
+                body: "updated".into(),
+            })
+            .await
+            .json();
+        assert_eq!(updated.body, "updated");
+        assert!(updated.edited_at.is_some());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 削除されたコメントは編集できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let comment = comment_factory::create(&db, &user.id, &task.id, None, "comment").await?;
+        test.server()
+            .delete(&CommentPaths::one_task_comment(&task.id, &comment.id))
+            .await
+            .assert_status_ok();
+
+        let res = test
+            .server()
+            .put(&CommentPaths::one_task_comment(&task.id, &comment.id))
+            .json(&UpdateTaskComment {
+                body: "updated".into(),
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
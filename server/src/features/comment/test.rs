@@ -0,0 +1,64 @@
+#[cfg(test)]
+pub mod comment_factory {
+    use crate::{
+        app::{AppResult, Db},
+        features::comment::{
+            db::{
+                find_task_comment, insert_task_comment, FindTaskCommentArgs, InsertTaskCommentArgs,
+            },
+            TaskComment,
+        },
+    };
+
+    pub async fn create(
+        db: &Db,
+        user_id: &str,
+        task_id: &str,
+        parent_comment_id: Option<&str>,
+        body: &str,
+    ) -> AppResult<TaskComment> {
+        let mut conn = db.acquire().await?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        insert_task_comment(
+            &mut conn,
+            InsertTaskCommentArgs {
+                id: &id,
+                task_id,
+                user_id,
+                parent_comment_id,
+                body,
+            },
+        )
+        .await?
+        .ok_or(anyhow::anyhow!("task not found"))?;
+
+        let comment = find_task_comment(
+            &mut conn,
+            FindTaskCommentArgs {
+                comment_id: &id,
+                task_id,
+                user_id,
+            },
+        )
+        .await?
+        .ok_or(anyhow::anyhow!("comment not found"))?;
+
+        Ok(comment)
+    }
+}
+
+#[cfg(test)]
+pub mod routes {
+    use crate::features::{comment, task::routes::TaskPaths};
+
+    impl comment::routes::CommentPaths {
+        pub fn one_task_comments(task_id: &str) -> String {
+            TaskPaths::one_task(task_id) + &Self::task_comments_base()
+        }
+
+        pub fn one_task_comment(task_id: &str, comment_id: &str) -> String {
+            Self::one_task_comments(task_id) + "/" + comment_id
+        }
+    }
+}
@@ -0,0 +1,121 @@
+pub mod db;
+pub mod middleware;
+pub mod routes;
+pub mod test;
+
+use crate::{i18n::Message, validation};
+use garde::Validate;
+pub use middleware::require_token_scope;
+pub use routes::router;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use super::{
+    attachment, block_task, checklist, comment, recurrence, stats, sub_task, task, task_node,
+    template, time_tracking, user,
+};
+
+/// トークンのスコープで読み書きを制限できる機能。それぞれのルーターのタグをスコープに使う
+pub const SCOPE_FEATURES: &[&str] = &[
+    task::routes::TAG,
+    sub_task::routes::TAG,
+    block_task::routes::TAG,
+    task_node::routes::TAG,
+    stats::routes::TAG,
+    template::routes::TAG,
+    recurrence::routes::TAG,
+    checklist::routes::TAG,
+    comment::routes::TAG,
+    attachment::routes::TAG,
+    time_tracking::routes::TAG,
+    user::routes::TAG,
+];
+
+/// 作成したときに返すトークンの先頭につける文字列
+const TOKEN_PREFIX: &str = "evodo_";
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct PersonalAccessToken {
+    pub id: String,
+    pub name: String,
+    /// `<機能>:read`か`<機能>:write`。writeはreadも含む
+    pub scopes: Vec<String>,
+    pub expires_at: String,
+    pub last_used_at: Option<String>,
+    /// 取り消されていない場合はNone
+    pub revoked_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct CreatePersonalAccessToken {
+    #[garde(custom(validation::length(1, 100)))]
+    #[schema(min_length = 1, max_length = 100)]
+    pub name: String,
+
+    #[garde(custom(validation::length(1, usize::MAX)), custom(validate_scopes))]
+    #[schema(min_items = 1)]
+    pub scopes: Vec<String>,
+
+    /// 何日後に期限切れにするか
+    #[garde(custom(validation::range(1, 365)))]
+    #[schema(minimum = 1, maximum = 365)]
+    pub expires_in_days: i64,
+}
+
+fn validate_scopes(scopes: &[String], _: &()) -> garde::Result {
+    for scope in scopes {
+        if parse_scope(scope).is_none() {
+            return Err(validation::error(Message::new("validation.invalid_scope")));
+        }
+    }
+    Ok(())
+}
+
+/// 作成したトークンの情報。トークンそのものは作成したときにしか返さない
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct CreatedPersonalAccessToken {
+    pub token: String,
+    pub personal_access_token: PersonalAccessToken,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct RevokePersonalAccessTokenResponse {
+    pub personal_access_token_id: String,
+}
+
+/// スコープを機能と、書き込みができるかに分ける
+fn parse_scope(scope: &str) -> Option<(&str, bool)> {
+    let (feature, access) = scope.split_once(':')?;
+    if !SCOPE_FEATURES.contains(&feature) {
+        return None;
+    }
+    match access {
+        "read" => Some((feature, false)),
+        "write" => Some((feature, true)),
+        _ => None,
+    }
+}
+
+/// トークンのスコープで、機能を読み書きできるか
+pub fn scopes_allow(scopes: &[String], feature: &str, write: bool) -> bool {
+    scopes.iter().filter_map(|s| parse_scope(s)).any(|(f, w)| {
+        // 書き込みできるスコープは読み取りもできる
+        f == feature && (w || !write)
+    })
+}
+
+pub fn generate_token() -> String {
+    // UUID v4は安全な乱数で作られるので、二つ繋げて推測できない長さにする
+    format!(
+        "{}{}{}",
+        TOKEN_PREFIX,
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
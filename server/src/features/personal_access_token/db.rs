@@ -0,0 +1,158 @@
+use crate::{app::Connection, features::user::User};
+
+use super::PersonalAccessToken;
+
+struct RawPersonalAccessToken {
+    id: String,
+    name: String,
+    scopes: String,
+    expires_at: String,
+    last_used_at: Option<String>,
+    revoked_at: Option<String>,
+    created_at: String,
+}
+impl From<RawPersonalAccessToken> for PersonalAccessToken {
+    fn from(raw: RawPersonalAccessToken) -> Self {
+        PersonalAccessToken {
+            id: raw.id,
+            name: raw.name,
+            scopes: raw.scopes.split_whitespace().map(String::from).collect(),
+            expires_at: raw.expires_at,
+            last_used_at: raw.last_used_at,
+            revoked_at: raw.revoked_at,
+            created_at: raw.created_at,
+        }
+    }
+}
+
+pub struct InsertPersonalAccessTokenArgs<'a> {
+    pub id: &'a str,
+    pub user_id: &'a str,
+    pub name: &'a str,
+    pub token_hash: &'a str,
+    pub scopes: &'a [String],
+    pub expires_in_days: i64,
+}
+pub async fn insert_personal_access_token<'a>(
+    db: &mut Connection,
+    args: InsertPersonalAccessTokenArgs<'a>,
+) -> anyhow::Result<PersonalAccessToken> {
+    let scopes = args.scopes.join(" ");
+    let expires_modifier = format!("{:+} days", args.expires_in_days);
+    let raw = sqlx::query_as!(
+        RawPersonalAccessToken,
+        r#"
+        INSERT INTO personal_access_tokens(id, user_id, name, token_hash, scopes, expires_at)
+        VALUES($1, $2, $3, $4, $5, strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime', $6))
+        RETURNING id, name, scopes, expires_at as "expires_at!", last_used_at, revoked_at, created_at;
+        "#,
+        args.id,
+        args.user_id,
+        args.name,
+        args.token_hash,
+        scopes,
+        expires_modifier,
+    )
+    .fetch_one(&mut *db)
+    .await?;
+
+    Ok(raw.into())
+}
+
+/// ユーザーのトークンを新しい順に返す
+pub async fn find_personal_access_tokens(
+    db: &mut Connection,
+    user_id: &str,
+) -> anyhow::Result<Vec<PersonalAccessToken>> {
+    let raws = sqlx::query_as!(
+        RawPersonalAccessToken,
+        r#"
+        SELECT id, name, scopes, expires_at, last_used_at, revoked_at, created_at
+        FROM personal_access_tokens
+        WHERE user_id = $1
+        ORDER BY created_at DESC, rowid DESC;
+        "#,
+        user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    Ok(raws.into_iter().map(PersonalAccessToken::from).collect())
+}
+
+pub struct RevokePersonalAccessTokenArgs<'a> {
+    pub id: &'a str,
+    pub user_id: &'a str,
+}
+/// 取り消したトークンのIDを返す。トークンが存在しないか、すでに取り消されている場合はNoneを返す
+pub async fn revoke_personal_access_token<'a>(
+    db: &mut Connection,
+    args: RevokePersonalAccessTokenArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE personal_access_tokens
+        SET revoked_at = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')
+        WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+        RETURNING id;
+        "#,
+        args.id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+/// 使えるトークンの持ち主とスコープを返す。
+/// トークンが存在しないか、期限切れか取り消されている場合はNoneを返す。
+pub async fn find_token_user(
+    db: &mut Connection,
+    token_hash: &str,
+) -> anyhow::Result<Option<(User, Vec<String>)>> {
+    let result = sqlx::query!(
+        r#"
+        SELECT u.id, u.name, u.profile, u.locale, t.scopes
+        FROM personal_access_tokens t
+        JOIN users u ON t.user_id = u.id
+        WHERE
+            t.token_hash = $1
+            AND t.revoked_at IS NULL
+            AND t.expires_at > strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime');
+        "#,
+        token_hash,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| {
+        (
+            User {
+                id: r.id,
+                name: r.name,
+                profile: r.profile,
+                locale: r.locale,
+            },
+            r.scopes.split_whitespace().map(String::from).collect(),
+        )
+    }))
+}
+
+pub async fn update_token_last_used_at(
+    db: &mut Connection,
+    token_hash: &str,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE personal_access_tokens
+        SET last_used_at = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')
+        WHERE token_hash = $1;
+        "#,
+        token_hash,
+    )
+    .execute(&mut *db)
+    .await?;
+
+    Ok(())
+}
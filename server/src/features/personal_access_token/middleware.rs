@@ -0,0 +1,203 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use axum_login::AuthSession;
+use http::{header::AUTHORIZATION, Method};
+
+use crate::{
+    app::AppResult,
+    error::{AppError, ErrorCode},
+    features::auth::Auth,
+};
+
+use super::{
+    db::{find_token_user, update_token_last_used_at},
+    hash_token, scopes_allow,
+};
+
+/// トークンで認証したリクエストに入れる、トークンのスコープ
+#[derive(Debug, Clone)]
+pub struct TokenScopes(pub Vec<String>);
+
+/// `Authorization: Bearer <トークン>`で認証する。
+/// セッションにはログインせず、このリクエストの間だけトークンの持ち主がログインしていることにするので、
+/// `login_required!(Auth)`や`AuthSession<Auth>`はCookieで認証したときと同じように使える。
+pub async fn bearer_auth(mut req: Request, next: Next) -> AppResult<Response> {
+    let Some(token) = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    else {
+        return Ok(next.run(req).await);
+    };
+    let token_hash = hash_token(token.trim());
+
+    let Some(auth_session) = req.extensions_mut().get_mut::<AuthSession<Auth>>() else {
+        return Err(anyhow::anyhow!("AuthSession not found").into());
+    };
+
+    let mut conn = auth_session.backend.db.acquire().await?;
+    let Some((user, scopes)) = find_token_user(&mut conn, &token_hash).await? else {
+        return Err(AppError::unauthorized());
+    };
+    update_token_last_used_at(&mut conn, &token_hash).await?;
+    drop(conn);
+
+    auth_session.user = Some(user);
+    req.extensions_mut().insert(TokenScopes(scopes));
+
+    Ok(next.run(req).await)
+}
+
+/// トークンで認証したリクエストのときに、トークンのスコープで機能を使えるかを確認する。
+/// GETとHEADは`<機能>:read`、それ以外は`<機能>:write`が必要になる。
+/// Cookieで認証したリクエストは制限しない。
+pub async fn require_token_scope(
+    State(feature): State<&'static str>,
+    req: Request,
+    next: Next,
+) -> AppResult<Response> {
+    if let Some(TokenScopes(scopes)) = req.extensions().get::<TokenScopes>() {
+        let write = !matches!(*req.method(), Method::GET | Method::HEAD);
+        if !scopes_allow(scopes, feature, write) {
+            return Err(AppError::with_code(ErrorCode::InsufficientScope));
+        }
+    }
+
+    Ok(next.run(req).await)
+}
+
+/// トークンの管理などの、トークンで使わせたくない機能をトークンで使えないようにする
+pub async fn reject_token_auth(req: Request, next: Next) -> AppResult<Response> {
+    if req.extensions().get::<TokenScopes>().is_some() {
+        return Err(AppError::with_code(ErrorCode::TokenAuthNotAllowed));
+    }
+
+    Ok(next.run(req).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum_test::TestRequest;
+    use http::{header::AUTHORIZATION, HeaderValue, StatusCode};
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            comment::routes::CommentPaths,
+            personal_access_token::{
+                routes::PersonalAccessTokenPaths, test::personal_access_token_factory,
+            },
+            task::{routes::TaskPaths, test::task_factory, CreateTask, Task},
+        },
+    };
+
+    fn with_token(req: TestRequest, token: &str) -> AppResult<TestRequest> {
+        // Cookieでログインしていない状態で、トークンだけで認証する
+        Ok(req.clear_cookies().add_header(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token))?,
+        ))
+    }
+
+    #[sqlx::test]
+    async fn 読み取りのスコープのトークンでは読み取りしかできない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let (token, _) =
+            personal_access_token_factory::create(&db, &user.id, &["task:read"], 30).await?;
+
+        let tasks: Vec<Task> = with_token(test.server().get(&TaskPaths::tasks()), &token)?
+            .await
+            .json();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, task.id);
+
+        let res = with_token(test.server().post(&TaskPaths::tasks()), &token)?
+            .json(&CreateTask {
+                title: "title".into(),
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 書き込みのスコープのトークンでは読み書きができる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let (token, _) =
+            personal_access_token_factory::create(&db, &user.id, &["task:write"], 30).await?;
+
+        let res = with_token(test.server().post(&TaskPaths::tasks()), &token)?
+            .json(&CreateTask {
+                title: "title".into(),
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+
+        with_token(test.server().get(&TaskPaths::tasks()), &token)?
+            .await
+            .assert_status_ok();
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn スコープに含まれていない機能は使えない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let (token, _) =
+            personal_access_token_factory::create(&db, &user.id, &["task:write"], 30).await?;
+
+        let res = with_token(
+            test.server()
+                .get(&CommentPaths::one_task_comments(&task.id)),
+            &token,
+        )?
+        .await;
+        assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+        // トークンの管理はトークンではできない
+        let res = with_token(
+            test.server()
+                .get(&PersonalAccessTokenPaths::personal_access_tokens()),
+            &token,
+        )?
+        .await;
+        assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 期限切れや存在しないトークンでは認証できない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let (expired_token, _) =
+            personal_access_token_factory::create(&db, &user.id, &["task:read"], -1).await?;
+
+        for token in [expired_token.as_str(), "evodo_unknown"] {
+            let res = with_token(test.server().get(&TaskPaths::tasks()), token)?.await;
+            assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+        }
+
+        Ok(())
+    }
+}
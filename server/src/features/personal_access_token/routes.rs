@@ -0,0 +1,46 @@
+use axum::{
+    middleware,
+    routing::{delete, get},
+    Router,
+};
+use axum_login::login_required;
+
+use crate::{app::AppState, features::auth::Auth};
+
+use super::middleware::reject_token_auth;
+
+pub mod create_personal_access_token;
+pub mod get_personal_access_tokens;
+pub mod revoke_personal_access_token;
+
+pub const TAG: &str = "personal-access-token";
+
+pub struct PersonalAccessTokenPaths;
+impl PersonalAccessTokenPaths {
+    pub fn personal_access_tokens() -> String {
+        "/personal-access-tokens".into()
+    }
+
+    pub fn personal_access_token() -> String {
+        Self::personal_access_tokens() + "/:id"
+    }
+
+    pub fn personal_access_token_open_api() -> String {
+        Self::personal_access_tokens() + "/{id}"
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            &PersonalAccessTokenPaths::personal_access_tokens(),
+            get(get_personal_access_tokens::handler).post(create_personal_access_token::handler),
+        )
+        .route(
+            &PersonalAccessTokenPaths::personal_access_token(),
+            delete(revoke_personal_access_token::handler),
+        )
+        // トークンでトークンを作れると、スコープを広げられてしまう
+        .route_layer(middleware::from_fn(reject_token_auth))
+        .route_layer(login_required!(Auth))
+}
@@ -0,0 +1,146 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        personal_access_token::{
+            db::{insert_personal_access_token, InsertPersonalAccessTokenArgs},
+            generate_token, hash_token, CreatePersonalAccessToken, CreatedPersonalAccessToken,
+        },
+    },
+    validation::WithValidation,
+};
+
+#[tracing::instrument(err, skip(payload))]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::PersonalAccessTokenPaths::personal_access_tokens(),
+    request_body = CreatePersonalAccessToken,
+    responses(
+        (status = 201, body = CreatedPersonalAccessToken),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    State(AppState { db, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<CreatePersonalAccessToken>>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    // トークンそのものは保存しないので、ここでしか返せない
+    let token = generate_token();
+    let personal_access_token = insert_personal_access_token(
+        &mut tx,
+        InsertPersonalAccessTokenArgs {
+            id: &uuid::Uuid::new_v4().to_string(),
+            user_id: &user.id,
+            name: &payload.name,
+            token_hash: &hash_token(&token),
+            scopes: &payload.scopes,
+            expires_in_days: payload.expires_in_days,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedPersonalAccessToken {
+            token,
+            personal_access_token,
+        }),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header::AUTHORIZATION, HeaderValue, StatusCode};
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            personal_access_token::{
+                routes::PersonalAccessTokenPaths, CreatePersonalAccessToken,
+                CreatedPersonalAccessToken,
+            },
+            task::routes::TaskPaths,
+        },
+    };
+
+    #[sqlx::test]
+    async fn トークンを作成できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        let res = test
+            .server()
+            .post(&PersonalAccessTokenPaths::personal_access_tokens())
+            .json(&CreatePersonalAccessToken {
+                name: "script".into(),
+                scopes: vec!["task:read".into(), "comment:write".into()],
+                expires_in_days: 30,
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+
+        let created: CreatedPersonalAccessToken = res.json();
+        assert!(created.token.starts_with("evodo_"));
+        assert_eq!(created.personal_access_token.name, "script");
+        assert_eq!(
+            created.personal_access_token.scopes,
+            vec!["task:read", "comment:write"]
+        );
+        assert!(
+            created.personal_access_token.expires_at > created.personal_access_token.created_at
+        );
+
+        // 作成したトークンで認証できる
+        test.server()
+            .get(&TaskPaths::tasks())
+            .clear_cookies()
+            .add_header(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", created.token))?,
+            )
+            .await
+            .assert_status_ok();
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 存在しないスコープのトークンは作成できない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        for scope in ["task:delete", "unknown:read", "task"] {
+            let res = test
+                .server()
+                .post(&PersonalAccessTokenPaths::personal_access_tokens())
+                .json(&CreatePersonalAccessToken {
+                    name: "script".into(),
+                    scopes: vec![scope.into()],
+                    expires_in_days: 30,
+                })
+                .await;
+            assert_eq!(res.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+
+        Ok(())
+    }
+}
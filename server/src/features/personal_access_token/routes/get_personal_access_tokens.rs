@@ -0,0 +1,74 @@
+use axum::{extract::State, Json};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        personal_access_token::{db::find_personal_access_tokens, PersonalAccessToken},
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::PersonalAccessTokenPaths::personal_access_tokens(),
+    responses(
+        (status = 200, body = [PersonalAccessToken]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<Vec<PersonalAccessToken>>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let tokens = find_personal_access_tokens(&mut tx, &user.id).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(tokens))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            personal_access_token::{
+                routes::PersonalAccessTokenPaths, test::personal_access_token_factory,
+                PersonalAccessToken,
+            },
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 自分のトークンの一覧を取得できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        personal_access_token_factory::create(&db, &other_user.id, &["task:read"], 30).await?;
+        let (_, token) =
+            personal_access_token_factory::create(&db, &user.id, &["task:read"], 30).await?;
+
+        let tokens: Vec<PersonalAccessToken> = test
+            .server()
+            .get(&PersonalAccessTokenPaths::personal_access_tokens())
+            .await
+            .json();
+        let ids: Vec<_> = tokens.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec![token.id.as_str()]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        personal_access_token::{
+            db::{revoke_personal_access_token, RevokePersonalAccessTokenArgs},
+            RevokePersonalAccessTokenResponse,
+        },
+    },
+};
+
+/// トークンを取り消す。取り消したトークンも一覧には残る
+#[tracing::instrument(err)]
+#[utoipa::path(
+    delete,
+    tag = super::TAG,
+    path = super::PersonalAccessTokenPaths::personal_access_token_open_api(),
+    responses(
+        (status = 200, body = RevokePersonalAccessTokenResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<RevokePersonalAccessTokenResponse>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(personal_access_token_id) = revoke_personal_access_token(
+        &mut tx,
+        RevokePersonalAccessTokenArgs {
+            id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    tx.commit().await?;
+
+    Ok(Json(RevokePersonalAccessTokenResponse {
+        personal_access_token_id,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header::AUTHORIZATION, HeaderValue, StatusCode};
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            personal_access_token::{
+                routes::PersonalAccessTokenPaths, test::personal_access_token_factory,
+                PersonalAccessToken,
+            },
+            task::routes::TaskPaths,
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 取り消したトークンでは認証できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let (token, created) =
+            personal_access_token_factory::create(&db, &user.id, &["task:read"], 30).await?;
+
+        test.server()
+            .delete(&PersonalAccessTokenPaths::one_personal_access_token(
+                &created.id,
+            ))
+            .await
+            .assert_status_ok();
+
+        let tokens: Vec<PersonalAccessToken> = test
+            .server()
+            .get(&PersonalAccessTokenPaths::personal_access_tokens())
+            .await
+            .json();
+        assert!(tokens[0].revoked_at.is_some());
+
+        let res = test
+            .server()
+            .get(&TaskPaths::tasks())
+            .clear_cookies()
+            .add_header(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", token))?,
+            )
+            .await;
+        assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のトークンは取り消せない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let (_, created) =
+            personal_access_token_factory::create(&db, &other_user.id, &["task:read"], 30).await?;
+
+        let res = test
+            .server()
+            .delete(&PersonalAccessTokenPaths::one_personal_access_token(
+                &created.id,
+            ))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
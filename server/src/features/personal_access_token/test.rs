@@ -0,0 +1,49 @@
+#[cfg(test)]
+pub mod personal_access_token_factory {
+    use crate::{
+        app::{AppResult, Db},
+        features::personal_access_token::{
+            db::{insert_personal_access_token, InsertPersonalAccessTokenArgs},
+            generate_token, hash_token, PersonalAccessToken,
+        },
+    };
+
+    /// トークンと、保存したトークンの情報を返す。
+    /// 期限切れのトークンを作れるように、有効期限の日数は負の値も指定できる。
+    pub async fn create(
+        db: &Db,
+        user_id: &str,
+        scopes: &[&str],
+        expires_in_days: i64,
+    ) -> AppResult<(String, PersonalAccessToken)> {
+        let mut conn = db.acquire().await?;
+
+        let token = generate_token();
+        let scopes: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+        let personal_access_token = insert_personal_access_token(
+            &mut conn,
+            InsertPersonalAccessTokenArgs {
+                id: &uuid::Uuid::new_v4().to_string(),
+                user_id,
+                name: "token",
+                token_hash: &hash_token(&token),
+                scopes: &scopes,
+                expires_in_days,
+            },
+        )
+        .await?;
+
+        Ok((token, personal_access_token))
+    }
+}
+
+#[cfg(test)]
+pub mod routes {
+    use crate::features::personal_access_token;
+
+    impl personal_access_token::routes::PersonalAccessTokenPaths {
+        pub fn one_personal_access_token(id: &str) -> String {
+            Self::personal_access_tokens() + "/" + id
+        }
+    }
+}
@@ -0,0 +1,88 @@
+pub mod db;
+pub mod routes;
+pub mod test;
+pub mod usecases;
+
+use crate::{i18n::Message, validation};
+use chrono::NaiveDate;
+use garde::Validate;
+pub use routes::router;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use strum::EnumString;
+use utoipa::ToSchema;
+
+/// タスクの繰り返しのルール。
+/// タスクが完了すると次のタスクが作成され、ルールは次のタスクに移される。
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct TaskRecurrence {
+    pub task_id: String,
+    pub frequency: RecurrenceFrequency,
+    /// 何日・何週・何ヶ月ごとに繰り返すか
+    pub interval: i64,
+    /// このタスクの予定日(%Y/%m/%d)
+    pub due_on: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(
+    Serialize, Deserialize, ToSchema, EnumString, sqlx::Type, Debug, PartialEq, Clone, Copy, Default,
+)]
+pub enum RecurrenceFrequency {
+    #[default]
+    Daily,
+    Weekly,
+    Monthly,
+}
+impl From<String> for RecurrenceFrequency {
+    fn from(value: String) -> Self {
+        RecurrenceFrequency::from_str(value.as_str()).unwrap_or(RecurrenceFrequency::Daily)
+    }
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct UpdateTaskRecurrence {
+    #[garde(skip)]
+    pub frequency: RecurrenceFrequency,
+
+    #[garde(custom(validation::range(1, 365)))]
+    #[schema(minimum = 1, maximum = 365)]
+    pub interval: i64,
+
+    /// このタスクの予定日(%Y/%m/%d)。指定しない場合は今日になる
+    #[garde(custom(validate_due_on))]
+    #[serde(default)]
+    pub due_on: Option<String>,
+}
+
+fn validate_due_on(value: &Option<String>, _: &()) -> garde::Result {
+    let Some(value) = value else {
+        return Ok(());
+    };
+
+    // chronoは桁数の足りない月や日も読み取るので、桁数は先に確認する
+    let parts: Vec<&str> = value.split('/').collect();
+    let is_valid = match parts.as_slice() {
+        [year, month, day] => {
+            let is_number =
+                |s: &str, len: usize| s.len() == len && s.chars().all(|c| c.is_ascii_digit());
+            is_number(year, 4)
+                && is_number(month, 2)
+                && is_number(day, 2)
+                && NaiveDate::parse_from_str(value, "%Y/%m/%d").is_ok()
+        }
+        _ => false,
+    };
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(validation::error(Message::new("validation.invalid_date")))
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct DeleteTaskRecurrenceResponse {
+    pub task_id: String,
+}
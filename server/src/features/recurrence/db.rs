@@ -0,0 +1,175 @@
+use crate::app::Connection;
+
+use super::{RecurrenceFrequency, TaskRecurrence};
+
+pub struct FindTaskRecurrenceArgs<'a> {
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+}
+/// タスクに繰り返しのルールが設定されていない場合はNoneを返す
+pub async fn find_task_recurrence<'a>(
+    db: &mut Connection,
+    args: FindTaskRecurrenceArgs<'a>,
+) -> anyhow::Result<Option<TaskRecurrence>> {
+    let raw = sqlx::query!(
+        r#"
+        SELECT task_id, frequency, interval, due_on, created_at, updated_at
+        FROM task_recurrences
+        WHERE task_id = $1 AND user_id = $2;
+        "#,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(raw.map(|raw| TaskRecurrence {
+        task_id: raw.task_id,
+        frequency: raw.frequency.into(),
+        interval: raw.interval,
+        due_on: raw.due_on,
+        created_at: raw.created_at,
+        updated_at: raw.updated_at,
+    }))
+}
+
+pub struct UpsertTaskRecurrenceArgs<'a> {
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+    pub frequency: &'a RecurrenceFrequency,
+    pub interval: i64,
+    /// Noneの場合は今日になる
+    pub due_on: Option<&'a str>,
+}
+/// タスクに繰り返しのルールを設定する。すでに設定されている場合は上書きする。
+/// 設定したタスクのIDを返す。タスクが存在しない場合はNoneを返す
+pub async fn upsert_task_recurrence<'a>(
+    db: &mut Connection,
+    args: UpsertTaskRecurrenceArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO task_recurrences(task_id, user_id, frequency, interval, due_on)
+        SELECT
+            id,
+            user_id,
+            $1,
+            $2,
+            COALESCE($3, strftime('%Y/%m/%d', CURRENT_TIMESTAMP, 'localtime'))
+        FROM tasks
+        WHERE id = $4 AND user_id = $5
+        ON CONFLICT(task_id) DO UPDATE SET
+            frequency = excluded.frequency,
+            interval = excluded.interval,
+            due_on = excluded.due_on
+        RETURNING task_id;
+        "#,
+        args.frequency,
+        args.interval,
+        args.due_on,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.task_id))
+}
+
+pub struct DeleteTaskRecurrenceArgs<'a> {
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+}
+/// 削除したルールのタスクのIDを返す。ルールが存在しない場合はNoneを返す
+pub async fn delete_task_recurrence<'a>(
+    db: &mut Connection,
+    args: DeleteTaskRecurrenceArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        "DELETE FROM task_recurrences WHERE task_id = $1 AND user_id = $2 RETURNING task_id;",
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.task_id))
+}
+
+/// 繰り返しのルールが設定されている完了状態のタスクのIDを返す
+pub async fn find_done_recurring_task_ids(
+    db: &mut Connection,
+    user_id: &str,
+) -> anyhow::Result<Vec<String>> {
+    let ids = sqlx::query!(
+        r#"
+        SELECT r.task_id
+        FROM task_recurrences r
+        JOIN tasks t ON (r.task_id = t.id)
+        WHERE r.user_id = $1 AND t.status = 'Done'
+        ORDER BY r.due_on, r.task_id;
+        "#,
+        user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?
+    .into_iter()
+    .map(|r| r.task_id)
+    .collect();
+
+    Ok(ids)
+}
+
+pub struct MoveTaskRecurrenceArgs<'a> {
+    pub task_id: &'a str,
+    pub next_task_id: &'a str,
+    pub user_id: &'a str,
+}
+/// 繰り返しのルールを次のタスクに移して、予定日を次の日付に進める。
+/// 月ごとの場合、次の月に同じ日がなければ月末にする
+pub async fn move_task_recurrence_to_next<'a>(
+    db: &mut Connection,
+    args: MoveTaskRecurrenceArgs<'a>,
+) -> anyhow::Result<()> {
+    // SQLiteの日付関数は%Y-%m-%dの形式しか扱えないので、変換してから計算する。
+    // 月を足すと1/31が3/2のように溢れるので、月初から同じ日数だけ進めた日と月末の早いほうにする
+    sqlx::query!(
+        r#"
+        UPDATE task_recurrences
+        SET
+            task_id = $1,
+            due_on = strftime(
+                '%Y/%m/%d',
+                CASE frequency
+                    WHEN 'Monthly' THEN min(
+                        date(
+                            replace(due_on, '/', '-'),
+                            'start of month',
+                            '+' || interval || ' months',
+                            '+' || (strftime('%d', replace(due_on, '/', '-')) - 1) || ' days'
+                        ),
+                        date(
+                            replace(due_on, '/', '-'),
+                            'start of month',
+                            '+' || (interval + 1) || ' months',
+                            '-1 days'
+                        )
+                    )
+                    ELSE date(
+                        replace(due_on, '/', '-'),
+                        '+' || (interval * (CASE frequency WHEN 'Weekly' THEN 7 ELSE 1 END)) || ' days'
+                    )
+                END
+            )
+        WHERE task_id = $2 AND user_id = $3
+        RETURNING task_id;
+        "#,
+        args.next_task_id,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_one(&mut *db)
+    .await?;
+
+    Ok(())
+}
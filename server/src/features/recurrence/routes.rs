@@ -0,0 +1,40 @@
+use axum::{middleware, routing::get, Router};
+use axum_login::login_required;
+
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope, task::routes::TaskPaths},
+};
+
+pub mod delete_task_recurrence;
+pub mod get_task_recurrence;
+pub mod update_task_recurrence;
+
+pub const TAG: &str = "recurrence";
+
+pub struct RecurrencePaths;
+impl RecurrencePaths {
+    pub fn task_recurrence_base() -> String {
+        "/recurrence".into()
+    }
+
+    pub fn task_recurrence() -> String {
+        TaskPaths::task() + &Self::task_recurrence_base()
+    }
+
+    pub fn task_recurrence_open_api() -> String {
+        TaskPaths::task_open_api() + &Self::task_recurrence_base()
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            &RecurrencePaths::task_recurrence(),
+            get(get_task_recurrence::handler)
+                .put(update_task_recurrence::handler)
+                .delete(delete_task_recurrence::handler),
+        )
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
+        .route_layer(login_required!(Auth))
+}
@@ -0,0 +1,125 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        recurrence::{
+            db::{delete_task_recurrence, DeleteTaskRecurrenceArgs},
+            DeleteTaskRecurrenceResponse,
+        },
+        sub_task::{db::TaskAndUser, usecases::update_task_and_ancestors_status},
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    delete,
+    tag = super::TAG,
+    path = super::RecurrencePaths::task_recurrence_open_api(),
+    responses(
+        (status = 200, body = DeleteTaskRecurrenceResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<DeleteTaskRecurrenceResponse>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(task_id) = delete_task_recurrence(
+        &mut tx,
+        DeleteTaskRecurrenceArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    // 未完了のタスクはメインタスクの状態の集計に含まれるようになるので、祖先メインタスクを更新する
+    update_task_and_ancestors_status::action(
+        &mut tx,
+        TaskAndUser {
+            task_id: &task_id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(DeleteTaskRecurrenceResponse { task_id }))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            recurrence::{routes::RecurrencePaths, RecurrenceFrequency, UpdateTaskRecurrence},
+            task::{routes::TaskPaths, test::task_factory, Task, TaskStatus, UpdateTaskStatus},
+        },
+    };
+
+    #[sqlx::test]
+    async fn 繰り返しのルールを削除すると完了しても次のタスクは作成されない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        test.server()
+            .put(&RecurrencePaths::one_task_recurrence(&task.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Daily,
+                interval: 1,
+                due_on: None,
+            })
+            .await
+            .assert_status_ok();
+
+        test.server()
+            .delete(&RecurrencePaths::one_task_recurrence(&task.id))
+            .await
+            .assert_status_ok();
+        let res = test
+            .server()
+            .get(&RecurrencePaths::one_task_recurrence(&task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&task.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+        assert_eq!(tasks.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        recurrence::{
+            db::{find_task_recurrence, FindTaskRecurrenceArgs},
+            TaskRecurrence,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::RecurrencePaths::task_recurrence_open_api(),
+    responses(
+        (status = 200, body = TaskRecurrence),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<TaskRecurrence>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(recurrence) = find_task_recurrence(
+        &mut tx,
+        FindTaskRecurrenceArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    tx.commit().await?;
+
+    Ok(Json(recurrence))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            recurrence::{
+                routes::RecurrencePaths, RecurrenceFrequency, TaskRecurrence, UpdateTaskRecurrence,
+            },
+            task::test::task_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 繰り返しのルールを取得できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        test.server()
+            .put(&RecurrencePaths::one_task_recurrence(&task.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Weekly,
+                interval: 2,
+                due_on: Some("2024/03/01".into()),
+            })
+            .await
+            .assert_status_ok();
+
+        let recurrence: TaskRecurrence = test
+            .server()
+            .get(&RecurrencePaths::one_task_recurrence(&task.id))
+            .await
+            .json();
+        assert_eq!(recurrence.task_id, task.id);
+        assert_eq!(recurrence.frequency, RecurrenceFrequency::Weekly);
+        assert_eq!(recurrence.interval, 2);
+        assert_eq!(recurrence.due_on, "2024/03/01");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 繰り返しのルールが設定されていない場合は404を返す(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .get(&RecurrencePaths::one_task_recurrence(&task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        recurrence::{
+            db::{
+                find_task_recurrence, upsert_task_recurrence, FindTaskRecurrenceArgs,
+                UpsertTaskRecurrenceArgs,
+            },
+            TaskRecurrence, UpdateTaskRecurrence,
+        },
+        sub_task::{db::TaskAndUser, usecases::update_task_and_ancestors_status},
+    },
+    validation::WithValidation,
+};
+
+/// 完了しているタスクにルールを設定すると、すぐに次のタスクが作成されてルールが移される。
+/// そのときは次のタスクに移されたルールを返す。
+#[tracing::instrument(err)]
+#[utoipa::path(
+    put,
+    tag = super::TAG,
+    path = super::RecurrencePaths::task_recurrence_open_api(),
+    request_body = UpdateTaskRecurrence,
+    responses(
+        (status = 200, body = TaskRecurrence),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<UpdateTaskRecurrence>>,
+) -> AppResult<Json<TaskRecurrence>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    if upsert_task_recurrence(
+        &mut tx,
+        UpsertTaskRecurrenceArgs {
+            task_id: &id,
+            user_id: &user.id,
+            frequency: &payload.frequency,
+            interval: payload.interval,
+            due_on: payload.due_on.as_deref(),
+        },
+    )
+    .await?
+    .is_none()
+    {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    }
+
+    // 未完了の繰り返しのタスクはメインタスクの状態の集計から外れるので、祖先メインタスクを更新する
+    let occurrences = update_task_and_ancestors_status::action(
+        &mut tx,
+        TaskAndUser {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+    let task_id = occurrences
+        .iter()
+        .find(|o| o.task_id == id)
+        .map_or(id.as_str(), |o| o.next_task_id.as_str());
+
+    let recurrence = find_task_recurrence(
+        &mut tx,
+        FindTaskRecurrenceArgs {
+            task_id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or_else(|| anyhow!("recurrence not found"))?;
+
+    tx.commit().await?;
+
+    Ok(Json(recurrence))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            recurrence::{
+                routes::RecurrencePaths, RecurrenceFrequency, TaskRecurrence, UpdateTaskRecurrence,
+            },
+            task::{test::task_factory, Task, TaskStatus},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 繰り返しのルールを上書きできる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .put(&RecurrencePaths::one_task_recurrence(&task.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Daily,
+                interval: 1,
+                due_on: None,
+            })
+            .await;
+        res.assert_status_ok();
+        // 予定日を指定しない場合は今日になる
+        assert_eq!(
+            res.json::<TaskRecurrence>().due_on.len(),
+            "2024/01/01".len()
+        );
+
+        let updated: TaskRecurrence = test
+            .server()
+            .put(&RecurrencePaths::one_task_recurrence(&task.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Monthly,
+                interval: 3,
+                due_on: Some("2024/01/31".into()),
+            })
+            .await
+            .json();
+        assert_eq!(updated.frequency, RecurrenceFrequency::Monthly);
+        assert_eq!(updated.interval, 3);
+        assert_eq!(updated.due_on, "2024/01/31");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 完了しているタスクにルールを設定するとすぐに次のタスクが作成される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create(
+            &db,
+            Task {
+                user_id: user.id.clone(),
+                status: TaskStatus::Done,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        let recurrence: TaskRecurrence = test
+            .server()
+            .put(&RecurrencePaths::one_task_recurrence(&task.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Daily,
+                interval: 1,
+                due_on: Some("2024/03/01".into()),
+            })
+            .await
+            .json();
+        // ルールは次のタスクに移されている
+        assert_ne!(recurrence.task_id, task.id);
+        assert_eq!(recurrence.due_on, "2024/03/02");
+
+        let res = test
+            .server()
+            .get(&RecurrencePaths::one_task_recurrence(&task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 不正な予定日や間隔は設定できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        for (interval, due_on) in [
+            (0, None),
+            (1, Some("2024-01-01")),
+            (1, Some("2024/13/01")),
+            (1, Some("2024/02/30")),
+            (1, Some("2023/02/29")),
+        ] {
+            let res = test
+                .server()
+                .put(&RecurrencePaths::one_task_recurrence(&task.id))
+                .json(&UpdateTaskRecurrence {
+                    frequency: RecurrenceFrequency::Daily,
+                    interval,
+                    due_on: due_on.map(|d| d.into()),
+                })
+                .await;
+            assert!(res.status_code().is_client_error());
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクには繰り返しのルールを設定できない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .put(&RecurrencePaths::one_task_recurrence(&other_user_task.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Daily,
+                interval: 1,
+                due_on: None,
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+#[cfg(test)]
+pub mod routes {
+    use crate::features::{recurrence, task::routes::TaskPaths};
+
+    impl recurrence::routes::RecurrencePaths {
+        pub fn one_task_recurrence(task_id: &str) -> String {
+            TaskPaths::one_task(task_id) + &Self::task_recurrence_base()
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::anyhow;
+
+use crate::{
+    app::Connection,
+    features::{
+        recurrence::db::{
+            find_done_recurring_task_ids, move_task_recurrence_to_next, MoveTaskRecurrenceArgs,
+        },
+        sub_task::db::{
+            check_sub_task_connection, insert_sub_task_connection, InsertSubTaskConnectionArgs,
+        },
+        task::{
+            db::load_task_graph,
+            usecases::duplicate_task::{self, DuplicateTaskArgs, DuplicateTaskError},
+        },
+    },
+};
+
+pub struct CreateNextOccurrencesArgs<'a> {
+    pub user_id: &'a str,
+}
+
+pub struct NextOccurrence {
+    /// 完了した繰り返しのタスクのID
+    pub task_id: String,
+    /// 作成した次のタスクのID
+    pub next_task_id: String,
+}
+
+/// 完了状態になった繰り返しのタスクについて次のタスクを作成する。
+/// 次のタスクはサブタスクも含めて未完了の状態で複製され、繰り返しのルールは次のタスクに移される。
+/// 繰り返しのタスクがサブタスクの場合は、次のタスクも同じメインタスクのサブタスクにする。
+/// ルールは次のタスクに移されるので、完了状態のタスクがルールを持ち続けることはない。
+pub async fn action<'a>(
+    db: &mut Connection,
+    args: CreateNextOccurrencesArgs<'a>,
+) -> anyhow::Result<Vec<NextOccurrence>> {
+    let task_ids = find_done_recurring_task_ids(&mut *db, args.user_id).await?;
+    if task_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // 状態を更新したあとのグラフが必要なので、キャッシュは使わずに読み込む
+    let graph = load_task_graph(&mut *db, args.user_id).await?;
+
+    let mut occurrences = vec![];
+    for task_id in task_ids {
+        let new_ids = duplicate_task::action(
+            &mut *db,
+            DuplicateTaskArgs {
+                task_id: &task_id,
+                user_id: args.user_id,
+                include_descendants: true,
+                copy_blocking_connections: true,
+                reset_status: true,
+                graph: &graph,
+            },
+        )
+        .await
+        .map_err(|e| match e {
+            DuplicateTaskError::TaskNotFound => anyhow!("task not found"),
+            DuplicateTaskError::Unknown(e) => e,
+        })?;
+        let next_task_id = new_ids
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("task not duplicated"))?;
+
+        // 複製したタスクはメインタスクを持たないので、元のタスクのメインタスクにつなげ直す。
+        // 次のタスクは未完了の繰り返しのタスクなので、メインタスクの状態は変わらない。
+        // 次のタスクはグラフにないので、つながりの確認はデータベースで行う
+        if let Some(main_task_id) = graph.main_task_id(&task_id) {
+            let insert_args = InsertSubTaskConnectionArgs {
+                main_task_id,
+                sub_task_id: &next_task_id,
+                user_id: args.user_id,
+            };
+            check_sub_task_connection(&mut *db, None, &insert_args)
+                .await
+                .map_err(|_| anyhow!("failed to connect main task"))?;
+            insert_sub_task_connection(&mut *db, insert_args).await?;
+        }
+
+        move_task_recurrence_to_next(
+            &mut *db,
+            MoveTaskRecurrenceArgs {
+                task_id: &task_id,
+                next_task_id: &next_task_id,
+                user_id: args.user_id,
+            },
+        )
+        .await?;
+
+        occurrences.push(NextOccurrence {
+            task_id,
+            next_task_id,
+        });
+    }
+
+    Ok(occurrences)
+}
@@ -0,0 +1,39 @@
+pub mod db;
+pub mod routes;
+
+pub use routes::router;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Default)]
+pub struct TaskStats {
+    pub status_counts: StatusCounts,
+    /// 日ごとの完了したタスク数 (日付の昇順)
+    pub completed_per_day: Vec<PeriodCount>,
+    /// 週ごとの完了したタスク数 (週の昇順)
+    pub completed_per_week: Vec<PeriodCount>,
+    /// 作成済みのタスク数と完了済みのタスク数の推移
+    pub burndown: Vec<BurndownPoint>,
+    /// タスクが作成されてから完了するまでの平均時間(秒)。完了したタスクがない場合はNone
+    pub average_lead_time_seconds: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Default, PartialEq)]
+pub struct StatusCounts {
+    pub todo: i64,
+    pub done: i64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, PartialEq)]
+pub struct PeriodCount {
+    /// 日の場合は`YYYY/MM/DD`、週の場合はISO 8601の週で`YYYY-Www`
+    pub period: String,
+    pub count: i64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, PartialEq)]
+pub struct BurndownPoint {
+    pub date: String,
+    pub open: i64,
+    pub closed: i64,
+}
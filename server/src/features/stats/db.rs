@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use crate::{app::Connection, features::task::TaskStatus};
+
+use super::{BurndownPoint, PeriodCount, StatusCounts, TaskStats};
+
+pub struct FindTaskStatsArgs<'a> {
+    pub user_id: &'a str,
+    /// 指定した場合は、そのタスクとすべての子孫サブタスクだけを集計する
+    pub root_task_id: Option<&'a str>,
+}
+pub async fn find_task_stats<'a>(
+    db: &mut Connection,
+    args: FindTaskStatsArgs<'a>,
+) -> anyhow::Result<TaskStats> {
+    // 日時は`YYYY/MM/DD HH:MM:SS`で保存されているので、SQLiteの日付関数に渡すときは`/`を`-`に置き換える
+    let rows = sqlx::query!(
+        r#"
+        WITH RECURSIVE scoped_tasks AS (
+            SELECT id
+            FROM tasks
+            WHERE user_id = $1 AND ($2 IS NULL OR id = $2)
+
+            UNION
+
+            SELECT s.sub_task_id
+            FROM sub_tasks s
+            JOIN scoped_tasks st ON s.main_task_id = st.id
+            WHERE $2 IS NOT NULL
+        )
+
+        SELECT
+            t.status,
+            substr(t.created_at, 1, 10) as "created_on!: String",
+            substr(t.completed_at, 1, 10) as "completed_on: String",
+            -- ISO 8601の週。週(月曜始まり)の木曜日がある年と、その年の何番目の木曜日かで決まる
+            CASE WHEN t.completed_at IS NOT NULL THEN printf(
+                '%s-W%02d',
+                strftime('%Y', replace(t.completed_at, '/', '-'), '-3 days', 'weekday 4'),
+                (strftime('%j', replace(t.completed_at, '/', '-'), '-3 days', 'weekday 4') - 1) / 7 + 1
+            ) END as "completed_week: String",
+            (julianday(replace(t.completed_at, '/', '-')) - julianday(replace(t.created_at, '/', '-'))) * 86400 as "lead_time_seconds: f64"
+        FROM tasks t
+        JOIN scoped_tasks st ON t.id = st.id
+        WHERE t.user_id = $1
+        "#,
+        args.user_id,
+        args.root_task_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let mut status_counts = StatusCounts::default();
+    let mut completed_per_day: BTreeMap<String, i64> = BTreeMap::new();
+    let mut completed_per_week: BTreeMap<String, i64> = BTreeMap::new();
+    // (作成されたタスク数, 完了したタスク数)
+    let mut daily_events: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+    let mut lead_times: Vec<f64> = Vec::new();
+
+    for row in rows {
+        let status: TaskStatus = row.status.into();
+        match status {
+            TaskStatus::Todo => status_counts.todo += 1,
+            TaskStatus::Done => status_counts.done += 1,
+        }
+
+        daily_events.entry(row.created_on).or_default().0 += 1;
+
+        // 完了日時は完了状態のタスクにしか存在しない
+        if let Some(completed_on) = row.completed_on {
+            *completed_per_day.entry(completed_on.clone()).or_default() += 1;
+            daily_events.entry(completed_on).or_default().1 += 1;
+        }
+        if let Some(week) = row.completed_week {
+            *completed_per_week.entry(week).or_default() += 1;
+        }
+        if let Some(lead_time) = row.lead_time_seconds {
+            lead_times.push(lead_time);
+        }
+    }
+
+    let mut burndown = Vec::new();
+    let (mut created, mut closed) = (0, 0);
+    for (date, (created_count, closed_count)) in daily_events {
+        created += created_count;
+        closed += closed_count;
+        burndown.push(BurndownPoint {
+            date,
+            open: created - closed,
+            closed,
+        });
+    }
+
+    let average_lead_time_seconds = if lead_times.is_empty() {
+        None
+    } else {
+        Some(lead_times.iter().sum::<f64>() / lead_times.len() as f64)
+    };
+
+    let to_period_counts = |map: BTreeMap<String, i64>| {
+        map.into_iter()
+            .map(|(period, count)| PeriodCount { period, count })
+            .collect()
+    };
+
+    Ok(TaskStats {
+        status_counts,
+        completed_per_day: to_period_counts(completed_per_day),
+        completed_per_week: to_period_counts(completed_per_week),
+        burndown,
+        average_lead_time_seconds,
+    })
+}
@@ -0,0 +1,25 @@
+use axum::{middleware, routing::get, Router};
+use axum_login::login_required;
+
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope},
+};
+
+pub mod get_stats;
+
+pub const TAG: &str = "stats";
+
+pub struct StatsPaths;
+impl StatsPaths {
+    pub fn stats() -> String {
+        "/stats".into()
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(&StatsPaths::stats(), get(get_stats::handler))
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
+        .route_layer(login_required!(Auth))
+}
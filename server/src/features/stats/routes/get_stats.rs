@@ -0,0 +1,211 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use axum_login::AuthSession;
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        stats::{
+            db::{find_task_stats, FindTaskStatsArgs},
+            TaskStats,
+        },
+    },
+};
+
+#[derive(Debug, Deserialize, Serialize, IntoParams, Default)]
+pub struct StatsQuery {
+    /// 指定した場合は、そのタスクをルートとするサブタスクのツリーだけを集計する
+    pub root_task_id: Option<String>,
+}
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::StatsPaths::stats(),
+    responses(
+        (status = 200, body = TaskStats),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
+    params(StatsQuery)
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    State(AppState { db, .. }): State<AppState>,
+    Query(StatsQuery { root_task_id }): Query<StatsQuery>,
+) -> AppResult<Json<TaskStats>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let stats = find_task_stats(
+        &mut tx,
+        FindTaskStatsArgs {
+            user_id: &user.id,
+            root_task_id: root_task_id.as_deref(),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            stats::{routes::StatsPaths, PeriodCount, StatusCounts, TaskStats},
+            task::{routes::TaskPaths, test::task_factory, Task, TaskStatus, UpdateTaskStatus},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 自分のタスク全体の統計を取得できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        task_factory::create_with_user(&db, &other_user.id).await?;
+
+        let user = test.login(None).await?;
+        let t1 = task_factory::create_with_user(&db, &user.id).await?;
+        task_factory::create_with_user(&db, &user.id).await?;
+        task_factory::create_with_user(&db, &user.id).await?;
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&t1.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let stats: TaskStats = test.server().get(&StatsPaths::stats()).await.json();
+        assert_eq!(stats.status_counts, StatusCounts { todo: 2, done: 1 });
+        assert_eq!(
+            stats.completed_per_day.iter().map(|c| c.count).sum::<i64>(),
+            1
+        );
+        assert_eq!(
+            stats
+                .completed_per_week
+                .iter()
+                .map(|c| c.count)
+                .sum::<i64>(),
+            1
+        );
+        assert!(stats.average_lead_time_seconds.is_some());
+
+        let last = stats.burndown.last().unwrap();
+        assert_eq!((last.open, last.closed), (2, 1));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 週ごとの完了数はiso_8601の週で集計する(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        // 年をまたぐ週は、木曜日がある年の週になる
+        for completed_at in [
+            "2021/01/03 12:00:00",
+            "2024/12/30 09:00:00",
+            "2025/01/05 23:59:59",
+        ] {
+            let task = task_factory::create(
+                &db,
+                Task {
+                    status: TaskStatus::Done,
+                    user_id: user.id.clone(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            sqlx::query!(
+                "UPDATE tasks SET completed_at = $1 WHERE id = $2;",
+                completed_at,
+                task.id
+            )
+            .execute(&db)
+            .await?;
+        }
+
+        let stats: TaskStats = test.server().get(&StatsPaths::stats()).await.json();
+        assert_eq!(
+            stats.completed_per_week,
+            vec![
+                PeriodCount {
+                    period: "2020-W53".into(),
+                    count: 1
+                },
+                PeriodCount {
+                    period: "2025-W01".into(),
+                    count: 2
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn メインタスクを指定するとサブタスクのツリーだけを集計する(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        // main --> sub1
+        // sub1 --> sub11
+        // other
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let sub1 = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        task_factory::create_sub_task(
+            &db,
+            &sub1.id,
+            Task {
+                status: TaskStatus::Done,
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        task_factory::create_with_user(&db, &user.id).await?;
+
+        let stats: TaskStats = test
+            .server()
+            .get(&StatsPaths::stats())
+            .add_query_param("root_task_id", &main.id)
+            .await
+            .json();
+        assert_eq!(stats.status_counts, StatusCounts { todo: 2, done: 1 });
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn タスクが存在しない場合は空の統計を返す(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        let stats: TaskStats = test.server().get(&StatsPaths::stats()).await.json();
+        assert_eq!(stats.status_counts, StatusCounts::default());
+        assert!(stats.burndown.is_empty());
+        assert!(stats.average_lead_time_seconds.is_none());
+
+        Ok(())
+    }
+}
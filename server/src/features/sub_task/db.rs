@@ -1,4 +1,4 @@
-use async_recursion::async_recursion;
+use std::collections::HashMap;
 
 use crate::{
     app::Connection,
@@ -6,11 +6,12 @@ use crate::{
         block_task::db::{is_blocked_task, IsBlockedTaskArgs},
         task::{
             db::{
-                detect_circular_connection, exists_tasks, find_task, is_all_tasks_done,
-                update_task_status, DetectCircularConnectionArgs, ExistsTasksArg, ExistsTasksError,
-                FindTaskArgs, UpdateTaskStatusArgs,
+                detect_circular_connection, exists_tasks, update_tasks_status,
+                DetectCircularConnectionArgs, ExistsTasksArg, ExistsTasksError,
+                UpdateTasksStatusArgs,
             },
-            TaskStatus,
+            graph::TaskGraph,
+            TaskRollupMode, TaskStatus,
         },
     },
 };
@@ -63,8 +64,10 @@ pub enum SubTaskConnectionError {
     Unknown(anyhow::Error),
 }
 
+/// `graph`が渡された場合は、タスクのつながりの確認をデータベースではなくメモリ上で行う
 pub async fn check_sub_task_connection<'a>(
     db: &mut Connection,
+    graph: Option<&TaskGraph>,
     args: &InsertSubTaskConnectionArgs<'a>,
 ) -> Result<(), SubTaskConnectionError> {
     // ログインユーザーが指定されたタスクを持っているかを確認する
@@ -87,39 +90,51 @@ pub async fn check_sub_task_connection<'a>(
 
     // タスク同士が循環していないかを確認する。
     // payload.main_task_idの祖先に、payload.sub_task_idを持つtaskが存在しないことを確認する。
-    if detect_circular_connection(
-        &mut *db,
-        DetectCircularConnectionArgs {
-            parent_task_id: args.main_task_id,
-            child_task_id: args.sub_task_id,
-            user_id: args.user_id,
-        },
-    )
-    .await
-    .map_err(SubTaskConnectionError::Unknown)?
-    {
+    let is_circular = match graph {
+        Some(graph) => graph.is_ancestor(args.main_task_id, args.sub_task_id),
+        None => detect_circular_connection(
+            &mut *db,
+            DetectCircularConnectionArgs {
+                parent_task_id: args.main_task_id,
+                child_task_id: args.sub_task_id,
+                user_id: args.user_id,
+            },
+        )
+        .await
+        .map_err(SubTaskConnectionError::Unknown)?,
+    };
+    if is_circular {
         return Err(SubTaskConnectionError::CircularTask);
     }
 
     // サブタスクがメインタスクにブロックされているタスクではないことを確認する
-    if is_blocked_task(
-        &mut *db,
-        IsBlockedTaskArgs {
-            blocking_task_id: args.main_task_id,
-            task_id: args.sub_task_id,
-        },
-    )
-    .await
-    .map_err(SubTaskConnectionError::Unknown)?
-    {
+    let is_blocked_by_main_task = match graph {
+        Some(graph) => graph
+            .blocking_task_ids(args.sub_task_id)
+            .iter()
+            .any(|id| id == args.main_task_id),
+        None => is_blocked_task(
+            &mut *db,
+            IsBlockedTaskArgs {
+                blocking_task_id: args.main_task_id,
+                task_id: args.sub_task_id,
+            },
+        )
+        .await
+        .map_err(SubTaskConnectionError::Unknown)?,
+    };
+    if is_blocked_by_main_task {
         return Err(SubTaskConnectionError::BlockedByMainTask);
     }
 
     // サブタスクが他のメインタスクを持っていないことを確認する
-    if has_main_task(&mut *db, args.sub_task_id)
-        .await
-        .map_err(SubTaskConnectionError::Unknown)?
-    {
+    let has_main_task = match graph {
+        Some(graph) => graph.main_task_id(args.sub_task_id).is_some(),
+        None => has_main_task(&mut *db, args.sub_task_id)
+            .await
+            .map_err(SubTaskConnectionError::Unknown)?,
+    };
+    if has_main_task {
         return Err(SubTaskConnectionError::MultipleMainTask);
     }
 
@@ -192,24 +207,132 @@ pub struct TaskAndUser<'a> {
     pub task_id: &'a str,
     pub user_id: &'a str,
 }
-pub async fn update_all_ancestor_main_tasks_status<'a>(
+/// タスクとそのすべての祖先メインタスクの状態を、サブタスクの状態に合わせて更新する。
+/// 祖先メインタスクと、それぞれの直下のサブタスクの状態を一つの再帰クエリでまとめて取得し、
+/// 下の階層から順に新しい状態を求めてから、状態が変わるタスクだけをまとめて更新する。
+/// 未完了の繰り返しのタスクは完了しても次のタスクが作られるので、メインタスクの状態を決めるときには数えない。
+pub async fn update_task_and_all_ancestor_main_tasks_status<'a>(
     db: &mut Connection,
     args: TaskAndUser<'a>,
 ) -> anyhow::Result<()> {
-    let main_task_id = find_main_task_id(
-        &mut *db,
-        FindMainTaskIdsArgs {
-            sub_task_id: args.task_id,
-            user_id: args.user_id,
-        },
+    let rows = sqlx::query!(
+        r#"
+        WITH RECURSIVE ancestors(task_id, depth) AS (
+            SELECT id, 0
+            FROM tasks
+            WHERE id = $1 AND user_id = $2
+
+            UNION ALL
+
+            SELECT s.main_task_id, a.depth + 1
+            FROM sub_tasks s
+            JOIN ancestors a ON s.sub_task_id = a.task_id
+            WHERE s.user_id = $2
+        )
+
+        SELECT
+            a.task_id as "task_id!: String",
+            a.depth as "depth!: i64",
+            t.status as "status!: String",
+            t.rollup_mode as "rollup_mode!: String",
+            s.sub_task_id as "sub_task_id?: String",
+            st.status as "sub_task_status?: String",
+            EXISTS (
+                SELECT 1 FROM task_recurrences r WHERE r.task_id = s.sub_task_id
+            ) as "sub_task_recurring!: bool"
+        FROM ancestors a
+        JOIN tasks t ON a.task_id = t.id
+        LEFT OUTER JOIN sub_tasks s ON a.task_id = s.main_task_id
+        LEFT OUTER JOIN tasks st ON s.sub_task_id = st.id
+        ORDER BY a.depth
+        "#,
+        args.task_id,
+        args.user_id,
     )
+    .fetch_all(&mut *db)
     .await?;
 
-    if let Some(id) = main_task_id {
-        update_task_and_all_ancestor_main_tasks_status(
+    struct Ancestor {
+        id: String,
+        status: TaskStatus,
+        rollup_mode: TaskRollupMode,
+        /// サブタスクのID、状態、繰り返しのルールがあるか
+        sub_tasks: Vec<(String, TaskStatus, bool)>,
+    }
+
+    // 深さの昇順(タスク自身 -> メインタスク -> ...)に並んでいる
+    let mut ancestors: Vec<Ancestor> = Vec::new();
+    for row in rows {
+        if ancestors.last().map(|a| &a.id) != Some(&row.task_id) {
+            ancestors.push(Ancestor {
+                id: row.task_id,
+                status: row.status.into(),
+                rollup_mode: row.rollup_mode.into(),
+                sub_tasks: Vec::new(),
+            });
+        }
+        if let (Some(sub_task_id), Some(sub_task_status), Some(ancestor)) =
+            (row.sub_task_id, row.sub_task_status, ancestors.last_mut())
+        {
+            ancestor
+                .sub_tasks
+                .push((sub_task_id, sub_task_status.into(), row.sub_task_recurring));
+        }
+    }
+
+    // 下の階層で更新された状態を上の階層で使うために、新しい状態を記録しておく
+    let mut new_statuses: HashMap<&str, TaskStatus> = HashMap::new();
+    let mut done_task_ids: Vec<String> = Vec::new();
+    let mut todo_task_ids: Vec<String> = Vec::new();
+    for ancestor in &ancestors {
+        // 下の階層で更新された状態を使い、未完了の繰り返しのタスクを除く
+        let sub_task_statuses: Vec<TaskStatus> = ancestor
+            .sub_tasks
+            .iter()
+            .map(|(id, status, recurring)| {
+                (*new_statuses.get(id.as_str()).unwrap_or(status), *recurring)
+            })
+            .filter(|(status, recurring)| !recurring || *status == TaskStatus::Done)
+            .map(|(status, _)| status)
+            .collect();
+
+        // 手動で状態を変更するタスクはサブタスクの状態を見ない
+        let new_status =
+            if sub_task_statuses.is_empty() || ancestor.rollup_mode == TaskRollupMode::Manual {
+                ancestor.status
+            } else {
+                let is_all_sub_tasks_done = sub_task_statuses
+                    .iter()
+                    .all(|status| *status == TaskStatus::Done);
+
+                match (ancestor.rollup_mode, is_all_sub_tasks_done) {
+                    (TaskRollupMode::Auto, true) => TaskStatus::Done,
+                    (_, false) => TaskStatus::Todo,
+                    // 完了への変更は手動で行う
+                    (TaskRollupMode::AutoReopenOnly, true) | (TaskRollupMode::Manual, _) => {
+                        ancestor.status
+                    }
+                }
+            };
+
+        if new_status != ancestor.status {
+            match new_status {
+                TaskStatus::Done => done_task_ids.push(ancestor.id.clone()),
+                TaskStatus::Todo => todo_task_ids.push(ancestor.id.clone()),
+            }
+        }
+        new_statuses.insert(&ancestor.id, new_status);
+    }
+
+    for (task_ids, status) in [
+        (&done_task_ids, TaskStatus::Done),
+        (&todo_task_ids, TaskStatus::Todo),
+    ] {
+        update_tasks_status(
             &mut *db,
-            TaskAndUser {
-                task_id: &id,
+            UpdateTasksStatusArgs {
+                task_ids,
+                status: &status,
                 user_id: args.user_id,
             },
         )
@@ -219,63 +342,288 @@ pub async fn update_all_ancestor_main_tasks_status<'a>(
     Ok(())
 }
 
-// パフォーマンス悪いかも
-#[async_recursion]
-pub async fn update_task_and_all_ancestor_main_tasks_status<'a>(
-    db: &mut Connection,
-    args: TaskAndUser<'a>,
-) -> anyhow::Result<()>
-where
-    'a: 'async_recursion,
-{
-    let task = find_task(
-        &mut *db,
-        FindTaskArgs {
-            task_id: args.task_id,
-            user_id: args.user_id,
-        },
-    )
-    .await?;
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
 
-    if !task.sub_task_ids.is_empty() {
-        // サブタスクの状態を見てタスクの状態を更新する
-        let is_all_sub_tasks_done = is_all_tasks_done(&mut *db, &task.sub_task_ids).await?;
-        let new_status = if is_all_sub_tasks_done {
-            TaskStatus::Done
-        } else {
-            TaskStatus::Todo
-        };
+    use async_recursion::async_recursion;
+    use sqlx::{QueryBuilder, Row, Sqlite};
 
-        update_task_status(
+    use super::*;
+    use crate::{
+        app::{AppResult, Db},
+        features::{
+            task::{
+                db::{find_task, update_task_status, FindTaskArgs, UpdateTaskStatusArgs},
+                test::random::Lcg,
+            },
+            user::test::user_factory,
+        },
+    };
+
+    /// 集合ベースに書き換える前の、祖先をひとつずつたどる実装。
+    /// 結果が変わっていないことの確認と、ベンチマークの比較対象のために残している。
+    #[async_recursion]
+    async fn legacy_update_task_and_all_ancestor_main_tasks_status<'a>(
+        db: &mut Connection,
+        args: TaskAndUser<'a>,
+    ) -> anyhow::Result<()>
+    where
+        'a: 'async_recursion,
+    {
+        let task = find_task(
             &mut *db,
-            UpdateTaskStatusArgs {
-                id: &task.id,
-                status: &new_status,
+            FindTaskArgs {
+                task_id: args.task_id,
                 user_id: args.user_id,
             },
         )
         .await?;
-    }
 
-    let main_task_id = find_main_task_id(
-        &mut *db,
-        FindMainTaskIdsArgs {
-            sub_task_id: &task.id,
-            user_id: args.user_id,
-        },
-    )
-    .await?;
+        if !task.sub_task_ids.is_empty() && task.rollup_mode != TaskRollupMode::Manual {
+            let mut query_builder: QueryBuilder<Sqlite> =
+                QueryBuilder::new("SELECT count(*) FROM tasks WHERE status <> 'Done' AND id IN (");
+            let mut separated = query_builder.separated(", ");
+            for id in &task.sub_task_ids {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(") ");
+            let count: i32 = query_builder.build().fetch_one(&mut *db).await?.get(0);
 
-    if let Some(id) = main_task_id {
-        update_task_and_all_ancestor_main_tasks_status(
+            let new_status = match (task.rollup_mode, count == 0) {
+                (TaskRollupMode::Auto, true) => Some(TaskStatus::Done),
+                (_, false) => Some(TaskStatus::Todo),
+                (TaskRollupMode::AutoReopenOnly, true) | (TaskRollupMode::Manual, _) => None,
+            };
+            if let Some(new_status) = new_status {
+                update_task_status(
+                    &mut *db,
+                    UpdateTaskStatusArgs {
+                        id: &task.id,
+                        status: &new_status,
+                        user_id: args.user_id,
+                    },
+                )
+                .await?;
+            }
+        }
+
+        let main_task_id = find_main_task_id(
             &mut *db,
-            TaskAndUser {
-                task_id: &id,
+            FindMainTaskIdsArgs {
+                sub_task_id: &task.id,
                 user_id: args.user_id,
             },
         )
         .await?;
+        if let Some(id) = main_task_id {
+            legacy_update_task_and_all_ancestor_main_tasks_status(
+                &mut *db,
+                TaskAndUser {
+                    task_id: &id,
+                    user_id: args.user_id,
+                },
+            )
+            .await?;
+        }
+
+        Ok(())
     }
 
-    Ok(())
+    struct TreeNode {
+        parent: Option<usize>,
+        status: TaskStatus,
+        rollup_mode: TaskRollupMode,
+    }
+
+    /// ノードのリストからタスクのツリーを作り、作成したタスクのIDをノードと同じ順番で返す
+    async fn create_tree(db: &Db, user_id: &str, nodes: &[TreeNode]) -> AppResult<Vec<String>> {
+        let ids: Vec<String> = nodes
+            .iter()
+            .map(|_| uuid::Uuid::new_v4().to_string())
+            .collect();
+
+        let mut tx = db.begin().await?;
+        for chunk in ids.iter().zip(nodes).collect::<Vec<_>>().chunks(100) {
+            let mut query_builder: QueryBuilder<Sqlite> =
+                QueryBuilder::new("INSERT INTO tasks(id, title, user_id, status, rollup_mode) ");
+            query_builder.push_values(chunk, |mut b, (id, node)| {
+                b.push_bind(id.as_str())
+                    .push_bind("title")
+                    .push_bind(user_id)
+                    .push_bind(node.status)
+                    .push_bind(node.rollup_mode);
+            });
+            query_builder.build().execute(&mut *tx).await?;
+        }
+
+        let edges: Vec<(&String, &String)> = nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, node)| node.parent.map(|p| (&ids[p], &ids[i])))
+            .collect();
+        for chunk in edges.chunks(100) {
+            let mut query_builder: QueryBuilder<Sqlite> =
+                QueryBuilder::new("INSERT INTO sub_tasks(main_task_id, sub_task_id, user_id) ");
+            query_builder.push_values(chunk, |mut b, (main_task_id, sub_task_id)| {
+                b.push_bind(main_task_id.as_str())
+                    .push_bind(sub_task_id.as_str())
+                    .push_bind(user_id);
+            });
+            query_builder.build().execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        Ok(ids)
+    }
+
+    async fn find_statuses(db: &Db, user_id: &str, ids: &[String]) -> AppResult<Vec<TaskStatus>> {
+        let rows = sqlx::query!("SELECT id, status FROM tasks WHERE user_id = $1", user_id)
+            .fetch_all(db)
+            .await?;
+        let statuses: HashMap<String, TaskStatus> = rows
+            .into_iter()
+            .map(|r| (r.id, TaskStatus::from(r.status)))
+            .collect();
+
+        Ok(ids.iter().map(|id| statuses[id]).collect())
+    }
+
+    /// 同じツリーを二人のユーザーに作り、それぞれの実装で祖先の状態を更新して
+    /// 結果と実行時間を返す
+    async fn run_both(
+        db: &Db,
+        nodes: &[TreeNode],
+        start_indexes: &[usize],
+    ) -> AppResult<((Vec<TaskStatus>, Duration), (Vec<TaskStatus>, Duration))> {
+        let new_user = user_factory::create_default(db).await?;
+        let new_ids = create_tree(db, &new_user.id, nodes).await?;
+        let legacy_user = user_factory::create_default(db).await?;
+        let legacy_ids = create_tree(db, &legacy_user.id, nodes).await?;
+
+        let mut conn = db.acquire().await?;
+
+        let started = Instant::now();
+        for &i in start_indexes {
+            update_task_and_all_ancestor_main_tasks_status(
+                &mut conn,
+                TaskAndUser {
+                    task_id: &new_ids[i],
+                    user_id: &new_user.id,
+                },
+            )
+            .await?;
+        }
+        let new_elapsed = started.elapsed();
+
+        let started = Instant::now();
+        for &i in start_indexes {
+            legacy_update_task_and_all_ancestor_main_tasks_status(
+                &mut conn,
+                TaskAndUser {
+                    task_id: &legacy_ids[i],
+                    user_id: &legacy_user.id,
+                },
+            )
+            .await?;
+        }
+        let legacy_elapsed = started.elapsed();
+
+        Ok((
+            (
+                find_statuses(db, &new_user.id, &new_ids).await?,
+                new_elapsed,
+            ),
+            (
+                find_statuses(db, &legacy_user.id, &legacy_ids).await?,
+                legacy_elapsed,
+            ),
+        ))
+    }
+
+    #[sqlx::test]
+    async fn 以前の実装と同じように祖先メインタスクの状態が更新される(
+        db: Db,
+    ) -> AppResult<()> {
+        let mut rng = Lcg(42);
+        let statuses = [TaskStatus::Todo, TaskStatus::Done];
+        let modes = [
+            TaskRollupMode::Auto,
+            TaskRollupMode::Auto,
+            TaskRollupMode::Manual,
+            TaskRollupMode::AutoReopenOnly,
+        ];
+        let nodes: Vec<TreeNode> = (0..300)
+            .map(|i| TreeNode {
+                // 1割くらいをルートにして、複数のツリーを作る
+                parent: (i > 0 && rng.next(10) != 0).then(|| rng.next(i)),
+                status: statuses[rng.next(statuses.len())],
+                rollup_mode: modes[rng.next(modes.len())],
+            })
+            .collect();
+        let start_indexes: Vec<usize> = (0..30).map(|_| rng.next(nodes.len())).collect();
+
+        let ((new_statuses, _), (legacy_statuses, _)) =
+            run_both(&db, &nodes, &start_indexes).await?;
+        assert_eq!(new_statuses, legacy_statuses);
+
+        Ok(())
+    }
+
+    // 時間がかかるので通常は実行しない。
+    // `cargo test bench_ -- --ignored --nocapture`で実行する。
+    #[sqlx::test]
+    #[ignore]
+    async fn bench_1000階層の直列なツリー(db: Db) -> AppResult<()> {
+        let depth: usize = 1000;
+        let nodes: Vec<TreeNode> = (0..depth)
+            .map(|i| TreeNode {
+                parent: i.checked_sub(1),
+                status: if i == depth - 1 {
+                    TaskStatus::Done
+                } else {
+                    TaskStatus::Todo
+                },
+                rollup_mode: TaskRollupMode::Auto,
+            })
+            .collect();
+
+        let ((new_statuses, new_elapsed), (legacy_statuses, legacy_elapsed)) =
+            run_both(&db, &nodes, &[depth - 1]).await?;
+        println!("chain({depth}): set-based={new_elapsed:?}, legacy={legacy_elapsed:?}");
+
+        assert!(new_statuses.iter().all(|s| *s == TaskStatus::Done));
+        assert_eq!(new_statuses, legacy_statuses);
+        assert!(new_elapsed < legacy_elapsed);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn bench_10000ノードのツリー(db: Db) -> AppResult<()> {
+        let size: usize = 10000;
+        let nodes: Vec<TreeNode> = (0..size)
+            .map(|i| TreeNode {
+                parent: i.checked_sub(1).map(|p| p / 10),
+                // 葉だけを完了状態にする
+                status: if i * 10 + 1 >= size {
+                    TaskStatus::Done
+                } else {
+                    TaskStatus::Todo
+                },
+                rollup_mode: TaskRollupMode::Auto,
+            })
+            .collect();
+        let start_indexes: Vec<usize> = (size - 100..size).collect();
+
+        let ((new_statuses, new_elapsed), (legacy_statuses, legacy_elapsed)) =
+            run_both(&db, &nodes, &start_indexes).await?;
+        println!("tree({size}): set-based={new_elapsed:?}, legacy={legacy_elapsed:?}");
+
+        assert_eq!(new_statuses, legacy_statuses);
+        assert!(new_elapsed < legacy_elapsed);
+
+        Ok(())
+    }
 }
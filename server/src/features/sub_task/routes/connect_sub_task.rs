@@ -1,13 +1,9 @@
-use anyhow::anyhow;
 use axum::{extract::State, response::IntoResponse, Json};
 use axum_login::AuthSession;
-use http::StatusCode;
-use serde::Serialize;
-use utoipa::ToSchema;
 
 use crate::{
     app::{AppResult, AppState},
-    error::AppError,
+    error::{AppError, ErrorCode, ProblemDetails},
     features::{
         auth::Auth,
         sub_task::{
@@ -18,19 +14,6 @@ use crate::{
     },
 };
 
-#[derive(Debug, Serialize, ToSchema)]
-pub enum ConnectSubTaskErrorType {
-    TaskNotFound,
-    CircularTask,
-    MultipleMainTask,
-    BlockedByMainTask,
-}
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct ConnectSubTaskErrorBody {
-    error_type: ConnectSubTaskErrorType,
-}
-
 #[tracing::instrument(err)]
 #[utoipa::path(
     post,
@@ -38,12 +21,15 @@ pub struct ConnectSubTaskErrorBody {
     path = super::SubTaskPaths::connect_sub_task(),
     responses(
         (status = 200),
-        (status = 400, body = ConnectSubTaskErrorBody)
+        (status = 400, response = ProblemDetails),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
     )
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, task_graph, .. }): State<AppState>,
     Json(payload): Json<ConnectSubTask>,
 ) -> AppResult<impl IntoResponse> {
     let Some(user) = auth_session.user else {
@@ -51,6 +37,7 @@ pub async fn handler(
     };
 
     let mut tx = db.begin().await?;
+    let graph = task_graph.get_or_load(&mut tx, &user.id).await?;
 
     if let Err(e) = connect_sub_task::action(
         &mut tx,
@@ -58,29 +45,25 @@ pub async fn handler(
             main_task_id: &payload.main_task_id,
             sub_task_id: &payload.sub_task_id,
             user_id: &user.id,
+            graph: Some(&graph),
         },
     )
     .await
     {
         use ConnectSubTaskError::{CheckError, Unknown};
-        use ConnectSubTaskErrorType::{
-            BlockedByMainTask, CircularTask, MultipleMainTask, TaskNotFound,
-        };
+        use ErrorCode::{BlockedByMainTask, CircularConnection, MultipleMainTask, TaskNotFound};
 
-        let error_type = match e {
+        let code = match e {
             CheckError(SubTaskConnectionError::TaskNotFound) => TaskNotFound,
-            CheckError(SubTaskConnectionError::CircularTask) => CircularTask,
+            CheckError(SubTaskConnectionError::CircularTask) => CircularConnection,
             CheckError(SubTaskConnectionError::MultipleMainTask) => MultipleMainTask,
             CheckError(SubTaskConnectionError::BlockedByMainTask) => BlockedByMainTask,
-            CheckError(SubTaskConnectionError::Unknown(_)) | Unknown(_) => {
-                return Err(anyhow!("Unknown").into());
+            CheckError(SubTaskConnectionError::Unknown(e)) | Unknown(e) => {
+                return Err(e.into());
             }
         };
 
-        return Err(AppError::with_json(
-            StatusCode::BAD_REQUEST,
-            ConnectSubTaskErrorBody { error_type },
-        ));
+        return Err(AppError::with_code(code));
     };
 
     tx.commit().await?;
@@ -91,6 +74,7 @@ pub async fn handler(
 #[cfg(test)]
 mod tests {
     use crate::app::{tests::AppTest, AppResult, Db};
+    use crate::error::{ErrorCode, ProblemDetails};
     use crate::features::sub_task::routes::SubTaskPaths;
     use crate::features::sub_task::ConnectSubTask;
     use crate::features::task::db::{find_task, FindTaskArgs};
@@ -208,6 +192,8 @@ mod tests {
             })
             .await;
         res.assert_status_not_ok();
+        let problem: ProblemDetails = res.json();
+        assert_eq!(problem.code, ErrorCode::CircularConnection);
 
         let sub_tasks = sqlx::query!(
             "SELECT * FROM sub_tasks WHERE main_task_id = $1 AND sub_task_id = $2",
@@ -1,13 +1,9 @@
-use anyhow::anyhow;
 use axum::{extract::State, Json};
 use axum_login::AuthSession;
-use http::StatusCode;
-use serde::Serialize;
-use utoipa::ToSchema;
 
 use crate::{
     app::{AppResult, AppState},
-    error::AppError,
+    error::{AppError, ErrorCode, ProblemDetails},
     features::{
         auth::Auth,
         sub_task::{
@@ -18,19 +14,6 @@ use crate::{
     },
 };
 
-#[derive(Debug, Serialize, ToSchema)]
-pub enum ReconnectSubTaskErrorType {
-    TaskNotFound,
-    BlockedByMainTask,
-    CircularTask,
-    MultipleMainTask,
-}
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct ReconnectSubTaskErrorBody {
-    error_type: ReconnectSubTaskErrorType,
-}
-
 #[tracing::instrument(err)]
 #[utoipa::path(
     put,
@@ -38,12 +21,15 @@ pub struct ReconnectSubTaskErrorBody {
     path = super::SubTaskPaths::reconnect_sub_task(),
     responses(
         (status = 200),
-        (status = 400, body = ReconnectSubTaskErrorBody)
+        (status = 400, response = ProblemDetails),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
     )
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, task_graph, .. }): State<AppState>,
     Json(payload): Json<ReconnectSubTask>,
 ) -> AppResult<()> {
     let Some(user) = auth_session.user else {
@@ -51,6 +37,7 @@ pub async fn handler(
     };
 
     let mut tx = db.begin().await?;
+    let graph = task_graph.get_or_load(&mut tx, &user.id).await?;
 
     let result = reconnect_sub_task::action(
         &mut tx,
@@ -60,29 +47,25 @@ pub async fn handler(
             new_main_task_id: &payload.new_main_task_id,
             new_sub_task_id: &payload.new_sub_task_id,
             user_id: &user.id,
+            graph: Some(&graph),
         },
     )
     .await;
     if let Err(e) = result {
+        use ErrorCode::{BlockedByMainTask, CircularConnection, MultipleMainTask, TaskNotFound};
         use ReconnectSubTaskError::{Connect, Unknown};
-        use ReconnectSubTaskErrorType::{
-            BlockedByMainTask, CircularTask, MultipleMainTask, TaskNotFound,
-        };
 
-        let error_type = match e {
+        let code = match e {
             Connect(SubTaskConnectionError::TaskNotFound) => TaskNotFound,
             Connect(SubTaskConnectionError::BlockedByMainTask) => BlockedByMainTask,
-            Connect(SubTaskConnectionError::CircularTask) => CircularTask,
+            Connect(SubTaskConnectionError::CircularTask) => CircularConnection,
             Connect(SubTaskConnectionError::MultipleMainTask) => MultipleMainTask,
-            Connect(SubTaskConnectionError::Unknown(_)) | Unknown(_) => {
-                return Err(anyhow!("Unknown").into())
+            Connect(SubTaskConnectionError::Unknown(e)) | Unknown(e) => {
+                return Err(e.into());
             }
         };
 
-        return Err(AppError::with_json(
-            StatusCode::BAD_REQUEST,
-            ReconnectSubTaskErrorBody { error_type },
-        ));
+        return Err(AppError::with_code(code));
     }
 
     tx.commit().await?;
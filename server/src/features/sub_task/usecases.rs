@@ -1,3 +1,4 @@
 pub mod connect_sub_task;
 pub mod disconnect_sub_task;
 pub mod reconnect_sub_task;
+pub mod update_task_and_ancestors_status;
@@ -1,9 +1,14 @@
 use crate::{
     app::Connection,
-    features::sub_task::db::{
-        check_sub_task_connection, insert_sub_task_connection,
-        update_all_ancestor_main_tasks_status, InsertSubTaskConnectionArgs, SubTaskConnectionError,
-        TaskAndUser,
+    features::{
+        sub_task::{
+            db::{
+                check_sub_task_connection, insert_sub_task_connection, InsertSubTaskConnectionArgs,
+                SubTaskConnectionError, TaskAndUser,
+            },
+            usecases::update_task_and_ancestors_status,
+        },
+        task::graph::TaskGraph,
     },
 };
 
@@ -11,6 +16,8 @@ pub struct ConnectSubTaskArgs<'a> {
     pub main_task_id: &'a str,
     pub sub_task_id: &'a str,
     pub user_id: &'a str,
+    /// 接続前のタスクのグラフ
+    pub graph: Option<&'a TaskGraph>,
 }
 
 pub enum ConnectSubTaskError {
@@ -28,7 +35,7 @@ pub async fn action<'a>(
         user_id: args.user_id,
     };
 
-    check_sub_task_connection(db, &insert_args)
+    check_sub_task_connection(db, args.graph, &insert_args)
         .await
         .map_err(ConnectSubTaskError::CheckError)?;
 
@@ -36,10 +43,10 @@ pub async fn action<'a>(
         .await
         .map_err(ConnectSubTaskError::Unknown)?;
 
-    update_all_ancestor_main_tasks_status(
+    update_task_and_ancestors_status::action(
         db,
         TaskAndUser {
-            task_id: args.sub_task_id,
+            task_id: args.main_task_id,
             user_id: args.user_id,
         },
     )
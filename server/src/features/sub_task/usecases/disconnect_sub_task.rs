@@ -1,9 +1,11 @@
 use crate::{
     app::Connection,
-    features::sub_task::db::{
-        delete_sub_task_connection, find_main_task_id,
-        update_task_and_all_ancestor_main_tasks_status, DeleteSubTaskConnectionArgs,
-        FindMainTaskIdsArgs, TaskAndUser,
+    features::sub_task::{
+        db::{
+            delete_sub_task_connection, find_main_task_id, DeleteSubTaskConnectionArgs,
+            FindMainTaskIdsArgs, TaskAndUser,
+        },
+        usecases::update_task_and_ancestors_status,
     },
 };
 
@@ -38,7 +40,7 @@ pub async fn action<'a>(
 
     if let Some(id) = main_task_id {
         // 接続を切り離したサブタスクのすべての祖先メインタスクの状態を更新する
-        update_task_and_all_ancestor_main_tasks_status(
+        update_task_and_ancestors_status::action(
             &mut *db,
             TaskAndUser {
                 task_id: &id,
@@ -1,7 +1,8 @@
 use crate::{
     app::Connection,
-    features::sub_task::{
-        db::SubTaskConnectionError, usecases::connect_sub_task::ConnectSubTaskError,
+    features::{
+        sub_task::{db::SubTaskConnectionError, usecases::connect_sub_task::ConnectSubTaskError},
+        task::graph::TaskGraph,
     },
 };
 
@@ -16,6 +17,8 @@ pub struct ReconnectSubTaskArgs<'a> {
     pub new_main_task_id: &'a str,
     pub new_sub_task_id: &'a str,
     pub user_id: &'a str,
+    /// つなぎ直す前のタスクのグラフ
+    pub graph: Option<&'a TaskGraph>,
 }
 
 pub enum ReconnectSubTaskError {
@@ -38,12 +41,20 @@ pub async fn action<'a>(
     .await
     .map_err(ReconnectSubTaskError::Unknown)?;
 
+    // 接続を切り離したあとのグラフで、新しい接続を確認する
+    let graph = args.graph.map(|graph| {
+        let mut graph = graph.clone();
+        graph.remove_sub_task_edge(args.old_main_task_id, args.old_sub_task_id);
+        graph
+    });
+
     connect_sub_task::action(
         db,
         ConnectSubTaskArgs {
             main_task_id: args.new_main_task_id,
             sub_task_id: args.new_sub_task_id,
             user_id: args.user_id,
+            graph: graph.as_ref(),
         },
     )
     .await
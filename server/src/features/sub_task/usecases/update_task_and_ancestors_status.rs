@@ -0,0 +1,34 @@
+use crate::{
+    app::Connection,
+    features::{
+        recurrence::usecases::create_next_occurrences::{
+            self, CreateNextOccurrencesArgs, NextOccurrence,
+        },
+        sub_task::db::{update_task_and_all_ancestor_main_tasks_status, TaskAndUser},
+    },
+};
+
+/// タスクとそのすべての祖先メインタスクの状態をサブタスクに合わせて更新し、
+/// 完了状態になった繰り返しのタスクがあれば次のタスクを作成する。
+/// タスクの状態が変わる操作のあとは、この関数を通して状態を確定させる。
+pub async fn action<'a>(
+    db: &mut Connection,
+    args: TaskAndUser<'a>,
+) -> anyhow::Result<Vec<NextOccurrence>> {
+    update_task_and_all_ancestor_main_tasks_status(
+        &mut *db,
+        TaskAndUser {
+            task_id: args.task_id,
+            user_id: args.user_id,
+        },
+    )
+    .await?;
+
+    create_next_occurrences::action(
+        &mut *db,
+        CreateNextOccurrencesArgs {
+            user_id: args.user_id,
+        },
+    )
+    .await
+}
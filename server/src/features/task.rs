@@ -1,6 +1,9 @@
 pub mod db;
+pub mod graph;
 pub mod routes;
 pub mod test;
+pub mod usecases;
+use crate::validation;
 use garde::Validate;
 pub use routes::router;
 use serde::{Deserialize, Serialize};
@@ -12,13 +15,34 @@ use utoipa::ToSchema;
 pub struct Task {
     pub id: String,
     pub status: TaskStatus,
+    pub rollup_mode: TaskRollupMode,
     pub title: String,
     pub description: String,
     pub user_id: String,
     pub sub_task_ids: Vec<String>,
     pub blocked_task_ids: Vec<String>,
+    /// このタスクをブロックしているタスク
+    pub blocking_task_ids: Vec<String>,
+    /// タスク自身か祖先タスクが、未完了のタスクにブロックされているか
+    pub is_blocked: bool,
+    /// 子孫サブタスクの数
+    pub descendant_count: i64,
+    /// 完了状態の子孫サブタスクの数
+    pub done_descendant_count: i64,
+    /// 子孫サブタスクの完了率(%)
+    pub progress: f64,
+    /// チェックリストの項目の数
+    pub checklist_count: i64,
+    /// チェック済みのチェックリストの項目の数
+    pub checked_checklist_count: i64,
+    /// 削除されていないコメントの数
+    pub comment_count: i64,
+    /// タスク自身の見積もり時間(分)。見積もっていない場合はNone
+    pub estimated_minutes: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
+    /// 完了状態になった日時。未完了の場合はNone
+    pub completed_at: Option<String>,
 }
 
 #[derive(
@@ -35,20 +59,39 @@ impl From<String> for TaskStatus {
     }
 }
 
+/// サブタスクの状態からメインタスクの状態をどう更新するか
+#[derive(
+    Serialize, Deserialize, ToSchema, EnumString, sqlx::Type, Debug, PartialEq, Clone, Copy, Default,
+)]
+pub enum TaskRollupMode {
+    /// すべてのサブタスクが完了したら完了、そうでなければ未完了にする
+    #[default]
+    Auto,
+    /// サブタスクの状態によって変更しない
+    Manual,
+    /// サブタスクが未完了になったときだけ未完了にする
+    AutoReopenOnly,
+}
+impl From<String> for TaskRollupMode {
+    fn from(value: String) -> Self {
+        TaskRollupMode::from_str(value.as_str()).unwrap_or(TaskRollupMode::Auto)
+    }
+}
+
 #[derive(Serialize, Deserialize, ToSchema, Debug, Validate)]
 pub struct CreateTask {
-    #[garde(length(min = 1, max = 100))]
+    #[garde(custom(validation::length(1, 100)))]
     #[schema(min_length = 1, max_length = 100)]
     pub title: String,
 }
 
 #[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
 pub struct UpdateTask {
-    #[garde(length(min = 1, max = 100))]
+    #[garde(custom(validation::length(1, 100)))]
     #[schema(min_length = 1, max_length = 100)]
     pub title: String,
 
-    #[garde(length(max = 2000))]
+    #[garde(custom(validation::length(0, 2000)))]
     #[schema(max_length = 2000)]
     pub description: String,
 }
@@ -58,6 +101,35 @@ pub struct UpdateTaskStatus {
     pub status: TaskStatus,
 }
 
+#[derive(Deserialize, Serialize, ToSchema, Debug)]
+pub struct UpdateTaskRollupMode {
+    pub rollup_mode: TaskRollupMode,
+}
+
+/// タスクの状態の遷移履歴
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct StatusTransition {
+    pub id: i64,
+    pub task_id: String,
+    /// タスクが作成されたときの遷移はNone
+    pub from_status: Option<TaskStatus>,
+    pub to_status: TaskStatus,
+    pub transitioned_at: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Default)]
+pub struct DuplicateTask {
+    /// trueの場合は、子孫サブタスクもすべて複製する
+    #[serde(default)]
+    pub include_descendants: bool,
+    /// trueの場合は、複製したタスク同士のブロッキングタスクのつながりも複製する
+    #[serde(default)]
+    pub copy_blocking_connections: bool,
+    /// trueの場合は、複製したタスクの状態をすべて未完了にする
+    #[serde(default)]
+    pub reset_status: bool,
+}
+
 #[derive(Serialize, Deserialize, ToSchema, Debug)]
 pub struct DeleteTaskResponse {
     pub task_id: String,
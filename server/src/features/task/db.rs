@@ -1,10 +1,13 @@
 use anyhow::anyhow;
-use sqlx::{Execute, QueryBuilder, Row, Sqlite};
+use sqlx::{Execute, QueryBuilder, Sqlite};
 use std::collections::HashMap;
 
-use crate::app::Connection;
+use crate::{app::Connection, features::block_task::db::is_all_blocking_tasks_done};
 
-use super::{Task, TaskStatus};
+use super::{
+    graph::{calc_progress, DescendantCount, TaskGraph},
+    StatusTransition, Task, TaskRollupMode, TaskStatus,
+};
 
 pub struct FindTaskArgs<'a> {
     pub user_id: &'a str,
@@ -14,97 +17,276 @@ pub async fn find_task<'a>(
     db: &mut Connection,
     FindTaskArgs { user_id, task_id }: FindTaskArgs<'a>,
 ) -> anyhow::Result<Task> {
-    let raw_task = sqlx::query!(
+    let raw = sqlx::query!(
+        "SELECT * FROM tasks WHERE user_id = $1 AND id = $2;",
+        user_id,
+        task_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?
+    .ok_or(anyhow!("Error"))?;
+
+    let mut task = Task {
+        id: raw.id,
+        title: raw.title,
+        status: raw.status.into(),
+        rollup_mode: raw.rollup_mode.into(),
+        user_id: raw.user_id,
+        description: raw.description,
+        created_at: raw.created_at,
+        updated_at: raw.updated_at,
+        completed_at: raw.completed_at,
+        sub_task_ids: Vec::new(),
+        blocked_task_ids: Vec::new(),
+        blocking_task_ids: Vec::new(),
+        is_blocked: false,
+        descendant_count: 0,
+        done_descendant_count: 0,
+        progress: 0.0,
+        checklist_count: 0,
+        checked_checklist_count: 0,
+        comment_count: 0,
+        estimated_minutes: raw.estimated_minutes,
+    };
+
+    // サブタスクとブロックしているタスクを一緒にJOINすると組み合わせの数だけ行が増えてしまうので、
+    // つながりはそれぞれ別のクエリで取得する
+    let sub_tasks = sqlx::query!(
         r#"
-        SELECT t.*, s.main_task_id, s.sub_task_id, b.blocked_task_id
-        FROM tasks t 
-        LEFT OUTER JOIN sub_tasks s ON (t.id = s.main_task_id AND t.user_id = s.user_id)
-        LEFT OUTER JOIN blocking_tasks b ON (t.id = b.blocking_task_id AND t.user_id = b.user_id)
-        WHERE t.user_id = $1 AND t.id = $2;
+        SELECT sub_task_id
+        FROM sub_tasks
+        WHERE main_task_id = $1 AND user_id = $2
+        ORDER BY sub_task_id;
         "#,
+        task_id,
         user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+    task.sub_task_ids = sub_tasks.into_iter().map(|r| r.sub_task_id).collect();
+
+    let blocked_tasks = sqlx::query!(
+        r#"
+        SELECT blocked_task_id
+        FROM blocking_tasks
+        WHERE blocking_task_id = $1 AND user_id = $2
+        ORDER BY blocked_task_id;
+        "#,
         task_id,
+        user_id,
     )
     .fetch_all(&mut *db)
     .await?;
+    task.blocked_task_ids = blocked_tasks
+        .into_iter()
+        .map(|r| r.blocked_task_id)
+        .collect();
 
-    // 実際にはTaskは一つになるけど、いい感じに書く方法が思いつかなかったのでHashMapを使う
-    let mut task_map: HashMap<String, Task> = HashMap::new();
-    for raw in raw_task {
-        let task = task_map.entry(raw.id.clone()).or_insert_with(|| Task {
-            id: raw.id,
-            title: raw.title,
-            status: raw.status.into(),
-            user_id: raw.user_id,
-            description: raw.description,
-            created_at: raw.created_at,
-            updated_at: raw.updated_at,
-            sub_task_ids: Vec::new(),
-            blocked_task_ids: Vec::new(),
-        });
-        if let Some(sub_task_id) = raw.sub_task_id {
-            task.sub_task_ids.push(sub_task_id);
-        }
-        if let Some(blocked_task_id) = raw.blocked_task_id {
-            task.blocked_task_ids.push(blocked_task_id);
-        }
-    }
-    let task = task_map
+    // 一つのタスクのためにユーザーのすべてのタスクを読み込みたくないので、find_tasksとは違ってSQLで求める
+    let blocking_tasks = sqlx::query!(
+        r#"
+        SELECT blocking_task_id
+        FROM blocking_tasks
+        WHERE blocked_task_id = $1 AND user_id = $2
+        ORDER BY blocking_task_id;
+        "#,
+        task_id,
+        user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+    task.blocking_task_ids = blocking_tasks
         .into_iter()
-        .next()
-        .map(|(_, mut t)| {
-            t.sub_task_ids.sort();
-            t.sub_task_ids.dedup();
-            t.blocked_task_ids.sort();
-            t.blocked_task_ids.dedup();
-            t
-        })
-        .ok_or(anyhow!("Error"))?;
+        .map(|r| r.blocking_task_id)
+        .collect();
+
+    task.is_blocked = !is_all_blocking_tasks_done(&mut *db, task_id).await?;
+
+    let descendant_count = sqlx::query!(
+        r#"
+        WITH RECURSIVE descendants AS (
+            SELECT sub_task_id
+            FROM sub_tasks
+            WHERE main_task_id = $1 AND user_id = $2
+
+            UNION
+
+            SELECT s.sub_task_id
+            FROM sub_tasks s
+            JOIN descendants d ON s.main_task_id = d.sub_task_id
+        )
+
+        SELECT
+            COUNT(*) as "total!: i64",
+            COALESCE(SUM(CASE WHEN t.status = 'Done' THEN 1 ELSE 0 END), 0) as "done!: i64"
+        FROM descendants d
+        JOIN tasks t ON d.sub_task_id = t.id
+        "#,
+        task_id,
+        user_id,
+    )
+    .fetch_one(&mut *db)
+    .await?;
+    let descendant_count = DescendantCount {
+        total: descendant_count.total,
+        done: descendant_count.done,
+    };
+    task.descendant_count = descendant_count.total;
+    task.done_descendant_count = descendant_count.done;
+    task.progress = calc_progress(task.status, descendant_count);
+
+    let checklist_count = sqlx::query!(
+        r#"
+        SELECT
+            COUNT(*) as "total!: i64",
+            COALESCE(SUM(CASE WHEN checked THEN 1 ELSE 0 END), 0) as "checked!: i64"
+        FROM checklist_items
+        WHERE task_id = $1 AND user_id = $2
+        "#,
+        task_id,
+        user_id,
+    )
+    .fetch_one(&mut *db)
+    .await?;
+    task.checklist_count = checklist_count.total;
+    task.checked_checklist_count = checklist_count.checked;
+
+    let comment_count = sqlx::query!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM task_comments
+        WHERE task_id = $1 AND user_id = $2 AND deleted_at IS NULL
+        "#,
+        task_id,
+        user_id,
+    )
+    .fetch_one(&mut *db)
+    .await?;
+    task.comment_count = comment_count.count;
 
     Ok(task)
 }
 
 pub async fn find_tasks(db: &mut Connection, user_id: &str) -> anyhow::Result<Vec<Task>> {
-    let raw_tasks = sqlx::query!(
+    let raw_tasks = sqlx::query!("SELECT * FROM tasks WHERE user_id = $1;", user_id)
+        .fetch_all(&mut *db)
+        .await?;
+
+    // サブタスクとブロックしているタスクを一緒にJOINすると組み合わせの数だけ行が増えてしまうので、
+    // つながりはそれぞれ別のクエリでまとめて読み込む
+    let sub_task_edges = sqlx::query!(
+        "SELECT main_task_id, sub_task_id FROM sub_tasks WHERE user_id = $1;",
+        user_id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+    let blocking_edges = sqlx::query!(
+        "SELECT blocking_task_id, blocked_task_id FROM blocking_tasks WHERE user_id = $1;",
+        user_id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let mut task_map: HashMap<String, Task> = raw_tasks
+        .into_iter()
+        .map(|raw| {
+            let task = Task {
+                id: raw.id,
+                title: raw.title,
+                status: raw.status.into(),
+                rollup_mode: raw.rollup_mode.into(),
+                description: raw.description,
+                user_id: raw.user_id,
+                created_at: raw.created_at,
+                updated_at: raw.updated_at,
+                completed_at: raw.completed_at,
+                sub_task_ids: Vec::new(),
+                blocked_task_ids: Vec::new(),
+                blocking_task_ids: Vec::new(),
+                is_blocked: false,
+                descendant_count: 0,
+                done_descendant_count: 0,
+                progress: 0.0,
+                checklist_count: 0,
+                checked_checklist_count: 0,
+                comment_count: 0,
+                estimated_minutes: raw.estimated_minutes,
+            };
+            (task.id.clone(), task)
+        })
+        .collect();
+    for edge in &blocking_edges {
+        if let Some(task) = task_map.get_mut(&edge.blocking_task_id) {
+            task.blocked_task_ids.push(edge.blocked_task_id.clone());
+        }
+    }
+
+    let checklist_counts = sqlx::query!(
         r#"
-        SELECT t.*, s.main_task_id, s.sub_task_id, b.blocked_task_id
-        FROM tasks t 
-        LEFT OUTER JOIN sub_tasks s ON (t.id = s.main_task_id AND t.user_id = s.user_id)
-        LEFT OUTER JOIN blocking_tasks b ON (t.id = b.blocking_task_id AND t.user_id = b.user_id)
-        WHERE t.user_id = $1;
+        SELECT
+            task_id,
+            COUNT(*) as "total!: i64",
+            COALESCE(SUM(CASE WHEN checked THEN 1 ELSE 0 END), 0) as "checked!: i64"
+        FROM checklist_items
+        WHERE user_id = $1
+        GROUP BY task_id;
         "#,
         user_id
     )
     .fetch_all(&mut *db)
     .await?;
-
-    let mut task_map: HashMap<String, Task> = HashMap::new();
-    for raw in raw_tasks {
-        let task = task_map.entry(raw.id.clone()).or_insert_with(|| Task {
-            id: raw.id,
-            title: raw.title,
-            status: raw.status.into(),
-            description: raw.description,
-            user_id: raw.user_id,
-            created_at: raw.created_at,
-            updated_at: raw.updated_at,
-            sub_task_ids: Vec::new(),
-            blocked_task_ids: Vec::new(),
-        });
-        if let Some(sub_task_id) = raw.sub_task_id {
-            task.sub_task_ids.push(sub_task_id);
+    for count in checklist_counts {
+        if let Some(task) = task_map.get_mut(&count.task_id) {
+            task.checklist_count = count.total;
+            task.checked_checklist_count = count.checked;
         }
-        if let Some(blocked_task_id) = raw.blocked_task_id {
-            task.blocked_task_ids.push(blocked_task_id);
+    }
+
+    let comment_counts = sqlx::query!(
+        r#"
+        SELECT task_id, COUNT(*) as "count!: i64"
+        FROM task_comments
+        WHERE user_id = $1 AND deleted_at IS NULL
+        GROUP BY task_id;
+        "#,
+        user_id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+    for count in comment_counts {
+        if let Some(task) = task_map.get_mut(&count.task_id) {
+            task.comment_count = count.count;
         }
     }
+
+    // ブロックされているかや子孫サブタスクの数はタスクごとに再帰クエリを発行せず、メモリ上で求める
+    let graph = TaskGraph::new(
+        task_map.values().map(|t| (t.id.clone(), t.status)),
+        sub_task_edges
+            .into_iter()
+            .map(|e| (e.main_task_id, e.sub_task_id)),
+        blocking_edges
+            .into_iter()
+            .map(|e| (e.blocking_task_id, e.blocked_task_id)),
+    );
+    let blocked_flags = graph.blocked_flags();
+    let descendant_counts = graph.descendant_counts();
+
     let tasks: Vec<Task> = task_map
         .into_values()
         .map(|mut t| {
+            t.sub_task_ids = graph.sub_task_ids(&t.id).to_vec();
             t.sub_task_ids.sort();
-            t.sub_task_ids.dedup();
             t.blocked_task_ids.sort();
-            t.blocked_task_ids.dedup();
+
+            t.blocking_task_ids = graph.blocking_task_ids(&t.id).to_vec();
+            t.blocking_task_ids.sort();
+            t.is_blocked = blocked_flags.get(&t.id).copied().unwrap_or(false);
+
+            let descendant_count = descendant_counts.get(&t.id).copied().unwrap_or_default();
+            t.descendant_count = descendant_count.total;
+            t.done_descendant_count = descendant_count.done;
+            t.progress = calc_progress(t.status, descendant_count);
             t
         })
         .collect();
@@ -112,31 +294,90 @@ pub async fn find_tasks(db: &mut Connection, user_id: &str) -> anyhow::Result<Ve
     Ok(tasks)
 }
 
-pub async fn is_all_tasks_done(
-    db: &mut Connection,
-    task_ids: &Vec<String>,
-) -> anyhow::Result<bool> {
-    if task_ids.is_empty() {
-        return Ok(true);
-    }
+/// ユーザーのタスクの状態やつながりが変更されるたびに増える番号を取得する
+pub async fn find_task_graph_version(db: &mut Connection, user_id: &str) -> anyhow::Result<i64> {
+    let result = sqlx::query!(
+        "SELECT version FROM task_graph_versions WHERE user_id = $1;",
+        user_id
+    )
+    .fetch_optional(&mut *db)
+    .await?;
 
-    let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
-        "SELECT count(*)
-        FROM tasks
-        WHERE status <> 'Done' AND id IN (",
-    );
+    Ok(result.map_or(0, |r| r.version))
+}
 
-    let mut separated = query_builder.separated(", ");
-    for id in task_ids {
-        separated.push_bind(id);
+/// ユーザーのすべてのタスクの状態とつながりを読み込む
+pub async fn load_task_graph(db: &mut Connection, user_id: &str) -> anyhow::Result<TaskGraph> {
+    let statuses = sqlx::query!("SELECT id, status FROM tasks WHERE user_id = $1;", user_id)
+        .fetch_all(&mut *db)
+        .await?;
+    let sub_task_edges = sqlx::query!(
+        "SELECT main_task_id, sub_task_id FROM sub_tasks WHERE user_id = $1;",
+        user_id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+    let blocking_edges = sqlx::query!(
+        "SELECT blocking_task_id, blocked_task_id FROM blocking_tasks WHERE user_id = $1;",
+        user_id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    Ok(TaskGraph::new(
+        statuses
+            .into_iter()
+            .map(|r| (r.id, TaskStatus::from(r.status))),
+        sub_task_edges
+            .into_iter()
+            .map(|e| (e.main_task_id, e.sub_task_id)),
+        blocking_edges
+            .into_iter()
+            .map(|e| (e.blocking_task_id, e.blocked_task_id)),
+    ))
+}
+
+/// タスクの状態の遷移履歴を返す。タスクが存在しないか他のユーザーのタスクの場合はNoneを返す
+pub async fn find_status_transitions<'a>(
+    db: &mut Connection,
+    FindTaskArgs { user_id, task_id }: FindTaskArgs<'a>,
+) -> anyhow::Result<Option<Vec<StatusTransition>>> {
+    let task = sqlx::query!(
+        "SELECT id FROM tasks WHERE id = $1 AND user_id = $2;",
+        task_id,
+        user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+    if task.is_none() {
+        return Ok(None);
     }
-    separated.push_unseparated(") ");
 
-    let query = query_builder.build();
-    let result = query.fetch_one(&mut *db).await?;
+    let raw_transitions = sqlx::query!(
+        r#"
+        SELECT id, task_id, from_status, to_status, transitioned_at
+        FROM status_transitions
+        WHERE user_id = $1 AND task_id = $2
+        ORDER BY id;
+        "#,
+        user_id,
+        task_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let transitions = raw_transitions
+        .into_iter()
+        .map(|raw| StatusTransition {
+            id: raw.id,
+            task_id: raw.task_id,
+            from_status: raw.from_status.map(|s| s.into()),
+            to_status: raw.to_status.into(),
+            transitioned_at: raw.transitioned_at,
+        })
+        .collect();
 
-    let count: i32 = result.get(0);
-    Ok(count == 0)
+    Ok(Some(transitions))
 }
 
 pub struct InsertTaskArgs<'a> {
@@ -173,6 +414,42 @@ pub async fn insert_task<'a>(
     Ok(task)
 }
 
+pub struct CopyTaskArgs<'a> {
+    pub source_task_id: &'a str,
+    pub new_task_id: &'a str,
+    pub user_id: &'a str,
+    /// trueの場合は、コピー元の状態に関わらず未完了にする
+    pub reset_status: bool,
+}
+/// タスクのタイトルや説明、状態、見積もり時間などをコピーして新しいタスクを作成する。
+/// サブタスクやブロッキングタスクとのつながりはコピーしない。
+pub async fn copy_task<'a>(db: &mut Connection, args: CopyTaskArgs<'a>) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO tasks(id, title, description, user_id, status, rollup_mode, estimated_minutes)
+        SELECT
+            $1,
+            title,
+            description,
+            user_id,
+            CASE WHEN $2 THEN 'Todo' ELSE status END,
+            rollup_mode,
+            estimated_minutes
+        FROM tasks
+        WHERE id = $3 AND user_id = $4
+        RETURNING id;
+        "#,
+        args.new_task_id,
+        args.reset_status,
+        args.source_task_id,
+        args.user_id,
+    )
+    .fetch_one(&mut *db)
+    .await?;
+
+    Ok(())
+}
+
 pub struct DeleteTaskArgs<'a> {
     pub id: &'a str,
     pub user_id: &'a str,
@@ -271,6 +548,44 @@ pub async fn update_task_status<'a>(
     Ok(task)
 }
 
+pub struct UpdateTaskRollupModeArgs<'a> {
+    pub id: &'a str,
+    pub rollup_mode: &'a TaskRollupMode,
+    pub user_id: &'a str,
+}
+pub async fn update_task_rollup_mode<'a>(
+    db: &mut Connection,
+    args: UpdateTaskRollupModeArgs<'a>,
+) -> anyhow::Result<Task> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE
+            tasks 
+        SET
+            rollup_mode = $1
+        WHERE
+            id = $2 AND user_id = $3
+        RETURNING *;        
+        "#,
+        args.rollup_mode,
+        args.id,
+        args.user_id
+    )
+    .fetch_one(&mut *db)
+    .await?;
+
+    let task = find_task(
+        &mut *db,
+        FindTaskArgs {
+            user_id: args.user_id,
+            task_id: &result.id,
+        },
+    )
+    .await?;
+
+    Ok(task)
+}
+
 pub struct UpdateTasksStatusArgs<'a> {
     pub task_ids: &'a Vec<String>,
     pub status: &'a TaskStatus,
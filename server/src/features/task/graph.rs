@@ -0,0 +1,746 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use crate::app::Connection;
+
+use super::{
+    db::{find_task_graph_version, load_task_graph},
+    TaskStatus,
+};
+
+/// ユーザーのタスク同士のつながり(サブタスクとブロッキングタスク)を表すグラフ
+#[derive(Debug, Default, Clone)]
+pub struct TaskGraph {
+    statuses: HashMap<String, TaskStatus>,
+    /// メインタスクID -> サブタスクIDのリスト
+    sub_tasks: HashMap<String, Vec<String>>,
+    /// サブタスクID -> メインタスクID
+    main_tasks: HashMap<String, String>,
+    /// ブロックされているタスクID -> ブロックしているタスクIDのリスト
+    blocking_tasks: HashMap<String, Vec<String>>,
+}
+
+/// 子孫サブタスクの数
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DescendantCount {
+    pub total: i64,
+    pub done: i64,
+}
+
+impl TaskGraph {
+    /// `sub_task_edges`は(メインタスクID, サブタスクID)、
+    /// `blocking_edges`は(ブロックしているタスクID, ブロックされているタスクID)のリスト
+    pub fn new(
+        statuses: impl IntoIterator<Item = (String, TaskStatus)>,
+        sub_task_edges: impl IntoIterator<Item = (String, String)>,
+        blocking_edges: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        let mut graph = TaskGraph {
+            statuses: statuses.into_iter().collect(),
+            ..Default::default()
+        };
+
+        for (main_task_id, sub_task_id) in sub_task_edges {
+            graph
+                .sub_tasks
+                .entry(main_task_id.clone())
+                .or_default()
+                .push(sub_task_id.clone());
+            graph.main_tasks.insert(sub_task_id, main_task_id);
+        }
+        for (blocking_task_id, blocked_task_id) in blocking_edges {
+            graph
+                .blocking_tasks
+                .entry(blocked_task_id)
+                .or_default()
+                .push(blocking_task_id);
+        }
+
+        graph
+    }
+
+    pub fn contains(&self, task_id: &str) -> bool {
+        self.statuses.contains_key(task_id)
+    }
+
+    pub fn sub_task_ids(&self, task_id: &str) -> &[String] {
+        self.sub_tasks
+            .get(task_id)
+            .map_or(&[], |ids| ids.as_slice())
+    }
+
+    pub fn blocking_task_ids(&self, task_id: &str) -> &[String] {
+        self.blocking_tasks
+            .get(task_id)
+            .map_or(&[], |ids| ids.as_slice())
+    }
+
+    pub fn is_done(&self, task_id: &str) -> bool {
+        self.statuses.get(task_id) == Some(&TaskStatus::Done)
+    }
+
+    /// `ancestor_id`が、メインタスクとブロッキングタスクをたどった`task_id`の祖先かを判定する。
+    /// `detect_circular_connection`と同じ判定をメモリ上で行う。
+    pub fn is_ancestor(&self, task_id: &str, ancestor_id: &str) -> bool {
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut stack: Vec<&str> = vec![task_id];
+
+        while let Some(id) = stack.pop() {
+            for parent_id in self
+                .blocking_task_ids(id)
+                .iter()
+                .chain(self.main_tasks.get(id))
+            {
+                if parent_id == ancestor_id {
+                    return true;
+                }
+                if visited.insert(parent_id.as_str()) {
+                    stack.push(parent_id);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// `task_id`が`main_task_id`の子孫サブタスクかを判定する。
+    /// `is_sub_task`と同じ判定をメモリ上で行う。
+    pub fn is_descendant_sub_task(&self, main_task_id: &str, task_id: &str) -> bool {
+        let mut id = task_id;
+        while let Some(main_id) = self.main_tasks.get(id) {
+            if main_id == main_task_id {
+                return true;
+            }
+            id = main_id.as_str();
+        }
+
+        false
+    }
+
+    /// `is_all_blocking_tasks_done`と同じ判定をメモリ上で行う。
+    pub fn is_all_blocking_tasks_done(&self, task_id: &str) -> bool {
+        !self.is_blocked_inner(task_id, &mut HashMap::new())
+    }
+
+    /// すべての子孫サブタスクのIDを返す
+    pub fn descendant_sub_task_ids(&self, task_id: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut stack: Vec<&str> = vec![task_id];
+
+        while let Some(id) = stack.pop() {
+            for sub_task_id in self.sub_task_ids(id) {
+                result.push(sub_task_id.clone());
+                stack.push(sub_task_id);
+            }
+        }
+
+        result
+    }
+
+    /// 指定したタスクとそのすべての子孫サブタスクのIDを、メインタスクがサブタスクより前になるように返す
+    pub fn subtree_task_ids<'a>(&'a self, task_id: &'a str) -> Vec<&'a str> {
+        let mut result: Vec<&str> = vec![task_id];
+        let mut i = 0;
+        while i < result.len() {
+            let sub_task_ids = self.sub_task_ids(result[i]);
+            result.extend(sub_task_ids.iter().map(String::as_str));
+            i += 1;
+        }
+
+        result
+    }
+
+    /// 子孫サブタスクのうち、自身か祖先メインタスクが未完了のタスクにブロックされていないタスクのIDを返す。
+    /// `update_all_unblocked_descendant_sub_tasks`でDoneに変更するタスクと同じになる。
+    pub fn unblocked_descendant_sub_task_ids(&self, task_id: &str) -> Vec<String> {
+        let mut memo: HashMap<String, bool> = HashMap::new();
+
+        self.descendant_sub_task_ids(task_id)
+            .into_iter()
+            .filter(|id| !self.is_blocked_via_main_tasks(id, &mut memo))
+            .collect()
+    }
+
+    /// タスク自身か祖先メインタスクが未完了のタスクに直接ブロックされているか。
+    /// 階層が深くてもスタックがあふれないように、再帰せずに祖先メインタスクを上から順に求める
+    fn is_blocked_via_main_tasks(&self, task_id: &str, memo: &mut HashMap<String, bool>) -> bool {
+        // 結果がわかっているタスクかルートのタスクまで、祖先メインタスクをたどる
+        let mut path: Vec<&str> = vec![];
+        let mut blocked = false;
+        let mut next = Some(task_id);
+        while let Some(id) = next {
+            if let Some(memoized) = memo.get(id) {
+                blocked = *memoized;
+                break;
+            }
+            path.push(id);
+            next = self.main_task_id(id);
+        }
+
+        for id in path.into_iter().rev() {
+            blocked = blocked
+                || self
+                    .blocking_task_ids(id)
+                    .iter()
+                    .any(|id| self.statuses.get(id) == Some(&TaskStatus::Todo));
+            memo.insert(id.to_string(), blocked);
+        }
+
+        blocked
+    }
+
+    pub fn main_task_id(&self, task_id: &str) -> Option<&str> {
+        self.main_tasks.get(task_id).map(String::as_str)
+    }
+
+    pub fn set_status(&mut self, task_id: &str, status: TaskStatus) {
+        self.statuses.insert(task_id.to_string(), status);
+    }
+
+    pub fn remove_sub_task_edge(&mut self, main_task_id: &str, sub_task_id: &str) {
+        if let Some(ids) = self.sub_tasks.get_mut(main_task_id) {
+            ids.retain(|id| id != sub_task_id);
+        }
+        if self.main_tasks.get(sub_task_id).map(String::as_str) == Some(main_task_id) {
+            self.main_tasks.remove(sub_task_id);
+        }
+    }
+
+    pub fn remove_blocking_edge(&mut self, blocking_task_id: &str, blocked_task_id: &str) {
+        if let Some(ids) = self.blocking_tasks.get_mut(blocked_task_id) {
+            ids.retain(|id| id != blocking_task_id);
+        }
+    }
+
+    /// すべてのタスクについて、ブロックされているかを求める。
+    /// タスク自身か、メインタスクやブロッキングタスクをたどった祖先のどれかが
+    /// 未完了のタスクにブロックされている場合にブロックされているとみなす。
+    pub fn blocked_flags(&self) -> HashMap<String, bool> {
+        let mut memo: HashMap<String, bool> = HashMap::new();
+        for task_id in self.statuses.keys() {
+            self.is_blocked_inner(task_id, &mut memo);
+        }
+        memo
+    }
+
+    /// 階層が深くてもスタックがあふれないように、再帰せずに明示的なスタックで祖先から順に求める
+    fn is_blocked_inner(&self, task_id: &str, memo: &mut HashMap<String, bool>) -> bool {
+        // (タスクID, 祖先を積み終えたか)
+        let mut stack: Vec<(&str, bool)> = vec![(task_id, false)];
+
+        while let Some((id, parents_pushed)) = stack.pop() {
+            if memo.contains_key(id) {
+                continue;
+            }
+
+            let blocking_ids = self.blocking_task_ids(id);
+            if blocking_ids.iter().any(|id| !self.is_done(id)) {
+                memo.insert(id.to_string(), true);
+                continue;
+            }
+
+            let mut parent_ids = blocking_ids
+                .iter()
+                .chain(self.main_tasks.get(id))
+                .map(String::as_str);
+            if parents_pushed {
+                let blocked = parent_ids.any(|id| memo.get(id) == Some(&true));
+                memo.insert(id.to_string(), blocked);
+            } else {
+                stack.push((id, true));
+                stack.extend(
+                    parent_ids
+                        .filter(|id| !memo.contains_key(*id))
+                        .map(|id| (id, false)),
+                );
+            }
+        }
+
+        memo[task_id]
+    }
+
+    /// すべてのタスクについて、子孫サブタスクの数と完了している子孫サブタスクの数を求める
+    pub fn descendant_counts(&self) -> HashMap<String, DescendantCount> {
+        let mut memo: HashMap<String, DescendantCount> = HashMap::new();
+        for task_id in self.statuses.keys() {
+            self.descendant_count_inner(task_id, &mut memo);
+        }
+        memo
+    }
+
+    /// 階層が深くてもスタックがあふれないように、再帰せずに明示的なスタックで子孫から順に求める
+    fn descendant_count_inner(&self, task_id: &str, memo: &mut HashMap<String, DescendantCount>) {
+        // (タスクID, サブタスクを積み終えたか)
+        let mut stack: Vec<(&str, bool)> = vec![(task_id, false)];
+
+        while let Some((id, sub_tasks_pushed)) = stack.pop() {
+            if memo.contains_key(id) {
+                continue;
+            }
+
+            if sub_tasks_pushed {
+                // サブタスクはメインタスクを一つしか持たないので、重複して数えることはない
+                let mut count = DescendantCount::default();
+                for sub_task_id in self.sub_task_ids(id) {
+                    let sub_count = memo.get(sub_task_id).copied().unwrap_or_default();
+                    count.total += 1 + sub_count.total;
+                    count.done += i64::from(self.is_done(sub_task_id)) + sub_count.done;
+                }
+                memo.insert(id.to_string(), count);
+            } else {
+                stack.push((id, true));
+                stack.extend(self.sub_task_ids(id).iter().map(|id| (id.as_str(), false)));
+            }
+        }
+    }
+}
+
+/// キャッシュするユーザー数の既定の上限
+pub const MAX_CACHED_USERS: usize = 1000;
+
+/// ユーザーごとのタスクのグラフのキャッシュ。
+/// 必要になったときに読み込み、タスクやつながりが変更されてバージョンが変わったときに読み込み直す。
+/// 上限を超えたら、最も長く使われていないユーザーのグラフから捨てる。
+#[derive(Clone)]
+pub struct TaskGraphCache {
+    /// ユーザーID -> キャッシュされたグラフ
+    entries: Arc<RwLock<HashMap<String, CacheEntry>>>,
+    /// グラフが使われた順番を決めるためのカウンター
+    clock: Arc<AtomicU64>,
+    capacity: usize,
+}
+
+struct CacheEntry {
+    version: i64,
+    graph: Arc<TaskGraph>,
+    /// 最後に使われたときの`clock`の値。読み込みのロックのまま更新できるようにアトミックにする
+    last_used: AtomicU64,
+}
+
+impl Default for TaskGraphCache {
+    fn default() -> Self {
+        Self::with_capacity(MAX_CACHED_USERS)
+    }
+}
+
+// ハンドラの引数としてトレースに出力されるので、グラフの中身までは出力しない
+impl std::fmt::Debug for TaskGraphCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        f.debug_struct("TaskGraphCache")
+            .field("users", &entries.len())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl TaskGraphCache {
+    pub fn with_capacity(capacity: usize) -> Self {
+        TaskGraphCache {
+            entries: Arc::default(),
+            clock: Arc::default(),
+            capacity,
+        }
+    }
+
+    /// キャッシュされたグラフを返す。キャッシュがないか古くなっている場合はデータベースから読み込む。
+    /// トランザクション内で書き込みを行ったあとに呼び出すと、コミットされていない状態をキャッシュしてしまうので、
+    /// 書き込みを行う前に呼び出す必要がある。
+    pub async fn get_or_load(
+        &self,
+        db: &mut Connection,
+        user_id: &str,
+    ) -> anyhow::Result<Arc<TaskGraph>> {
+        let version = find_task_graph_version(&mut *db, user_id).await?;
+
+        {
+            let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+            if let Some(entry) = entries.get(user_id) {
+                if entry.version == version {
+                    entry.last_used.store(self.tick(), Ordering::Relaxed);
+                    return Ok(entry.graph.clone());
+                }
+            }
+        }
+
+        let graph = Arc::new(load_task_graph(db, user_id).await?);
+
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        // 並行して新しいバージョンが読み込まれていた場合は上書きしない
+        if entries
+            .get(user_id)
+            .is_none_or(|entry| entry.version < version)
+        {
+            entries.insert(
+                user_id.to_string(),
+                CacheEntry {
+                    version,
+                    graph: graph.clone(),
+                    last_used: AtomicU64::new(self.tick()),
+                },
+            );
+            self.evict(&mut entries);
+        }
+
+        Ok(graph)
+    }
+
+    /// ユーザーのグラフをキャッシュから捨てる。ユーザーを削除したときに呼び出す
+    pub fn remove(&self, user_id: &str) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        entries.remove(user_id);
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn evict(&self, entries: &mut HashMap<String, CacheEntry>) {
+        while entries.len() > self.capacity {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used.load(Ordering::Relaxed))
+                .map(|(user_id, _)| user_id.clone())
+            else {
+                return;
+            };
+            entries.remove(&oldest);
+        }
+    }
+}
+
+/// 子孫サブタスクの完了率(%)を求める。子孫サブタスクがない場合はタスク自身の状態で決まる。
+pub fn calc_progress(status: TaskStatus, count: DescendantCount) -> f64 {
+    if count.total == 0 {
+        return match status {
+            TaskStatus::Done => 100.0,
+            TaskStatus::Todo => 0.0,
+        };
+    }
+
+    count.done as f64 / count.total as f64 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        app::{AppResult, Db},
+        features::{
+            block_task::db::{
+                is_all_blocking_tasks_done, update_all_unblocked_descendant_sub_tasks,
+            },
+            sub_task::db::{is_sub_task, IsSubTaskArgs},
+            task::{
+                db::{
+                    detect_circular_connection, DetectCircularConnectionArgs, UpdateTaskStatusArgs,
+                },
+                test::{random::Lcg, task_factory},
+                Task,
+            },
+            user::test::user_factory,
+        },
+    };
+
+    fn ids(edges: &[(&str, &str)]) -> Vec<(String, String)> {
+        edges
+            .iter()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn 祖先をブロックしているタスクが未完了ならブロックされている() {
+        // blocking -> main(ブロック)
+        // main --> sub --> sub_sub
+        let graph = TaskGraph::new(
+            [
+                ("blocking".to_string(), TaskStatus::Todo),
+                ("main".to_string(), TaskStatus::Todo),
+                ("sub".to_string(), TaskStatus::Todo),
+                ("sub_sub".to_string(), TaskStatus::Todo),
+                ("other".to_string(), TaskStatus::Todo),
+            ],
+            ids(&[("main", "sub"), ("sub", "sub_sub")]),
+            ids(&[("blocking", "main")]),
+        );
+
+        let flags = graph.blocked_flags();
+        assert!(!flags["blocking"]);
+        assert!(flags["main"]);
+        assert!(flags["sub"]);
+        assert!(flags["sub_sub"]);
+        assert!(!flags["other"]);
+    }
+
+    #[test]
+    fn ブロックしているタスクが完了していればブロックされていない() {
+        let graph = TaskGraph::new(
+            [
+                ("blocking".to_string(), TaskStatus::Done),
+                ("blocked".to_string(), TaskStatus::Todo),
+            ],
+            [],
+            ids(&[("blocking", "blocked")]),
+        );
+
+        assert!(!graph.blocked_flags()["blocked"]);
+    }
+
+    /// 再帰でたどるとスタックがあふれる深さ
+    const DEEP_CHAIN_LEN: usize = 100_000;
+
+    #[test]
+    fn 深いサブタスクの階層でも求められる() {
+        // blocking -> t0 --> t1 --> ... --> t99999
+        let chain_ids: Vec<String> = (0..DEEP_CHAIN_LEN).map(|i| format!("t{i}")).collect();
+        let graph = TaskGraph::new(
+            chain_ids
+                .iter()
+                .map(|id| (id.clone(), TaskStatus::Todo))
+                .chain([("blocking".to_string(), TaskStatus::Done)]),
+            chain_ids.windows(2).map(|w| (w[0].clone(), w[1].clone())),
+            ids(&[("blocking", "t0")]),
+        );
+        let leaf = chain_ids.last().unwrap();
+
+        assert!(graph.is_all_blocking_tasks_done(leaf));
+        assert!(!graph.blocked_flags()[leaf]);
+        assert_eq!(
+            graph.unblocked_descendant_sub_task_ids("t0").len(),
+            DEEP_CHAIN_LEN - 1
+        );
+        assert_eq!(
+            graph.descendant_counts()["t0"],
+            DescendantCount {
+                total: DEEP_CHAIN_LEN as i64 - 1,
+                done: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn 深いブロッキングタスクの連鎖でも求められる() {
+        // t0 -> t1 -> ... -> t99999
+        let chain_ids: Vec<String> = (0..DEEP_CHAIN_LEN).map(|i| format!("t{i}")).collect();
+        let graph = TaskGraph::new(
+            chain_ids.iter().enumerate().map(|(i, id)| {
+                let status = if i == 0 {
+                    TaskStatus::Todo
+                } else {
+                    TaskStatus::Done
+                };
+                (id.clone(), status)
+            }),
+            [],
+            chain_ids.windows(2).map(|w| (w[0].clone(), w[1].clone())),
+        );
+        let leaf = chain_ids.last().unwrap();
+
+        // 直接ブロックしているタスクは完了しているが、祖先の先頭が未完了なのでブロックされている
+        assert!(!graph.is_all_blocking_tasks_done(leaf));
+        assert!(graph.blocked_flags()[leaf]);
+    }
+
+    #[test]
+    fn 子孫サブタスクの数と進捗を求められる() {
+        // main --> sub1 --> sub11(Done)
+        // main --> sub2(Done)
+        let graph = TaskGraph::new(
+            [
+                ("main".to_string(), TaskStatus::Todo),
+                ("sub1".to_string(), TaskStatus::Todo),
+                ("sub11".to_string(), TaskStatus::Done),
+                ("sub2".to_string(), TaskStatus::Done),
+            ],
+            ids(&[("main", "sub1"), ("sub1", "sub11"), ("main", "sub2")]),
+            [],
+        );
+
+        let counts = graph.descendant_counts();
+        assert_eq!(counts["main"], DescendantCount { total: 3, done: 2 });
+        assert_eq!(counts["sub1"], DescendantCount { total: 1, done: 1 });
+        assert_eq!(counts["sub2"], DescendantCount::default());
+
+        assert_eq!(calc_progress(TaskStatus::Todo, counts["sub1"]), 100.0);
+        assert_eq!(calc_progress(TaskStatus::Done, counts["sub2"]), 100.0);
+        assert_eq!(
+            calc_progress(TaskStatus::Todo, DescendantCount::default()),
+            0.0
+        );
+    }
+
+    /// ランダムなタスクのつながりを作り、作成したタスクのIDを返す。
+    /// サブタスクとブロッキングタスクのつながりは、どちらも前に作ったタスクから後に作ったタスクへ向かうので循環しない。
+    async fn create_random_graph(db: &Db, user_id: &str, rng: &mut Lcg) -> AppResult<Vec<String>> {
+        let mut ids: Vec<String> = Vec::new();
+        for i in 0..30 {
+            let task = Task {
+                status: [TaskStatus::Todo, TaskStatus::Done][rng.next(2)],
+                user_id: user_id.into(),
+                ..Default::default()
+            };
+            let task = if i > 0 && rng.next(3) != 0 {
+                task_factory::create_sub_task(db, &ids[rng.next(i)], task).await?
+            } else {
+                task_factory::create(db, task).await?
+            };
+            ids.push(task.id);
+        }
+
+        let mut blocking_edges = HashSet::new();
+        for _ in 0..30 {
+            let (a, b) = (rng.next(ids.len()), rng.next(ids.len()));
+            if a < b && blocking_edges.insert((a, b)) {
+                task_factory::create_blocking_connection(db, user_id, &ids[a], &ids[b]).await?;
+            }
+        }
+
+        Ok(ids)
+    }
+
+    async fn find_statuses(db: &mut Connection, ids: &[String]) -> AppResult<Vec<TaskStatus>> {
+        let mut statuses = Vec::new();
+        for id in ids {
+            let row = sqlx::query!("SELECT status FROM tasks WHERE id = $1", id)
+                .fetch_one(&mut *db)
+                .await?;
+            statuses.push(TaskStatus::from(row.status));
+        }
+
+        Ok(statuses)
+    }
+
+    #[sqlx::test]
+    async fn メモリ上のグラフとデータベースで同じ判定になる(
+        db: Db,
+    ) -> AppResult<()> {
+        let mut rng = Lcg(7);
+
+        for _ in 0..3 {
+            let user = user_factory::create_default(&db).await?;
+            let ids = create_random_graph(&db, &user.id, &mut rng).await?;
+
+            let mut conn = db.acquire().await?;
+            let graph = load_task_graph(&mut conn, &user.id).await?;
+
+            for a in &ids {
+                assert_eq!(
+                    graph.is_all_blocking_tasks_done(a),
+                    is_all_blocking_tasks_done(&mut conn, a).await?
+                );
+
+                for b in ids.iter().filter(|b| *b != a) {
+                    let circular = detect_circular_connection(
+                        &mut conn,
+                        DetectCircularConnectionArgs {
+                            parent_task_id: a,
+                            child_task_id: b,
+                            user_id: &user.id,
+                        },
+                    )
+                    .await?;
+                    assert_eq!(graph.is_ancestor(a, b), circular);
+
+                    let sub_task = is_sub_task(
+                        &mut conn,
+                        IsSubTaskArgs {
+                            main_task_id: a,
+                            task_id: b,
+                            user_id: &user.id,
+                        },
+                    )
+                    .await?;
+                    assert_eq!(graph.is_descendant_sub_task(a, b), sub_task);
+                }
+
+                // 子孫サブタスクの更新は、それぞれ更新したあとの状態を比べてからロールバックする
+                for status in [TaskStatus::Todo, TaskStatus::Done] {
+                    let mut results = Vec::new();
+                    for graph in [Some(&graph), None] {
+                        let mut tx = db.begin().await?;
+                        update_all_unblocked_descendant_sub_tasks(
+                            &mut tx,
+                            graph,
+                            UpdateTaskStatusArgs {
+                                id: a,
+                                status: &status,
+                                user_id: &user.id,
+                            },
+                        )
+                        .await?;
+                        results.push(find_statuses(&mut tx, &ids).await?);
+                        tx.rollback().await?;
+                    }
+                    assert_eq!(results[0], results[1]);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn タスクのつながりが変更されるとキャッシュが読み込み直される(
+        db: Db,
+    ) -> AppResult<()> {
+        let user = user_factory::create_default(&db).await?;
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+
+        let cache = TaskGraphCache::default();
+        let mut conn = db.acquire().await?;
+
+        let graph = cache.get_or_load(&mut conn, &user.id).await?;
+        assert!(graph.sub_task_ids(&main.id).is_empty());
+        // 変更がなければキャッシュされたグラフを返す
+        assert!(Arc::ptr_eq(
+            &graph,
+            &cache.get_or_load(&mut conn, &user.id).await?
+        ));
+
+        let sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+
+        let graph = cache.get_or_load(&mut conn, &user.id).await?;
+        assert_eq!(graph.sub_task_ids(&main.id), [sub.id]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 上限を超えると最も長く使われていないユーザーのグラフを捨てる(
+        db: Db,
+    ) -> AppResult<()> {
+        let user1 = user_factory::create_default(&db).await?;
+        let user2 = user_factory::create_default(&db).await?;
+        let user3 = user_factory::create_default(&db).await?;
+
+        let cache = TaskGraphCache::with_capacity(2);
+        let mut conn = db.acquire().await?;
+
+        let graph1 = cache.get_or_load(&mut conn, &user1.id).await?;
+        cache.get_or_load(&mut conn, &user2.id).await?;
+        // user1のほうが最近使われている
+        cache.get_or_load(&mut conn, &user1.id).await?;
+        cache.get_or_load(&mut conn, &user3.id).await?;
+
+        {
+            let entries = cache.entries.read().unwrap();
+            assert_eq!(entries.len(), 2);
+            assert!(!entries.contains_key(&user2.id));
+        }
+        assert!(Arc::ptr_eq(
+            &graph1,
+            &cache.get_or_load(&mut conn, &user1.id).await?
+        ));
+
+        cache.remove(&user1.id);
+        assert!(!cache.entries.read().unwrap().contains_key(&user1.id));
+
+        Ok(())
+    }
+}
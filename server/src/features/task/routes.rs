@@ -1,14 +1,21 @@
-use crate::{app::AppState, features::auth::Auth};
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope},
+};
 use axum::{
-    routing::{get, put},
+    middleware,
+    routing::{get, post, put},
     Router,
 };
 use axum_login::login_required;
 pub mod create_task;
 pub mod delete_task;
+pub mod duplicate_task;
+pub mod get_status_transitions;
 pub mod get_task;
 pub mod get_tasks;
 pub mod update_task;
+pub mod update_task_rollup_mode;
 pub mod update_task_status;
 
 pub const TAG: &str = "task";
@@ -38,6 +45,42 @@ impl TaskPaths {
     pub fn update_task_status_open_api() -> String {
         Self::task_open_api() + &Self::update_task_status_base()
     }
+
+    pub fn update_task_rollup_mode_base() -> String {
+        "/update-rollup-mode".into()
+    }
+
+    pub fn update_task_rollup_mode() -> String {
+        Self::task() + &Self::update_task_rollup_mode_base()
+    }
+
+    pub fn update_task_rollup_mode_open_api() -> String {
+        Self::task_open_api() + &Self::update_task_rollup_mode_base()
+    }
+
+    pub fn duplicate_task_base() -> String {
+        "/duplicate".into()
+    }
+
+    pub fn duplicate_task() -> String {
+        Self::task() + &Self::duplicate_task_base()
+    }
+
+    pub fn duplicate_task_open_api() -> String {
+        Self::task_open_api() + &Self::duplicate_task_base()
+    }
+
+    pub fn status_transitions_base() -> String {
+        "/status-transitions".into()
+    }
+
+    pub fn status_transitions() -> String {
+        Self::task() + &Self::status_transitions_base()
+    }
+
+    pub fn status_transitions_open_api() -> String {
+        Self::task_open_api() + &Self::status_transitions_base()
+    }
 }
 
 pub fn router() -> Router<AppState> {
@@ -56,5 +99,15 @@ pub fn router() -> Router<AppState> {
             &TaskPaths::update_task_status(),
             put(update_task_status::handler),
         )
+        .route(
+            &TaskPaths::update_task_rollup_mode(),
+            put(update_task_rollup_mode::handler),
+        )
+        .route(&TaskPaths::duplicate_task(), post(duplicate_task::handler))
+        .route(
+            &TaskPaths::status_transitions(),
+            get(get_status_transitions::handler),
+        )
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
         .route_layer(login_required!(Auth))
 }
@@ -1,5 +1,4 @@
 use axum::{extract::State, response::IntoResponse, Json};
-use axum_garde::WithValidation;
 use axum_login::AuthSession;
 use http::StatusCode;
 
@@ -7,8 +6,9 @@ use crate::app::AppResult;
 use crate::features::task::db::{insert_task, InsertTaskArgs};
 use crate::{
     app::AppState,
-    error::AppError,
+    error::{AppError, ProblemDetails},
     features::{auth::Auth, task::CreateTask},
+    validation::WithValidation,
 };
 
 #[tracing::instrument(err)]
@@ -17,11 +17,15 @@ use crate::{
     tag = super::TAG,
     path = super::TaskPaths::tasks(),
     request_body = CreateTask,
-    responses((status = 201, body = Task))
+    responses(
+        (status = 201, body = Task),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, .. }): State<AppState>,
     WithValidation(payload): WithValidation<Json<CreateTask>>,
 ) -> AppResult<impl IntoResponse> {
     let Some(user) = auth_session.user else {
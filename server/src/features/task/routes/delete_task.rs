@@ -8,10 +8,12 @@ use http::StatusCode;
 
 use crate::{
     app::AppResult,
+    error::ProblemDetails,
     features::{
-        sub_task::db::{
-            find_main_task_id, update_task_and_all_ancestor_main_tasks_status, FindMainTaskIdsArgs,
-            TaskAndUser,
+        attachment::db::{find_attachment_storage_keys, FindAttachmentsArgs},
+        sub_task::{
+            db::{find_main_task_id, FindMainTaskIdsArgs, TaskAndUser},
+            usecases::update_task_and_ancestors_status,
         },
         task::{
             db::{delete_task, DeleteTaskArgs},
@@ -26,13 +28,19 @@ use crate::{app::AppState, error::AppError, features::auth::Auth};
     delete,
     tag = super::TAG,
     path = super::TaskPaths::task_open_api(),
-    responses((status = 200, body = DeleteTaskResponse)),
+    responses(
+        (status = 200, body = DeleteTaskResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
     params(("id" = String, Path,))
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
     Path(id): Path<String>,
-    State(AppState { db }): State<AppState>,
+    State(AppState {
+        db, attachments, ..
+    }): State<AppState>,
 ) -> AppResult<impl IntoResponse> {
     let Some(user) = auth_session.user else {
         return Err(AppError::unauthorized());
@@ -50,6 +58,16 @@ pub async fn handler(
     )
     .await?;
 
+    // タスクを削除すると添付ファイルの情報も削除されるので、削除する前にストレージのキーを取得しておく
+    let storage_keys = find_attachment_storage_keys(
+        &mut tx,
+        FindAttachmentsArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
     let deleted_id = delete_task(
         &mut tx,
         DeleteTaskArgs {
@@ -61,7 +79,7 @@ pub async fn handler(
 
     // すべての祖先メインタスクを更新
     if let Some(id) = main_task_id {
-        update_task_and_all_ancestor_main_tasks_status(
+        update_task_and_ancestors_status::action(
             &mut tx,
             TaskAndUser {
                 task_id: &id,
@@ -73,6 +91,12 @@ pub async fn handler(
 
     tx.commit().await?;
 
+    for key in storage_keys {
+        if let Err(e) = attachments.storage.delete(&key).await {
+            tracing::warn!("Failed to delete attachment file {}: {:?}", key, e);
+        }
+    }
+
     Ok((
         StatusCode::OK,
         Json(DeleteTaskResponse {
@@ -84,7 +108,16 @@ pub async fn handler(
 
 #[cfg(test)]
 mod tests {
+    use axum_test::multipart::{MultipartForm, Part};
+
     use crate::app::Db;
+    use crate::features::attachment::routes::{
+        upload_attachment::FILE_FIELD_NAME, AttachmentPaths,
+    };
+    use crate::features::attachment::Attachment;
+    use crate::features::recurrence::{
+        routes::RecurrencePaths, RecurrenceFrequency, TaskRecurrence, UpdateTaskRecurrence,
+    };
     use crate::features::task::db::{find_task, FindTaskArgs};
     use crate::features::task::test::task_factory;
     use crate::features::task::{Task, TaskStatus};
@@ -189,4 +222,101 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn サブタスクの削除で完了した繰り返しのタスクも次のタスクが作成される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let done_sub = task_factory::create_sub_task(
+            &db,
+            &main.id,
+            Task {
+                status: TaskStatus::Done,
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let todo_sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        test.server()
+            .put(&RecurrencePaths::one_task_recurrence(&main.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Daily,
+                interval: 1,
+                due_on: Some("2024/03/01".into()),
+            })
+            .await
+            .assert_status_ok();
+
+        test.server()
+            .delete(&TaskPaths::one_task(&todo_sub.id))
+            .await
+            .assert_status_ok();
+
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+        // メインタスクは完了したサブタスクとともに複製される
+        assert_eq!(tasks.len(), 4);
+        let main = tasks.iter().find(|t| t.id == main.id).unwrap();
+        assert_eq!(main.status, TaskStatus::Done);
+        let next = tasks
+            .iter()
+            .find(|t| t.id != main.id && !t.sub_task_ids.is_empty())
+            .unwrap();
+        assert_eq!(next.status, TaskStatus::Todo);
+        assert!(!next.sub_task_ids.contains(&done_sub.id));
+
+        let recurrence: TaskRecurrence = test
+            .server()
+            .get(&RecurrencePaths::one_task_recurrence(&next.id))
+            .await
+            .json();
+        assert_eq!(recurrence.due_on, "2024/03/02");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn タスクを削除すると添付ファイルも削除される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let attachment: Attachment = test
+            .server()
+            .post(&AttachmentPaths::one_task_attachments(&task.id))
+            .multipart(
+                MultipartForm::new().add_part(
+                    FILE_FIELD_NAME,
+                    Part::bytes(b"hello".to_vec())
+                        .file_name("hello.txt")
+                        .mime_type("text/plain"),
+                ),
+            )
+            .await
+            .json();
+
+        test.server()
+            .delete(&TaskPaths::one_task(&task.id))
+            .await
+            .assert_status_ok();
+
+        let attachments = sqlx::query!("SELECT * FROM attachments;")
+            .fetch_all(&db)
+            .await?;
+        assert!(attachments.is_empty());
+        assert!(test
+            .attachments()
+            .storage
+            .get(&attachment.id)
+            .await
+            .is_err());
+
+        Ok(())
+    }
 }
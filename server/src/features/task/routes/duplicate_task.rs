@@ -0,0 +1,267 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        auth::Auth,
+        task::{
+            db::{find_task, FindTaskArgs},
+            usecases::duplicate_task::{self, DuplicateTaskArgs, DuplicateTaskError},
+            DuplicateTask,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::TaskPaths::duplicate_task_open_api(),
+    request_body = DuplicateTask,
+    responses(
+        (status = 201, body = [Task]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    State(AppState { db, task_graph, .. }): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<DuplicateTask>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+    let graph = task_graph.get_or_load(&mut tx, &user.id).await?;
+
+    let new_ids = match duplicate_task::action(
+        &mut tx,
+        DuplicateTaskArgs {
+            task_id: &id,
+            user_id: &user.id,
+            include_descendants: payload.include_descendants,
+            copy_blocking_connections: payload.copy_blocking_connections,
+            reset_status: payload.reset_status,
+            graph: &graph,
+        },
+    )
+    .await
+    {
+        Ok(ids) => ids,
+        Err(DuplicateTaskError::TaskNotFound) => {
+            return Err(AppError::with_code(ErrorCode::TaskNotFound));
+        }
+        Err(DuplicateTaskError::Unknown(e)) => return Err(e.into()),
+    };
+
+    let mut tasks = Vec::new();
+    for new_id in &new_ids {
+        let task = find_task(
+            &mut tx,
+            FindTaskArgs {
+                task_id: new_id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        tasks.push(task);
+    }
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(tasks)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::{
+                routes::TaskPaths, test::task_factory,
+                usecases::duplicate_task::DUPLICATED_NODE_OFFSET, DuplicateTask, Task, TaskStatus,
+            },
+            task_node::{
+                db::{find_task_node_info, FindTaskNodeInfo},
+                test::task_node_factory,
+                TaskNode, TaskNodeInfo,
+            },
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn タスクだけを複製できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create(
+            &db,
+            Task {
+                title: "main".into(),
+                description: "description".into(),
+                status: TaskStatus::Done,
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+
+        let res = test
+            .server()
+            .post(&TaskPaths::one_duplicate_task(&main.id))
+            .json(&DuplicateTask::default())
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+
+        let tasks: Vec<Task> = res.json();
+        assert_eq!(tasks.len(), 1);
+        let copied = &tasks[0];
+        assert_ne!(copied.id, main.id);
+        assert_eq!(copied.title, "main");
+        assert_eq!(copied.description, "description");
+        assert_eq!(copied.status, TaskStatus::Done);
+        assert!(copied.sub_task_ids.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 子孫サブタスクとそのつながりを複製できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        // outer -> main
+        // main --> sub1 --> sub11
+        // main --> sub2
+        // sub1 -> sub2
+        let outer = task_factory::create_with_user(&db, &user.id).await?;
+        let main = task_factory::create_default_blocked_task(&db, &user.id, &outer.id).await?;
+        let sub1 = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        task_factory::create_sub_task(
+            &db,
+            &sub1.id,
+            Task {
+                status: TaskStatus::Done,
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let sub2 = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        task_factory::create_blocking_connection(&db, &user.id, &sub1.id, &sub2.id).await?;
+
+        let tasks: Vec<Task> = test
+            .server()
+            .post(&TaskPaths::one_duplicate_task(&main.id))
+            .json(&DuplicateTask {
+                include_descendants: true,
+                copy_blocking_connections: true,
+                reset_status: true,
+            })
+            .await
+            .json();
+        assert_eq!(tasks.len(), 4);
+        assert!(tasks.iter().all(|t| t.status == TaskStatus::Todo));
+
+        let copied_main = &tasks[0];
+        assert_eq!(copied_main.descendant_count, 3);
+        assert_eq!(copied_main.sub_task_ids.len(), 2);
+        // 複製したタスクの外とのつながりは複製しない
+        assert!(copied_main.blocking_task_ids.is_empty());
+
+        // sub1 -> sub2 のつながりは、複製したタスク同士のつながりとして複製される
+        let copied_ids: Vec<&String> = tasks.iter().map(|t| &t.id).collect();
+        let copied_blocked: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| !t.blocking_task_ids.is_empty())
+            .collect();
+        assert_eq!(copied_blocked.len(), 1);
+        assert!(copied_blocked[0]
+            .blocking_task_ids
+            .iter()
+            .all(|id| copied_ids.contains(&id)));
+        assert!(!copied_ids.contains(&&sub2.id));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 複製したノードは元のノードからずらして配置される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let node = task_node_factory::create(
+            &db,
+            TaskNode {
+                task: Task {
+                    user_id: user.id.clone(),
+                    ..Default::default()
+                },
+                node_info: TaskNodeInfo {
+                    user_id: user.id.clone(),
+                    x: 10.0,
+                    y: 20.0,
+                    ..Default::default()
+                },
+            },
+        )
+        .await?;
+
+        let tasks: Vec<Task> = test
+            .server()
+            .post(&TaskPaths::one_duplicate_task(&node.task.id))
+            .json(&DuplicateTask::default())
+            .await
+            .json();
+
+        let mut conn = db.acquire().await?;
+        let node_info = find_task_node_info(
+            &mut conn,
+            FindTaskNodeInfo {
+                task_id: &tasks[0].id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        assert_eq!(node_info.x, 10.0 + DUPLICATED_NODE_OFFSET);
+        assert_eq!(node_info.y, 20.0 + DUPLICATED_NODE_OFFSET);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクは複製できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .post(&TaskPaths::one_duplicate_task(&other_user_task.id))
+            .json(&DuplicateTask::default())
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
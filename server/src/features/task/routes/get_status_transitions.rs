@@ -0,0 +1,240 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        auth::Auth,
+        task::{
+            db::{find_status_transitions, FindTaskArgs},
+            StatusTransition,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::TaskPaths::status_transitions_open_api(),
+    responses(
+        (status = 200, body = [StatusTransition]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<Vec<StatusTransition>>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(transitions) = find_status_transitions(
+        &mut tx,
+        FindTaskArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::with_code(ErrorCode::TaskNotFound));
+    };
+
+    tx.commit().await?;
+
+    Ok(Json(transitions))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        error::{ErrorCode, ProblemDetails},
+        features::{
+            task::{
+                db::{find_task, FindTaskArgs},
+                routes::TaskPaths,
+                test::task_factory,
+                StatusTransition, TaskStatus, UpdateTaskStatus,
+            },
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 状態の遷移履歴を取得できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        for status in [TaskStatus::Done, TaskStatus::Todo] {
+            test.server()
+                .put(&TaskPaths::one_update_task_status(&task.id))
+                .json(&UpdateTaskStatus { status })
+                .await
+                .assert_status_ok();
+        }
+
+        let transitions: Vec<StatusTransition> = test
+            .server()
+            .get(&TaskPaths::one_status_transitions(&task.id))
+            .await
+            .json();
+        let transitions: Vec<_> = transitions
+            .iter()
+            .map(|t| (t.from_status, t.to_status))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (None, TaskStatus::Todo),
+                (Some(TaskStatus::Todo), TaskStatus::Done),
+                (Some(TaskStatus::Done), TaskStatus::Todo),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 完了日時は完了時に設定され未完了に戻すと消える(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        assert!(task.completed_at.is_none());
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&task.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let mut conn = db.acquire().await?;
+        let done = find_task(
+            &mut conn,
+            FindTaskArgs {
+                task_id: &task.id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        assert!(done.completed_at.is_some());
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&task.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Todo,
+            })
+            .await
+            .assert_status_ok();
+
+        let reopened = find_task(
+            &mut conn,
+            FindTaskArgs {
+                task_id: &task.id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        assert!(reopened.completed_at.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 連鎖的に完了したタスクにも完了日時と遷移が記録される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+
+        // サブタスクを完了にするとメインタスクも完了になる
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&sub.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let mut conn = db.acquire().await?;
+        let main_task = find_task(
+            &mut conn,
+            FindTaskArgs {
+                task_id: &main.id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        assert_eq!(main_task.status, TaskStatus::Done);
+        assert!(main_task.completed_at.is_some());
+
+        let transitions: Vec<StatusTransition> = test
+            .server()
+            .get(&TaskPaths::one_status_transitions(&main.id))
+            .await
+            .json();
+        assert_eq!(
+            transitions.last().map(|t| t.to_status),
+            Some(TaskStatus::Done)
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクの遷移履歴は取得できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .get(&TaskPaths::one_status_transitions(&other_user_task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+        let problem: ProblemDetails = res.json();
+        assert_eq!(problem.code, ErrorCode::TaskNotFound);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 存在しないタスクの遷移履歴は取得できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        let res = test
+            .server()
+            .get(&TaskPaths::one_status_transitions("unknown"))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -6,7 +6,7 @@ use axum_login::AuthSession;
 
 use crate::{
     app::{AppResult, AppState},
-    error::AppError,
+    error::{AppError, ProblemDetails},
     features::{
         auth::Auth,
         task::{
@@ -21,13 +21,17 @@ use crate::{
     get,
     tag = super::TAG,
     path = super::TaskPaths::task_open_api(),
-    responses((status = 200, body = Task)),
+    responses(
+        (status = 200, body = Task),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
     params(("id" = String, Path,))
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
     Path(id): Path<String>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, .. }): State<AppState>,
 ) -> AppResult<Json<Task>> {
     let Some(user) = auth_session.user else {
         return Err(AppError::unauthorized());
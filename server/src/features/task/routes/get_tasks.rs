@@ -4,18 +4,26 @@ use http::StatusCode;
 
 use crate::app::AppResult;
 use crate::features::task::db::find_tasks;
-use crate::{app::AppState, error::AppError, features::auth::Auth};
+use crate::{
+    app::AppState,
+    error::{AppError, ProblemDetails},
+    features::auth::Auth,
+};
 
 #[tracing::instrument(err)]
 #[utoipa::path(
     get,
     tag = super::TAG,
     path = super::TaskPaths::tasks(),
-    responses((status = 200, body = [Task]))
+    responses(
+        (status = 200, body = [Task]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, .. }): State<AppState>,
 ) -> AppResult<impl IntoResponse> {
     let Some(user) = auth_session.user else {
         return Err(AppError::unauthorized());
@@ -36,7 +44,7 @@ mod tests {
     use crate::app::tests::AppTest;
     use crate::app::Db;
     use crate::features::task::routes::TaskPaths;
-    use crate::features::task::Task;
+    use crate::features::task::{Task, TaskStatus};
     use crate::features::{task::test::task_factory, user::test::user_factory};
 
     use super::*;
@@ -91,4 +99,96 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn サブタスクとブロックされたタスクが多くても正しく取得できる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let mut sub_task_ids = Vec::new();
+        let mut blocked_task_ids = Vec::new();
+        for _ in 0..30 {
+            let sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+            sub_task_ids.push(sub.id);
+            let blocked =
+                task_factory::create_default_blocked_task(&db, &user.id, &main.id).await?;
+            blocked_task_ids.push(blocked.id);
+        }
+        sub_task_ids.sort();
+        blocked_task_ids.sort();
+
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+        assert_eq!(tasks.len(), 61);
+
+        let t = tasks.iter().find(|t| t.id == main.id).unwrap();
+        assert_eq!(t.sub_task_ids, sub_task_ids);
+        assert_eq!(t.blocked_task_ids, blocked_task_ids);
+        assert_eq!(t.descendant_count, 30);
+
+        let fetched: Task = test
+            .server()
+            .get(&TaskPaths::one_task(&main.id))
+            .await
+            .json();
+        assert_eq!(fetched.sub_task_ids, sub_task_ids);
+        assert_eq!(fetched.blocked_task_ids, blocked_task_ids);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn ブロック状態と進捗がサーバーで計算される(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        // blocking -> main(ブロック)
+        // main --> sub1(Done)
+        // main --> sub2
+        let blocking = task_factory::create_with_user(&db, &user.id).await?;
+        let main = task_factory::create_default_blocked_task(&db, &user.id, &blocking.id).await?;
+        task_factory::create_sub_task(
+            &db,
+            &main.id,
+            Task {
+                status: TaskStatus::Done,
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let sub2 = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+
+        let main = tasks.iter().find(|t| t.id == main.id).unwrap();
+        assert!(main.is_blocked);
+        assert_eq!(main.blocking_task_ids, vec![blocking.id.clone()]);
+        assert_eq!(main.descendant_count, 2);
+        assert_eq!(main.done_descendant_count, 1);
+        assert_eq!(main.progress, 50.0);
+
+        // 祖先がブロックされているのでサブタスクもブロックされている
+        let sub2 = tasks.iter().find(|t| t.id == sub2.id).unwrap();
+        assert!(sub2.is_blocked);
+
+        let blocking = tasks.iter().find(|t| t.id == blocking.id).unwrap();
+        assert!(!blocking.is_blocked);
+
+        // 一つのタスクを取得した場合も同じ値になる
+        let fetched: Task = test
+            .server()
+            .get(&TaskPaths::one_task(&main.id))
+            .await
+            .json();
+        assert_eq!(fetched.is_blocked, main.is_blocked);
+        assert_eq!(fetched.blocking_task_ids, main.blocking_task_ids);
+        assert_eq!(fetched.descendant_count, main.descendant_count);
+        assert_eq!(fetched.done_descendant_count, main.done_descendant_count);
+        assert_eq!(fetched.progress, main.progress);
+
+        Ok(())
+    }
 }
@@ -3,17 +3,17 @@ use axum::{
     response::IntoResponse,
     Json,
 };
-use axum_garde::WithValidation;
 use axum_login::AuthSession;
 use http::StatusCode;
 
 use crate::{
     app::AppResult,
     features::task::db::{update_task, UpdateTaskArgs},
+    validation::WithValidation,
 };
 use crate::{
     app::AppState,
-    error::AppError,
+    error::{AppError, ProblemDetails},
     features::{auth::Auth, task::UpdateTask},
 };
 
@@ -23,13 +23,17 @@ use crate::{
     tag = super::TAG,
     path = super::TaskPaths::task_open_api(),
     request_body = UpdateTask,
-    responses((status = 200, body = Task)),
+    responses(
+        (status = 200, body = Task),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
     params(("id" = String, Path,))
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
     Path(id): Path<String>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, .. }): State<AppState>,
     WithValidation(payload): WithValidation<Json<UpdateTask>>,
 ) -> AppResult<impl IntoResponse> {
     let Some(user) = auth_session.user else {
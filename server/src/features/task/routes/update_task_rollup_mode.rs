@@ -0,0 +1,295 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        sub_task::{db::TaskAndUser, usecases::update_task_and_ancestors_status},
+        task::{
+            db::{find_task, update_task_rollup_mode, FindTaskArgs, UpdateTaskRollupModeArgs},
+            UpdateTaskRollupMode,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    put,
+    tag = super::TAG,
+    path = super::TaskPaths::update_task_rollup_mode_open_api(),
+    request_body = UpdateTaskRollupMode,
+    responses(
+        (status = 200, body = Task),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    State(AppState { db, .. }): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateTaskRollupMode>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    update_task_rollup_mode(
+        &mut tx,
+        UpdateTaskRollupModeArgs {
+            id: &id,
+            rollup_mode: &payload.rollup_mode,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    // 自動で更新するモードに変更された場合は、サブタスクの状態に合わせてタスクとその祖先メインタスクを更新する
+    update_task_and_ancestors_status::action(
+        &mut tx,
+        TaskAndUser {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    let task = find_task(
+        &mut tx,
+        FindTaskArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(task))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::task::{
+            db::{find_task, FindTaskArgs},
+            routes::TaskPaths,
+            test::task_factory,
+            Task, TaskRollupMode, TaskStatus, UpdateTaskRollupMode, UpdateTaskStatus,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 手動モードのメインタスクはサブタスクがすべて完了しても完了にならない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+
+        let res = test
+            .server()
+            .put(&TaskPaths::one_update_task_rollup_mode(&main.id))
+            .json(&UpdateTaskRollupMode {
+                rollup_mode: TaskRollupMode::Manual,
+            })
+            .await;
+        res.assert_status_ok();
+        assert_eq!(res.json::<Task>().rollup_mode, TaskRollupMode::Manual);
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&sub.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let mut conn = db.acquire().await?;
+        let main = find_task(
+            &mut conn,
+            FindTaskArgs {
+                task_id: &main.id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        assert_eq!(main.status, TaskStatus::Todo);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 手動モードのメインタスクはサブタスクが未完了になっても未完了にならない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create(
+            &db,
+            Task {
+                status: TaskStatus::Done,
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let sub = task_factory::create_sub_task(
+            &db,
+            &main.id,
+            Task {
+                status: TaskStatus::Done,
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        test.server()
+            .put(&TaskPaths::one_update_task_rollup_mode(&main.id))
+            .json(&UpdateTaskRollupMode {
+                rollup_mode: TaskRollupMode::Manual,
+            })
+            .await
+            .assert_status_ok();
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&sub.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Todo,
+            })
+            .await
+            .assert_status_ok();
+
+        let mut conn = db.acquire().await?;
+        let main = find_task(
+            &mut conn,
+            FindTaskArgs {
+                task_id: &main.id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        assert_eq!(main.status, TaskStatus::Done);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 未完了への自動更新だけを行うメインタスクは未完了にだけなる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+
+        test.server()
+            .put(&TaskPaths::one_update_task_rollup_mode(&main.id))
+            .json(&UpdateTaskRollupMode {
+                rollup_mode: TaskRollupMode::AutoReopenOnly,
+            })
+            .await
+            .assert_status_ok();
+
+        // サブタスクが完了してもメインタスクは完了にならない
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&sub.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let mut conn = db.acquire().await?;
+        let main_task = find_task(
+            &mut conn,
+            FindTaskArgs {
+                task_id: &main.id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        assert_eq!(main_task.status, TaskStatus::Todo);
+
+        // メインタスクを手動で完了にしたあと、サブタスクが未完了になるとメインタスクも未完了になる
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&main.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&sub.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Todo,
+            })
+            .await
+            .assert_status_ok();
+
+        let main_task = find_task(
+            &mut conn,
+            FindTaskArgs {
+                task_id: &main.id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        assert_eq!(main_task.status, TaskStatus::Todo);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 自動モードに戻すとサブタスクの状態に合わせて更新される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+
+        test.server()
+            .put(&TaskPaths::one_update_task_rollup_mode(&main.id))
+            .json(&UpdateTaskRollupMode {
+                rollup_mode: TaskRollupMode::Manual,
+            })
+            .await
+            .assert_status_ok();
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&sub.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let updated: Task = test
+            .server()
+            .put(&TaskPaths::one_update_task_rollup_mode(&main.id))
+            .json(&UpdateTaskRollupMode {
+                rollup_mode: TaskRollupMode::Auto,
+            })
+            .await
+            .json();
+        assert_eq!(updated.status, TaskStatus::Done);
+
+        Ok(())
+    }
+}
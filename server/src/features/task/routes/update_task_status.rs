@@ -4,15 +4,14 @@ use axum::{
     Json,
 };
 use axum_login::AuthSession;
-use http::StatusCode;
 
 use crate::{
     app::{AppResult, AppState},
-    error::AppError,
+    error::{AppError, ErrorCode, ProblemDetails},
     features::{
         auth::Auth,
-        block_task::db::{is_all_blocking_tasks_done, update_all_unblocked_descendant_sub_tasks},
-        sub_task::db::{update_task_and_all_ancestor_main_tasks_status, TaskAndUser},
+        block_task::db::update_all_unblocked_descendant_sub_tasks,
+        sub_task::{db::TaskAndUser, usecases::update_task_and_ancestors_status},
         task::{
             db::{update_task_status, UpdateTaskStatusArgs},
             TaskStatus, UpdateTaskStatus,
@@ -25,12 +24,17 @@ use crate::{
     put,
     tag = super::TAG,
     path = super::TaskPaths::update_task_status_open_api(),
-    responses((status = 200, body = Task)),
+    responses(
+        (status = 200, body = Task),
+        (status = 400, response = ProblemDetails),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
     params(("id" = String, Path,))
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, task_graph, .. }): State<AppState>,
     Path(id): Path<String>,
     Json(payload): Json<UpdateTaskStatus>,
 ) -> AppResult<impl IntoResponse> {
@@ -39,14 +43,12 @@ pub async fn handler(
     };
 
     let mut tx = db.begin().await?;
+    let graph = task_graph.get_or_load(&mut tx, &user.id).await?;
 
     // ブロックしているタスクが完了状態かを確認する。
     // ブロックしているタスクが完了状態ではない場合、TodoからDoneには変更できない。
-    if !is_all_blocking_tasks_done(&mut tx, &id).await? && payload.status == TaskStatus::Done {
-        return Err(AppError::new(
-            StatusCode::BAD_REQUEST,
-            Some("ブロックしているタスクが全て完了状態ではありません"),
-        ));
+    if !graph.is_all_blocking_tasks_done(&id) && payload.status == TaskStatus::Done {
+        return Err(AppError::with_code(ErrorCode::BlockingNotDone));
     }
 
     let updated_task = update_task_status(
@@ -59,9 +61,13 @@ pub async fn handler(
     )
     .await?;
 
+    // 更新したタスクの状態をグラフにも反映してから、
     //　ブロッキングタスクにブロックされていない子孫サブタスクをすべて更新する
+    let mut updated_graph = (*graph).clone();
+    updated_graph.set_status(&updated_task.id, payload.status);
     update_all_unblocked_descendant_sub_tasks(
         &mut tx,
+        Some(&updated_graph),
         UpdateTaskStatusArgs {
             id: &updated_task.id,
             user_id: &user.id,
@@ -71,8 +77,9 @@ pub async fn handler(
     .await?;
 
     // 子孫サブタスクを更新しているので、タスクの状態が変更している可能性があるため、
-    // タスクをもう一度更新して、その祖先メインタスクも更新する
-    update_task_and_all_ancestor_main_tasks_status(
+    // タスクをもう一度更新して、その祖先メインタスクも更新する。
+    // 完了状態になった繰り返しのタスクがあれば、次のタスクも作成される
+    update_task_and_ancestors_status::action(
         &mut tx,
         TaskAndUser {
             task_id: &updated_task.id,
@@ -88,13 +95,21 @@ pub async fn handler(
 
 #[cfg(test)]
 mod tests {
+    use http::StatusCode;
+
     use crate::{
         app::{tests::AppTest, AppResult, Db},
-        features::task::{
-            db::{find_task, FindTaskArgs},
-            routes::TaskPaths,
-            test::task_factory,
-            Task, TaskStatus, UpdateTaskStatus,
+        error::{ErrorCode, ProblemDetails},
+        features::{
+            recurrence::{
+                routes::RecurrencePaths, RecurrenceFrequency, TaskRecurrence, UpdateTaskRecurrence,
+            },
+            task::{
+                db::{find_task, FindTaskArgs},
+                routes::TaskPaths,
+                test::task_factory,
+                StatusTransition, Task, TaskStatus, UpdateTaskStatus,
+            },
         },
     };
 
@@ -476,7 +491,9 @@ mod tests {
                 status: TaskStatus::Done,
             })
             .await;
-        res.assert_status_not_ok();
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+        let problem: ProblemDetails = res.json();
+        assert_eq!(problem.code, ErrorCode::BlockingNotDone);
 
         let mut conn = db.acquire().await?;
         let task = find_task(
@@ -822,4 +839,300 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn 繰り返しのタスクを完了すると次のタスクが作成される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        test.server()
+            .put(&RecurrencePaths::one_task_recurrence(&main.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Weekly,
+                interval: 1,
+                due_on: Some("2024/03/01".into()),
+            })
+            .await
+            .assert_status_ok();
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&main.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+        assert_eq!(tasks.len(), 4);
+        let next = tasks
+            .iter()
+            .find(|t| t.id != main.id && t.sub_task_ids.len() == 1)
+            .expect("次のタスクが作成されていない");
+        assert_eq!(next.title, main.title);
+        assert_eq!(next.status, TaskStatus::Todo);
+        let next_sub = tasks
+            .iter()
+            .find(|t| t.id == next.sub_task_ids[0])
+            .expect("サブタスクが複製されていない");
+        assert_ne!(next_sub.id, sub.id);
+        assert_eq!(next_sub.status, TaskStatus::Todo);
+
+        // ルールは次のタスクに移り、予定日が進む
+        let recurrence: TaskRecurrence = test
+            .server()
+            .get(&RecurrencePaths::one_task_recurrence(&next.id))
+            .await
+            .json();
+        assert_eq!(recurrence.due_on, "2024/03/08");
+        let res = test
+            .server()
+            .get(&RecurrencePaths::one_task_recurrence(&main.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        // 完了したタスクを未完了に戻してもう一度完了しても、次のタスクは増えない
+        for status in [TaskStatus::Todo, TaskStatus::Done] {
+            test.server()
+                .put(&TaskPaths::one_update_task_status(&main.id))
+                .json(&UpdateTaskStatus { status })
+                .await
+                .assert_status_ok();
+        }
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+        assert_eq!(tasks.len(), 4);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 月ごとの繰り返しでは予定日が月単位で進む(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        test.server()
+            .put(&RecurrencePaths::one_task_recurrence(&task.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Monthly,
+                interval: 2,
+                due_on: Some("2024/11/15".into()),
+            })
+            .await
+            .assert_status_ok();
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&task.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+        let next = tasks
+            .iter()
+            .find(|t| t.id != task.id)
+            .expect("次のタスクが作成されていない");
+        let recurrence: TaskRecurrence = test
+            .server()
+            .get(&RecurrencePaths::one_task_recurrence(&next.id))
+            .await
+            .json();
+        assert_eq!(recurrence.frequency, RecurrenceFrequency::Monthly);
+        assert_eq!(recurrence.due_on, "2025/01/15");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 月ごとの繰り返しで次の月に同じ日がなければ月末になる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        test.server()
+            .put(&RecurrencePaths::one_task_recurrence(&task.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Monthly,
+                interval: 1,
+                due_on: Some("2024/01/31".into()),
+            })
+            .await
+            .assert_status_ok();
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&task.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+        let next = tasks
+            .iter()
+            .find(|t| t.id != task.id)
+            .expect("次のタスクが作成されていない");
+        let recurrence: TaskRecurrence = test
+            .server()
+            .get(&RecurrencePaths::one_task_recurrence(&next.id))
+            .await
+            .json();
+        assert_eq!(recurrence.due_on, "2024/02/29");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn サブタスクの完了で完了した繰り返しのタスクも次のタスクが作成される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        test.server()
+            .put(&RecurrencePaths::one_task_recurrence(&main.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Daily,
+                interval: 1,
+                due_on: Some("2024/02/28".into()),
+            })
+            .await
+            .assert_status_ok();
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&sub.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+        assert_eq!(tasks.len(), 4);
+        let next = tasks
+            .iter()
+            .find(|t| t.id != main.id && t.sub_task_ids.len() == 1)
+            .expect("次のタスクが作成されていない");
+        let recurrence: TaskRecurrence = test
+            .server()
+            .get(&RecurrencePaths::one_task_recurrence(&next.id))
+            .await
+            .json();
+        assert_eq!(recurrence.due_on, "2024/02/29");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 繰り返しのサブタスクの次のタスクは同じメインタスクのサブタスクになる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        test.server()
+            .put(&RecurrencePaths::one_task_recurrence(&sub.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Daily,
+                interval: 1,
+                due_on: Some("2024/03/01".into()),
+            })
+            .await
+            .assert_status_ok();
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&sub.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let tasks: Vec<Task> = test.server().get(&TaskPaths::tasks()).await.json();
+        assert_eq!(tasks.len(), 3);
+        let main = tasks.iter().find(|t| t.id == main.id).unwrap();
+        let next_id = main
+            .sub_task_ids
+            .iter()
+            .find(|id| **id != sub.id)
+            .expect("次のタスクがメインタスクにつながっていない");
+        let next = tasks.iter().find(|t| t.id == *next_id).unwrap();
+        assert_eq!(next.status, TaskStatus::Todo);
+        // 次のタスクは未完了の繰り返しのタスクなので、メインタスクは完了のままになる
+        assert_eq!(main.status, TaskStatus::Done);
+
+        // メインタスクが一度完了してから未完了に戻されていない
+        let transitions: Vec<StatusTransition> = test
+            .server()
+            .get(&TaskPaths::one_status_transitions(&main.id))
+            .await
+            .json();
+        let transitions: Vec<_> = transitions
+            .iter()
+            .map(|t| (t.from_status, t.to_status))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (None, TaskStatus::Todo),
+                (Some(TaskStatus::Todo), TaskStatus::Done),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 繰り返しのサブタスクは完了していなくてもメインタスクの完了を妨げない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let recurring = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        let sub = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        test.server()
+            .put(&RecurrencePaths::one_task_recurrence(&recurring.id))
+            .json(&UpdateTaskRecurrence {
+                frequency: RecurrenceFrequency::Daily,
+                interval: 1,
+                due_on: None,
+            })
+            .await
+            .assert_status_ok();
+
+        test.server()
+            .put(&TaskPaths::one_update_task_status(&sub.id))
+            .json(&UpdateTaskStatus {
+                status: TaskStatus::Done,
+            })
+            .await
+            .assert_status_ok();
+
+        let mut conn = db.acquire().await?;
+        let main = find_task(
+            &mut conn,
+            FindTaskArgs {
+                task_id: &main.id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        assert_eq!(main.status, TaskStatus::Done);
+
+        Ok(())
+    }
 }
@@ -11,13 +11,24 @@ pub mod task_factory {
             Task {
                 id: Uuid::new_v4().into(),
                 status: Default::default(),
+                rollup_mode: Default::default(),
                 user_id: "user_id".into(),
                 title: "title".into(),
                 description: "description".into(),
                 sub_task_ids: Vec::new(),
                 blocked_task_ids: Vec::new(),
+                blocking_task_ids: Vec::new(),
+                is_blocked: false,
+                descendant_count: 0,
+                done_descendant_count: 0,
+                progress: 0.0,
+                checklist_count: 0,
+                checked_checklist_count: 0,
+                comment_count: 0,
+                estimated_minutes: None,
                 created_at: "".into(),
                 updated_at: "".into(),
+                completed_at: None,
             }
         }
     }
@@ -50,16 +61,27 @@ pub mod task_factory {
     pub async fn create_sub_task(db: &Db, main_task_id: &str, task: Task) -> AppResult<Task> {
         create(db, task.clone()).await?;
 
+        create_sub_task_connection(db, &task.user_id, main_task_id, &task.id).await?;
+
+        Ok(task)
+    }
+
+    pub async fn create_sub_task_connection(
+        db: &Db,
+        user_id: &str,
+        main_task_id: &str,
+        sub_task_id: &str,
+    ) -> AppResult<()> {
         sqlx::query!(
             "INSERT INTO sub_tasks(main_task_id, sub_task_id, user_id) VALUES($1, $2, $3);",
             main_task_id,
-            task.id,
-            task.user_id,
+            sub_task_id,
+            user_id,
         )
         .execute(db)
         .await?;
 
-        Ok(task)
+        Ok(())
     }
 
     pub async fn create_default_sub_task(
@@ -121,6 +143,21 @@ pub mod task_factory {
     }
 }
 
+#[cfg(test)]
+pub mod random {
+    /// テストごとに同じ結果になるように、シードを固定した簡単な乱数生成器を使う
+    pub struct Lcg(pub u64);
+    impl Lcg {
+        pub fn next(&mut self, max: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) as usize) % max
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod routes {
     use crate::features::task;
@@ -132,5 +169,14 @@ pub mod routes {
         pub fn one_update_task_status(id: &str) -> String {
             Self::one_task(id) + &Self::update_task_status_base()
         }
+        pub fn one_update_task_rollup_mode(id: &str) -> String {
+            Self::one_task(id) + &Self::update_task_rollup_mode_base()
+        }
+        pub fn one_status_transitions(id: &str) -> String {
+            Self::one_task(id) + &Self::status_transitions_base()
+        }
+        pub fn one_duplicate_task(id: &str) -> String {
+            Self::one_task(id) + &Self::duplicate_task_base()
+        }
     }
 }
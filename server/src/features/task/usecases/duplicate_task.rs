@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+
+use crate::{
+    app::Connection,
+    features::{
+        block_task::db::{insert_block_task_connection, InsertBlockTaskConnectionArgs},
+        sub_task::db::{insert_sub_task_connection, InsertSubTaskConnectionArgs},
+        task::{
+            db::{copy_task, CopyTaskArgs},
+            graph::TaskGraph,
+        },
+        task_node::db::{copy_task_node_info, CopyTaskNodeInfoArgs},
+    },
+};
+
+/// 複製したタスクのノードを、元のノードと重ならないようにずらす量
+pub const DUPLICATED_NODE_OFFSET: f64 = 40.0;
+
+pub struct DuplicateTaskArgs<'a> {
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+    pub include_descendants: bool,
+    pub copy_blocking_connections: bool,
+    pub reset_status: bool,
+    /// 複製前のタスクのグラフ
+    pub graph: &'a TaskGraph,
+}
+
+pub enum DuplicateTaskError {
+    TaskNotFound,
+    Unknown(anyhow::Error),
+}
+
+/// タスクを複製し、複製したタスクのIDを返す。先頭が指定したタスクを複製したタスクになる。
+/// 複製したタスクはメインタスクを持たない。
+pub async fn action<'a>(
+    db: &mut Connection,
+    args: DuplicateTaskArgs<'a>,
+) -> Result<Vec<String>, DuplicateTaskError> {
+    if !args.graph.contains(args.task_id) {
+        return Err(DuplicateTaskError::TaskNotFound);
+    }
+
+    // メインタスクを先に作成するために、指定したタスクから順番に並べる
+    let source_ids: Vec<&str> = if args.include_descendants {
+        args.graph.subtree_task_ids(args.task_id)
+    } else {
+        vec![args.task_id]
+    };
+
+    // 複製元のタスクID -> 複製したタスクID
+    let new_ids: HashMap<&str, String> = source_ids
+        .iter()
+        .map(|id| (*id, uuid::Uuid::new_v4().to_string()))
+        .collect();
+
+    for source_id in &source_ids {
+        let new_id = &new_ids[source_id];
+
+        copy_task(
+            &mut *db,
+            CopyTaskArgs {
+                source_task_id: source_id,
+                new_task_id: new_id,
+                user_id: args.user_id,
+                reset_status: args.reset_status,
+            },
+        )
+        .await
+        .map_err(DuplicateTaskError::Unknown)?;
+
+        copy_task_node_info(
+            &mut *db,
+            CopyTaskNodeInfoArgs {
+                source_task_id: source_id,
+                task_id: new_id,
+                user_id: args.user_id,
+                offset_x: DUPLICATED_NODE_OFFSET,
+                offset_y: DUPLICATED_NODE_OFFSET,
+            },
+        )
+        .await
+        .map_err(DuplicateTaskError::Unknown)?;
+    }
+
+    // 指定したタスク自身はメインタスクとつなげない
+    for source_id in source_ids.iter().skip(1) {
+        let main_task_id = args
+            .graph
+            .main_task_id(source_id)
+            .and_then(|id| new_ids.get(id))
+            .ok_or_else(|| DuplicateTaskError::Unknown(anyhow!("main task not found")))?;
+
+        insert_sub_task_connection(
+            &mut *db,
+            InsertSubTaskConnectionArgs {
+                main_task_id,
+                sub_task_id: &new_ids[source_id],
+                user_id: args.user_id,
+            },
+        )
+        .await
+        .map_err(DuplicateTaskError::Unknown)?;
+    }
+
+    if args.copy_blocking_connections {
+        for source_id in &source_ids {
+            // 複製したタスク同士のつながりだけを複製する
+            for blocking_task_id in args.graph.blocking_task_ids(source_id) {
+                let Some(new_blocking_task_id) = new_ids.get(blocking_task_id.as_str()) else {
+                    continue;
+                };
+
+                insert_block_task_connection(
+                    &mut *db,
+                    InsertBlockTaskConnectionArgs {
+                        blocking_task_id: new_blocking_task_id,
+                        blocked_task_id: &new_ids[source_id],
+                        user_id: args.user_id,
+                    },
+                )
+                .await
+                .map_err(DuplicateTaskError::Unknown)?;
+            }
+        }
+    }
+
+    Ok(source_ids.iter().map(|id| new_ids[id].clone()).collect())
+}
@@ -11,6 +11,7 @@ use super::{TaskNode, TaskNodeInfo};
 pub struct InsertTaskNodeArgs<'a> {
     pub task_id: &'a str,
     pub title: &'a str,
+    pub description: &'a str,
     pub status: &'a TaskStatus,
     pub user_id: &'a str,
     pub x: f64,
@@ -21,6 +22,7 @@ pub async fn insert_task_node<'a>(
     InsertTaskNodeArgs {
         task_id,
         title,
+        description,
         status,
         user_id,
         x,
@@ -32,7 +34,7 @@ pub async fn insert_task_node<'a>(
         InsertTaskArgs {
             id: task_id,
             title,
-            description: "",
+            description,
             user_id,
             status,
         },
@@ -67,10 +69,7 @@ pub async fn find_task_node<'a>(
     Ok(TaskNode { task, node_info })
 }
 
-pub async fn find_task_nodes<'a>(
-    db: &mut Connection,
-    user_id: &str,
-) -> anyhow::Result<Vec<TaskNode>> {
+pub async fn find_task_nodes(db: &mut Connection, user_id: &str) -> anyhow::Result<Vec<TaskNode>> {
     let tasks = find_tasks(db, user_id).await?;
     let node_info_list = find_task_node_info_list(db, user_id).await?;
 
@@ -129,6 +128,44 @@ pub async fn insert_task_node_info<'a>(
     Ok(task_node_info)
 }
 
+pub struct CopyTaskNodeInfoArgs<'a> {
+    pub source_task_id: &'a str,
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+    pub offset_x: f64,
+    pub offset_y: f64,
+}
+/// コピー元のタスクのノード情報を、位置をずらしてコピーする。
+/// コピー元のタスクにノード情報がない場合は何もしない。
+pub async fn copy_task_node_info<'a>(
+    db: &mut Connection,
+    CopyTaskNodeInfoArgs {
+        source_task_id,
+        task_id,
+        user_id,
+        offset_x,
+        offset_y,
+    }: CopyTaskNodeInfoArgs<'a>,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO task_node_info(task_id, user_id, x, y)
+        SELECT $1, user_id, x + $2, y + $3
+        FROM task_node_info
+        WHERE task_id = $4 AND user_id = $5;
+        "#,
+        task_id,
+        offset_x,
+        offset_y,
+        source_task_id,
+        user_id,
+    )
+    .execute(&mut *db)
+    .await?;
+
+    Ok(())
+}
+
 pub struct UpdateTaskNodeInfoArgs<'a> {
     pub task_id: &'a str,
     pub user_id: &'a str,
@@ -201,7 +238,7 @@ pub async fn find_task_node_info<'a>(
     Ok(task_node_info)
 }
 
-pub async fn find_task_node_info_list<'a>(
+pub async fn find_task_node_info_list(
     db: &mut Connection,
     user_id: &str,
 ) -> anyhow::Result<Vec<TaskNodeInfo>> {
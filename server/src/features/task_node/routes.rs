@@ -1,10 +1,14 @@
 use axum::{
+    middleware,
     routing::{get, put},
     Router,
 };
 use axum_login::login_required;
 
-use crate::{app::AppState, features::auth::Auth};
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope},
+};
 
 pub mod create_task_node;
 pub mod get_task_node;
@@ -51,5 +55,6 @@ pub fn router() -> Router<AppState> {
             put(update_task_node_info::handler),
         )
         .route(&TaskNodePaths::task_node(), get(get_task_node::handler))
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
         .route_layer(login_required!(Auth))
 }
@@ -1,25 +1,29 @@
 use axum::response::IntoResponse;
 use axum::{extract::State, Json};
-use axum_garde::WithValidation;
 use axum_login::AuthSession;
 use http::StatusCode;
 
 use crate::app::{AppResult, AppState};
-use crate::error::AppError;
+use crate::error::{AppError, ProblemDetails};
 use crate::features::auth::Auth;
 use crate::features::task_node::db::{insert_task_node, InsertTaskNodeArgs};
 use crate::features::task_node::CreateTaskNode;
+use crate::validation::WithValidation;
 
 #[tracing::instrument(err)]
 #[utoipa::path(
     post,
     tag = super::TAG,
     path = super::TaskNodePaths::task_nodes(),
-    request_body = CreateTaskNode, responses((status = 201, body = TaskNode))
+    request_body = CreateTaskNode, responses(
+        (status = 201, body = TaskNode),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, .. }): State<AppState>,
     WithValidation(payload): WithValidation<Json<CreateTaskNode>>,
 ) -> AppResult<impl IntoResponse> {
     let Some(user) = auth_session.user else {
@@ -34,6 +38,7 @@ pub async fn handler(
         InsertTaskNodeArgs {
             task_id: &task_id,
             title: &payload.task.title,
+            description: "",
             status: &Default::default(),
             user_id: &user.id,
             x: payload.x,
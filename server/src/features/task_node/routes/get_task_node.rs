@@ -8,7 +8,7 @@ use http::StatusCode;
 
 use crate::{
     app::{AppResult, AppState},
-    error::AppError,
+    error::{AppError, ProblemDetails},
     features::{
         auth::Auth,
         task_node::db::{find_task_node, FindTaskNodeArgs},
@@ -20,12 +20,16 @@ use crate::{
     get,
     tag = super::TAG,
     path = super::TaskNodePaths::task_node_open_api(),
-    responses((status = 200, body = TaskNode)),
+    responses(
+        (status = 200, body = TaskNode),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
     params(("id" = String, Path,))
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, .. }): State<AppState>,
     Path(id): Path<String>,
 ) -> AppResult<impl IntoResponse> {
     let Some(user) = auth_session.user else {
@@ -4,18 +4,26 @@ use http::StatusCode;
 
 use crate::app::AppResult;
 use crate::features::task_node::db::find_task_nodes;
-use crate::{app::AppState, error::AppError, features::auth::Auth};
+use crate::{
+    app::AppState,
+    error::{AppError, ProblemDetails},
+    features::auth::Auth,
+};
 
 #[tracing::instrument(err)]
 #[utoipa::path(
     get,
     tag = super::TAG,
     path = super::TaskNodePaths::task_nodes(),
-    responses((status = 200, body = [TaskNode]))
+    responses(
+        (status = 200, body = [TaskNode]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, .. }): State<AppState>,
 ) -> AppResult<impl IntoResponse> {
     let Some(user) = auth_session.user else {
         return Err(AppError::unauthorized());
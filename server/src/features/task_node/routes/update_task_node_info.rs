@@ -12,7 +12,7 @@ use crate::{
 };
 use crate::{
     app::AppState,
-    error::AppError,
+    error::{AppError, ProblemDetails},
     features::{auth::Auth, task_node::UpdateTaskNodeInfo},
 };
 
@@ -21,13 +21,17 @@ use crate::{
     put,
     tag = super::TAG,
     path = super::TaskNodePaths::task_node_info_open_api(),
-    responses((status = 200, body = TaskNodeInfo)),
+    responses(
+        (status = 200, body = TaskNodeInfo),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
     params(("id" = String, Path,))
 )]
 pub async fn handler(
     auth_session: AuthSession<Auth>,
     Path(id): Path<String>,
-    State(AppState { db }): State<AppState>,
+    State(AppState { db, .. }): State<AppState>,
     Json(payload): Json<UpdateTaskNodeInfo>,
 ) -> AppResult<impl IntoResponse> {
     let Some(user) = auth_session.user else {
@@ -0,0 +1,76 @@
+pub mod db;
+pub mod routes;
+pub mod test;
+pub mod usecases;
+
+use crate::validation;
+use garde::Validate;
+pub use routes::router;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// タスクのツリーを再利用するためのテンプレート
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct Template {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    /// メインタスクがサブタスクより前になるように並んでいる。先頭がルートのタスクになる。
+    pub tasks: Vec<TemplateTask>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct TemplateTask {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// ルートのタスクのノードからの相対位置
+    pub x: f64,
+    pub y: f64,
+    /// ルートのタスクの場合はNone
+    pub main_task_id: Option<String>,
+    /// このタスクをブロックしているテンプレート内のタスク
+    pub blocking_task_ids: Vec<String>,
+}
+
+/// 一覧で返すテンプレートの概要
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct TemplateSummary {
+    pub id: String,
+    pub name: String,
+    pub task_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct CreateTemplate {
+    #[garde(custom(validation::length(1, 100)))]
+    #[schema(min_length = 1, max_length = 100)]
+    pub name: String,
+
+    /// このタスクとすべての子孫サブタスクをテンプレートにする
+    #[garde(skip)]
+    pub task_id: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct UpdateTemplate {
+    #[garde(custom(validation::length(1, 100)))]
+    #[schema(min_length = 1, max_length = 100)]
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug)]
+pub struct InstantiateTemplate {
+    /// ルートのタスクのノードを置く位置
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct DeleteTemplateResponse {
+    pub template_id: String,
+}
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use crate::app::Connection;
+
+use super::{Template, TemplateSummary, TemplateTask};
+
+pub struct InsertTemplateArgs<'a> {
+    pub id: &'a str,
+    pub user_id: &'a str,
+    pub name: &'a str,
+}
+pub async fn insert_template<'a>(
+    db: &mut Connection,
+    args: InsertTemplateArgs<'a>,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        "INSERT INTO templates(id, user_id, name) VALUES($1, $2, $3) RETURNING id;",
+        args.id,
+        args.user_id,
+        args.name,
+    )
+    .fetch_one(&mut *db)
+    .await?;
+
+    Ok(())
+}
+
+pub struct CopyTaskToTemplateArgs<'a> {
+    pub template_task_id: &'a str,
+    pub template_id: &'a str,
+    pub task_id: &'a str,
+    /// ノードの相対位置の基準になるタスク
+    pub root_task_id: &'a str,
+    pub main_template_task_id: Option<&'a str>,
+    pub sort_order: i64,
+    pub user_id: &'a str,
+}
+/// タスクのタイトルや説明とノードの位置をテンプレートにコピーする。
+/// ノードがない場合は、基準になるタスクと同じ位置にする。
+pub async fn copy_task_to_template<'a>(
+    db: &mut Connection,
+    args: CopyTaskToTemplateArgs<'a>,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO template_tasks(id, template_id, title, description, x, y, main_template_task_id, sort_order)
+        SELECT
+            $1,
+            $2,
+            t.title,
+            t.description,
+            COALESCE(n.x - r.x, 0),
+            COALESCE(n.y - r.y, 0),
+            $3,
+            $4
+        FROM tasks t
+        LEFT OUTER JOIN task_node_info n ON (t.id = n.task_id)
+        LEFT OUTER JOIN task_node_info r ON (r.task_id = $5)
+        WHERE t.id = $6 AND t.user_id = $7
+        RETURNING id;
+        "#,
+        args.template_task_id,
+        args.template_id,
+        args.main_template_task_id,
+        args.sort_order,
+        args.root_task_id,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_one(&mut *db)
+    .await?;
+
+    Ok(())
+}
+
+pub struct InsertTemplateBlockingTaskArgs<'a> {
+    pub template_id: &'a str,
+    pub blocking_template_task_id: &'a str,
+    pub blocked_template_task_id: &'a str,
+}
+pub async fn insert_template_blocking_task<'a>(
+    db: &mut Connection,
+    args: InsertTemplateBlockingTaskArgs<'a>,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO template_blocking_tasks(template_id, blocking_template_task_id, blocked_template_task_id)
+        VALUES($1, $2, $3);
+        "#,
+        args.template_id,
+        args.blocking_template_task_id,
+        args.blocked_template_task_id,
+    )
+    .execute(&mut *db)
+    .await?;
+
+    Ok(())
+}
+
+pub struct FindTemplateArgs<'a> {
+    pub template_id: &'a str,
+    pub user_id: &'a str,
+}
+/// テンプレートが存在しない場合はNoneを返す
+pub async fn find_template<'a>(
+    db: &mut Connection,
+    FindTemplateArgs {
+        template_id,
+        user_id,
+    }: FindTemplateArgs<'a>,
+) -> anyhow::Result<Option<Template>> {
+    let Some(raw) = sqlx::query!(
+        "SELECT * FROM templates WHERE id = $1 AND user_id = $2;",
+        template_id,
+        user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let raw_tasks = sqlx::query!(
+        r#"
+        SELECT id, title, description, x, y, main_template_task_id
+        FROM template_tasks
+        WHERE template_id = $1
+        ORDER BY sort_order;
+        "#,
+        template_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let blocking_edges = sqlx::query!(
+        r#"
+        SELECT blocking_template_task_id, blocked_template_task_id
+        FROM template_blocking_tasks
+        WHERE template_id = $1
+        ORDER BY blocking_template_task_id;
+        "#,
+        template_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+    let mut blocking_task_ids: HashMap<String, Vec<String>> = HashMap::new();
+    for edge in blocking_edges {
+        blocking_task_ids
+            .entry(edge.blocked_template_task_id)
+            .or_default()
+            .push(edge.blocking_template_task_id);
+    }
+
+    let tasks = raw_tasks
+        .into_iter()
+        .map(|t| TemplateTask {
+            blocking_task_ids: blocking_task_ids.remove(&t.id).unwrap_or_default(),
+            id: t.id,
+            title: t.title,
+            description: t.description,
+            x: t.x,
+            y: t.y,
+            main_task_id: t.main_template_task_id,
+        })
+        .collect();
+
+    Ok(Some(Template {
+        id: raw.id,
+        user_id: raw.user_id,
+        name: raw.name,
+        tasks,
+        created_at: raw.created_at,
+        updated_at: raw.updated_at,
+    }))
+}
+
+pub async fn find_templates(
+    db: &mut Connection,
+    user_id: &str,
+) -> anyhow::Result<Vec<TemplateSummary>> {
+    let templates = sqlx::query_as!(
+        TemplateSummary,
+        r#"
+        SELECT
+            t.id,
+            t.name,
+            (SELECT COUNT(*) FROM template_tasks tt WHERE tt.template_id = t.id) as "task_count!: i64",
+            t.created_at,
+            t.updated_at
+        FROM templates t
+        WHERE t.user_id = $1
+        ORDER BY t.created_at, t.id;
+        "#,
+        user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    Ok(templates)
+}
+
+pub struct UpdateTemplateArgs<'a> {
+    pub template_id: &'a str,
+    pub name: &'a str,
+    pub user_id: &'a str,
+}
+/// 更新したテンプレートのIDを返す。テンプレートが存在しない場合はNoneを返す
+pub async fn update_template<'a>(
+    db: &mut Connection,
+    args: UpdateTemplateArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        "UPDATE templates SET name = $1 WHERE id = $2 AND user_id = $3 RETURNING id;",
+        args.name,
+        args.template_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+pub struct DeleteTemplateArgs<'a> {
+    pub template_id: &'a str,
+    pub user_id: &'a str,
+}
+/// 削除したテンプレートのIDを返す。テンプレートが存在しない場合はNoneを返す
+pub async fn delete_template<'a>(
+    db: &mut Connection,
+    args: DeleteTemplateArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        "DELETE FROM templates WHERE id = $1 AND user_id = $2 RETURNING id;",
+        args.template_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
@@ -0,0 +1,67 @@
+use axum::{
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use axum_login::login_required;
+
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope},
+};
+
+pub mod create_template;
+pub mod delete_template;
+pub mod get_template;
+pub mod get_templates;
+pub mod instantiate_template;
+pub mod update_template;
+
+pub const TAG: &str = "template";
+
+pub struct TemplatePaths;
+impl TemplatePaths {
+    pub fn templates() -> String {
+        "/templates".into()
+    }
+
+    pub fn template() -> String {
+        Self::templates() + "/:id"
+    }
+
+    pub fn template_open_api() -> String {
+        Self::templates() + "/{id}"
+    }
+
+    pub fn instantiate_template_base() -> String {
+        "/instantiate".into()
+    }
+
+    pub fn instantiate_template() -> String {
+        Self::template() + &Self::instantiate_template_base()
+    }
+
+    pub fn instantiate_template_open_api() -> String {
+        Self::template_open_api() + &Self::instantiate_template_base()
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            &TemplatePaths::templates(),
+            get(get_templates::handler).post(create_template::handler),
+        )
+        .route(
+            &TemplatePaths::template(),
+            get(get_template::handler)
+                .put(update_template::handler)
+                .delete(delete_template::handler),
+        )
+        .route(
+            &TemplatePaths::instantiate_template(),
+            post(instantiate_template::handler),
+        )
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
+        .route_layer(login_required!(Auth))
+}
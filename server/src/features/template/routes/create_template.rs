@@ -0,0 +1,206 @@
+use anyhow::anyhow;
+use axum::{extract::State, response::IntoResponse, Json};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        auth::Auth,
+        template::{
+            db::{find_template, FindTemplateArgs},
+            usecases::create_template::{self, CreateTemplateArgs, CreateTemplateError},
+            CreateTemplate,
+        },
+    },
+    validation::WithValidation,
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::TemplatePaths::templates(),
+    request_body = CreateTemplate,
+    responses(
+        (status = 201, body = Template),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    )
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    State(AppState { db, task_graph, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<CreateTemplate>>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+    let graph = task_graph.get_or_load(&mut tx, &user.id).await?;
+
+    let template_id = uuid::Uuid::new_v4().to_string();
+    let result = create_template::action(
+        &mut tx,
+        CreateTemplateArgs {
+            template_id: &template_id,
+            name: &payload.name,
+            task_id: &payload.task_id,
+            user_id: &user.id,
+            graph: &graph,
+        },
+    )
+    .await;
+    match result {
+        Ok(()) => {}
+        Err(CreateTemplateError::TaskNotFound) => {
+            return Err(AppError::with_code(ErrorCode::TaskNotFound));
+        }
+        Err(CreateTemplateError::Unknown(e)) => return Err(e.into()),
+    }
+
+    let template = find_template(
+        &mut tx,
+        FindTemplateArgs {
+            template_id: &template_id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or(anyhow!("Error"))?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(template)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::{test::task_factory, Task, TaskStatus},
+            task_node::{test::task_node_factory, TaskNode, TaskNodeInfo},
+            template::{routes::TemplatePaths, CreateTemplate, Template},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn タスクのツリーからテンプレートを作成できる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        // outer -> main
+        // main --> sub1
+        // main --> sub2
+        // sub1 -> sub2
+        let outer = task_factory::create_with_user(&db, &user.id).await?;
+        let main = task_node_factory::create(
+            &db,
+            TaskNode {
+                task: Task {
+                    title: "main".into(),
+                    user_id: user.id.clone(),
+                    ..Default::default()
+                },
+                node_info: TaskNodeInfo {
+                    user_id: user.id.clone(),
+                    x: 100.0,
+                    y: 100.0,
+                    ..Default::default()
+                },
+            },
+        )
+        .await?
+        .task;
+        task_factory::create_blocking_connection(&db, &user.id, &outer.id, &main.id).await?;
+        let sub1 = task_node_factory::create(
+            &db,
+            TaskNode {
+                task: Task {
+                    title: "sub1".into(),
+                    status: TaskStatus::Done,
+                    user_id: user.id.clone(),
+                    ..Default::default()
+                },
+                node_info: TaskNodeInfo {
+                    user_id: user.id.clone(),
+                    x: 150.0,
+                    y: 200.0,
+                    ..Default::default()
+                },
+            },
+        )
+        .await?
+        .task;
+        task_factory::create_sub_task_connection(&db, &user.id, &main.id, &sub1.id).await?;
+        let sub2 = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        task_factory::create_blocking_connection(&db, &user.id, &sub1.id, &sub2.id).await?;
+
+        let res = test
+            .server()
+            .post(&TemplatePaths::templates())
+            .json(&CreateTemplate {
+                name: "template".into(),
+                task_id: main.id.clone(),
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+
+        let template: Template = res.json();
+        assert_eq!(template.name, "template");
+        assert_eq!(template.tasks.len(), 3);
+
+        let root = &template.tasks[0];
+        assert_eq!(root.title, "main");
+        assert_eq!((root.x, root.y), (0.0, 0.0));
+        assert!(root.main_task_id.is_none());
+        // テンプレートの外とのつながりは保存しない
+        assert!(root.blocking_task_ids.is_empty());
+
+        let template_sub1 = template.tasks.iter().find(|t| t.title == "sub1").unwrap();
+        assert_eq!((template_sub1.x, template_sub1.y), (50.0, 100.0));
+        assert_eq!(template_sub1.main_task_id.as_ref(), Some(&root.id));
+
+        let blocked: Vec<_> = template
+            .tasks
+            .iter()
+            .filter(|t| !t.blocking_task_ids.is_empty())
+            .collect();
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0].blocking_task_ids, vec![template_sub1.id.clone()]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクからテンプレートは作成できない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .post(&TemplatePaths::templates())
+            .json(&CreateTemplate {
+                name: "template".into(),
+                task_id: other_user_task.id,
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        template::{
+            db::{delete_template, DeleteTemplateArgs},
+            DeleteTemplateResponse,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    delete,
+    tag = super::TAG,
+    path = super::TemplatePaths::template_open_api(),
+    responses(
+        (status = 200, body = DeleteTemplateResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<DeleteTemplateResponse>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(deleted_id) = delete_template(
+        &mut tx,
+        DeleteTemplateArgs {
+            template_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    tx.commit().await?;
+
+    Ok(Json(DeleteTemplateResponse {
+        template_id: deleted_id,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::test::task_factory,
+            template::{
+                db::{find_template, FindTemplateArgs},
+                routes::TemplatePaths,
+                test::template_factory,
+            },
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn テンプレートを削除できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let template =
+            template_factory::create_from_task(&db, &user.id, &task.id, "template").await?;
+
+        test.server()
+            .delete(&TemplatePaths::one_template(&template.id))
+            .await
+            .assert_status_ok();
+
+        let mut conn = db.acquire().await?;
+        let deleted = find_template(
+            &mut conn,
+            FindTemplateArgs {
+                template_id: &template.id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        assert!(deleted.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のテンプレートは削除できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+        let template = template_factory::create_from_task(
+            &db,
+            &other_user.id,
+            &other_user_task.id,
+            "template",
+        )
+        .await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .delete(&TemplatePaths::one_template(&template.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
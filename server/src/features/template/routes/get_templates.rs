@@ -0,0 +1,74 @@
+use axum::{extract::State, Json};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        template::{db::find_templates, TemplateSummary},
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::TemplatePaths::templates(),
+    responses(
+        (status = 200, body = [TemplateSummary]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<Vec<TemplateSummary>>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let templates = find_templates(&mut tx, &user.id).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(templates))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::test::task_factory,
+            template::{routes::TemplatePaths, test::template_factory, TemplateSummary},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 自分のテンプレートの一覧を取得できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+        template_factory::create_from_task(&db, &other_user.id, &other_user_task.id, "other")
+            .await?;
+
+        let user = test.login(None).await?;
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        template_factory::create_from_task(&db, &user.id, &main.id, "template").await?;
+
+        let templates: Vec<TemplateSummary> =
+            test.server().get(&TemplatePaths::templates()).await.json();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].name, "template");
+        assert_eq!(templates[0].task_count, 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        task_node::db::{find_task_node, FindTaskNodeArgs},
+        template::{
+            db::{find_template, FindTemplateArgs},
+            usecases::instantiate_template::{self, InstantiateTemplateArgs},
+            InstantiateTemplate,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::TemplatePaths::instantiate_template_open_api(),
+    request_body = InstantiateTemplate,
+    responses(
+        (status = 201, body = [TaskNode]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+    Json(payload): Json<InstantiateTemplate>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(template) = find_template(
+        &mut tx,
+        FindTemplateArgs {
+            template_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    let task_ids = instantiate_template::action(
+        &mut tx,
+        InstantiateTemplateArgs {
+            template: &template,
+            user_id: &user.id,
+            x: payload.x,
+            y: payload.y,
+        },
+    )
+    .await?;
+
+    let mut task_nodes = Vec::new();
+    for task_id in &task_ids {
+        let task_node = find_task_node(
+            &mut tx,
+            FindTaskNodeArgs {
+                task_id,
+                user_id: &user.id,
+            },
+        )
+        .await?;
+        task_nodes.push(task_node);
+    }
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(task_nodes)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::{test::task_factory, Task, TaskStatus},
+            task_node::{test::task_node_factory, TaskNode, TaskNodeInfo},
+            template::{routes::TemplatePaths, test::template_factory, InstantiateTemplate},
+        },
+    };
+
+    #[sqlx::test]
+    async fn テンプレートからタスクを指定した位置に作成できる(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        // main --> sub1
+        // main --> sub2
+        // sub1 -> sub2
+        let main = task_node_factory::create(
+            &db,
+            TaskNode {
+                task: Task {
+                    title: "main".into(),
+                    user_id: user.id.clone(),
+                    ..Default::default()
+                },
+                node_info: TaskNodeInfo {
+                    user_id: user.id.clone(),
+                    x: 100.0,
+                    y: 100.0,
+                    ..Default::default()
+                },
+            },
+        )
+        .await?
+        .task;
+        let sub1 = task_node_factory::create(
+            &db,
+            TaskNode {
+                task: Task {
+                    title: "sub1".into(),
+                    status: TaskStatus::Done,
+                    user_id: user.id.clone(),
+                    ..Default::default()
+                },
+                node_info: TaskNodeInfo {
+                    user_id: user.id.clone(),
+                    x: 120.0,
+                    y: 200.0,
+                    ..Default::default()
+                },
+            },
+        )
+        .await?
+        .task;
+        task_factory::create_sub_task_connection(&db, &user.id, &main.id, &sub1.id).await?;
+        let sub2 = task_factory::create_default_sub_task(&db, &user.id, &main.id).await?;
+        task_factory::create_blocking_connection(&db, &user.id, &sub1.id, &sub2.id).await?;
+
+        let template =
+            template_factory::create_from_task(&db, &user.id, &main.id, "template").await?;
+
+        let res = test
+            .server()
+            .post(&TemplatePaths::one_instantiate_template(&template.id))
+            .json(&InstantiateTemplate { x: 0.0, y: 50.0 })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+
+        let nodes: Vec<TaskNode> = res.json();
+        assert_eq!(nodes.len(), 3);
+        assert!(nodes.iter().all(|n| n.task.status == TaskStatus::Todo));
+
+        let root = &nodes[0];
+        assert_eq!(root.task.title, "main");
+        assert_eq!((root.node_info.x, root.node_info.y), (0.0, 50.0));
+        assert_eq!(root.task.sub_task_ids.len(), 2);
+
+        let new_sub1 = nodes.iter().find(|n| n.task.title == "sub1").unwrap();
+        assert_eq!((new_sub1.node_info.x, new_sub1.node_info.y), (20.0, 150.0));
+        assert_eq!(new_sub1.task.blocked_task_ids.len(), 1);
+        assert_ne!(new_sub1.task.blocked_task_ids[0], sub2.id);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 存在しないテンプレートからは作成できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        let res = test
+            .server()
+            .post(&TemplatePaths::one_instantiate_template("unknown"))
+            .json(&InstantiateTemplate { x: 0.0, y: 0.0 })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
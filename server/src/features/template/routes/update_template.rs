@@ -0,0 +1,125 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        template::{
+            db::{find_template, update_template, FindTemplateArgs, UpdateTemplateArgs},
+            Template, UpdateTemplate,
+        },
+    },
+    validation::WithValidation,
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    put,
+    tag = super::TAG,
+    path = super::TemplatePaths::template_open_api(),
+    request_body = UpdateTemplate,
+    responses(
+        (status = 200, body = Template),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<UpdateTemplate>>,
+) -> AppResult<Json<Template>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    if update_template(
+        &mut tx,
+        UpdateTemplateArgs {
+            template_id: &id,
+            name: &payload.name,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .is_none()
+    {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    }
+
+    let template = find_template(
+        &mut tx,
+        FindTemplateArgs {
+            template_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or(anyhow!("Error"))?;
+
+    tx.commit().await?;
+
+    Ok(Json(template))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::test::task_factory,
+            template::{routes::TemplatePaths, test::template_factory, Template, UpdateTemplate},
+        },
+    };
+
+    #[sqlx::test]
+    async fn テンプレートの名前を変更できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let template = template_factory::create_from_task(&db, &user.id, &task.id, "old").await?;
+
+        let updated: Template = test
+            .server()
+            .put(&TemplatePaths::one_template(&template.id))
+            .json(&UpdateTemplate { name: "new".into() })
+            .await
+            .json();
+        assert_eq!(updated.name, "new");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 空の名前には変更できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let template = template_factory::create_from_task(&db, &user.id, &task.id, "old").await?;
+
+        let res = test
+            .server()
+            .put(&TemplatePaths::one_template(&template.id))
+            .json(&UpdateTemplate { name: "".into() })
+            .await;
+        assert_ne!(res.status_code(), StatusCode::OK);
+
+        Ok(())
+    }
+}
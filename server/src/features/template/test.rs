@@ -0,0 +1,69 @@
+#[cfg(test)]
+pub mod template_factory {
+    use crate::{
+        app::{AppResult, Db},
+        features::{
+            task::db::load_task_graph,
+            template::{
+                db::{find_template, FindTemplateArgs},
+                usecases::create_template::{self, CreateTemplateArgs, CreateTemplateError},
+                Template,
+            },
+        },
+    };
+
+    /// 指定したタスクとすべての子孫サブタスクからテンプレートを作成する
+    pub async fn create_from_task(
+        db: &Db,
+        user_id: &str,
+        task_id: &str,
+        name: &str,
+    ) -> AppResult<Template> {
+        let mut conn = db.acquire().await?;
+        let graph = load_task_graph(&mut conn, user_id).await?;
+
+        let template_id = uuid::Uuid::new_v4().to_string();
+        create_template::action(
+            &mut conn,
+            CreateTemplateArgs {
+                template_id: &template_id,
+                name,
+                task_id,
+                user_id,
+                graph: &graph,
+            },
+        )
+        .await
+        .map_err(|e| match e {
+            CreateTemplateError::TaskNotFound => anyhow::anyhow!("task not found"),
+            CreateTemplateError::Unknown(e) => e,
+        })?;
+
+        let template = find_template(
+            &mut conn,
+            FindTemplateArgs {
+                template_id: &template_id,
+                user_id,
+            },
+        )
+        .await?
+        .ok_or(anyhow::anyhow!("template not found"))?;
+
+        Ok(template)
+    }
+}
+
+#[cfg(test)]
+pub mod routes {
+    use crate::features::template;
+
+    impl template::routes::TemplatePaths {
+        pub fn one_template(id: &str) -> String {
+            Self::templates() + "/" + id
+        }
+
+        pub fn one_instantiate_template(id: &str) -> String {
+            Self::one_template(id) + &Self::instantiate_template_base()
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod create_template;
+pub mod instantiate_template;
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use crate::{
+    app::Connection,
+    features::{
+        task::graph::TaskGraph,
+        template::db::{
+            copy_task_to_template, insert_template, insert_template_blocking_task,
+            CopyTaskToTemplateArgs, InsertTemplateArgs, InsertTemplateBlockingTaskArgs,
+        },
+    },
+};
+
+pub struct CreateTemplateArgs<'a> {
+    pub template_id: &'a str,
+    pub name: &'a str,
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+    pub graph: &'a TaskGraph,
+}
+
+pub enum CreateTemplateError {
+    TaskNotFound,
+    Unknown(anyhow::Error),
+}
+
+/// 指定したタスクとすべての子孫サブタスクからテンプレートを作成する。
+/// ブロッキングタスクのつながりは、テンプレートに含まれるタスク同士のものだけを保存する。
+pub async fn action<'a>(
+    db: &mut Connection,
+    args: CreateTemplateArgs<'a>,
+) -> Result<(), CreateTemplateError> {
+    if !args.graph.contains(args.task_id) {
+        return Err(CreateTemplateError::TaskNotFound);
+    }
+
+    insert_template(
+        &mut *db,
+        InsertTemplateArgs {
+            id: args.template_id,
+            user_id: args.user_id,
+            name: args.name,
+        },
+    )
+    .await
+    .map_err(CreateTemplateError::Unknown)?;
+
+    let task_ids = args.graph.subtree_task_ids(args.task_id);
+
+    // タスクID -> テンプレート内のタスクID
+    let template_task_ids: HashMap<&str, String> = task_ids
+        .iter()
+        .map(|id| (*id, uuid::Uuid::new_v4().to_string()))
+        .collect();
+
+    for (sort_order, task_id) in task_ids.iter().enumerate() {
+        // ルートのタスクのメインタスクはテンプレートに含めない
+        let main_template_task_id = if *task_id == args.task_id {
+            None
+        } else {
+            args.graph
+                .main_task_id(task_id)
+                .and_then(|id| template_task_ids.get(id))
+                .map(String::as_str)
+        };
+
+        copy_task_to_template(
+            &mut *db,
+            CopyTaskToTemplateArgs {
+                template_task_id: &template_task_ids[task_id],
+                template_id: args.template_id,
+                task_id,
+                root_task_id: args.task_id,
+                main_template_task_id,
+                sort_order: sort_order as i64,
+                user_id: args.user_id,
+            },
+        )
+        .await
+        .map_err(CreateTemplateError::Unknown)?;
+    }
+
+    for task_id in &task_ids {
+        for blocking_task_id in args.graph.blocking_task_ids(task_id) {
+            let Some(blocking_template_task_id) = template_task_ids.get(blocking_task_id.as_str())
+            else {
+                continue;
+            };
+
+            insert_template_blocking_task(
+                &mut *db,
+                InsertTemplateBlockingTaskArgs {
+                    template_id: args.template_id,
+                    blocking_template_task_id,
+                    blocked_template_task_id: &template_task_ids[task_id],
+                },
+            )
+            .await
+            .map_err(CreateTemplateError::Unknown)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use crate::{
+    app::Connection,
+    features::{
+        block_task::db::{insert_block_task_connection, InsertBlockTaskConnectionArgs},
+        sub_task::db::{insert_sub_task_connection, InsertSubTaskConnectionArgs},
+        task_node::db::{insert_task_node, InsertTaskNodeArgs},
+        template::Template,
+    },
+};
+
+pub struct InstantiateTemplateArgs<'a> {
+    pub template: &'a Template,
+    pub user_id: &'a str,
+    /// ルートのタスクのノードを置く位置
+    pub x: f64,
+    pub y: f64,
+}
+
+/// テンプレートからタスクを作成し、作成したタスクのIDを返す。先頭がルートのタスクになる。
+/// 作成したタスクはすべて未完了になる。
+pub async fn action<'a>(
+    db: &mut Connection,
+    args: InstantiateTemplateArgs<'a>,
+) -> anyhow::Result<Vec<String>> {
+    // テンプレート内のタスクID -> 作成したタスクID
+    let task_ids: HashMap<&str, String> = args
+        .template
+        .tasks
+        .iter()
+        .map(|t| (t.id.as_str(), uuid::Uuid::new_v4().to_string()))
+        .collect();
+
+    // メインタスクがサブタスクより前に並んでいるので、順番に作成すればつなげられる
+    for template_task in &args.template.tasks {
+        let task_id = &task_ids[template_task.id.as_str()];
+
+        insert_task_node(
+            &mut *db,
+            InsertTaskNodeArgs {
+                task_id,
+                title: &template_task.title,
+                description: &template_task.description,
+                status: &Default::default(),
+                user_id: args.user_id,
+                x: args.x + template_task.x,
+                y: args.y + template_task.y,
+            },
+        )
+        .await?;
+
+        if let Some(main_task_id) = &template_task.main_task_id {
+            insert_sub_task_connection(
+                &mut *db,
+                InsertSubTaskConnectionArgs {
+                    main_task_id: &task_ids[main_task_id.as_str()],
+                    sub_task_id: task_id,
+                    user_id: args.user_id,
+                },
+            )
+            .await?;
+        }
+    }
+
+    for template_task in &args.template.tasks {
+        for blocking_task_id in &template_task.blocking_task_ids {
+            insert_block_task_connection(
+                &mut *db,
+                InsertBlockTaskConnectionArgs {
+                    blocking_task_id: &task_ids[blocking_task_id.as_str()],
+                    blocked_task_id: &task_ids[template_task.id.as_str()],
+                    user_id: args.user_id,
+                },
+            )
+            .await?;
+        }
+    }
+
+    Ok(args
+        .template
+        .tasks
+        .iter()
+        .map(|t| task_ids[t.id.as_str()].clone())
+        .collect())
+}
@@ -0,0 +1,57 @@
+pub mod db;
+pub mod routes;
+pub mod test;
+
+use crate::validation;
+use garde::Validate;
+pub use routes::router;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// タスクに費やした時間の記録
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct TimeEntry {
+    pub id: String,
+    pub task_id: String,
+    pub started_at: String,
+    /// タイマーが動いている間はNone
+    pub stopped_at: Option<String>,
+    /// 費やした秒数。タイマーが動いている間はNone
+    pub seconds: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct UpdateTaskEstimate {
+    /// 見積もり時間(分)。Noneの場合は見積もりを取り消す
+    #[garde(custom(validation::range(0, 525600)))]
+    #[schema(minimum = 0, maximum = 525600)]
+    pub estimated_minutes: Option<i64>,
+}
+
+/// 手動で入力する時間の記録
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct CreateTimeEntry {
+    #[garde(custom(validation::range(1, 1440)))]
+    #[schema(minimum = 1, maximum = 1440)]
+    pub minutes: i64,
+}
+
+/// タスクと、その子孫サブタスクすべての時間の集計
+#[derive(Serialize, Deserialize, ToSchema, Debug, PartialEq)]
+pub struct TaskTimeSummary {
+    pub task_id: String,
+    /// 見積もり時間(分)の合計。見積もっていないタスクは0分として扱う
+    pub estimated_minutes: i64,
+    /// 費やした時間(分)の合計。動いているタイマーの時間は含めない
+    pub spent_minutes: i64,
+    /// 残りの時間(分)の合計。
+    /// タスクごとに見積もりから費やした時間を引いて求めるので、あるタスクで見積もりを超えても他のタスクの残りは減らない。
+    /// 完了したタスクの残りは0分になる。
+    pub remaining_minutes: i64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct DeleteTimeEntryResponse {
+    pub time_entry_id: String,
+}
@@ -0,0 +1,256 @@
+use crate::app::Connection;
+
+use super::TimeEntry;
+
+pub struct UpdateTaskEstimateArgs<'a> {
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+    pub estimated_minutes: Option<i64>,
+}
+/// 更新したタスクのIDを返す。タスクが存在しない場合はNoneを返す
+pub async fn update_task_estimate<'a>(
+    db: &mut Connection,
+    args: UpdateTaskEstimateArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE tasks
+        SET estimated_minutes = $1
+        WHERE id = $2 AND user_id = $3
+        RETURNING id;
+        "#,
+        args.estimated_minutes,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+pub struct InsertTimeEntryArgs<'a> {
+    pub id: &'a str,
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+    /// Noneの場合は、タイマーを動かした状態で追加する
+    pub seconds: Option<i64>,
+}
+/// 追加した記録のIDを返す。タスクが存在しない場合はNoneを返す
+pub async fn insert_time_entry<'a>(
+    db: &mut Connection,
+    args: InsertTimeEntryArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO time_entries(id, task_id, user_id, stopped_at, seconds)
+        SELECT
+            $1,
+            id,
+            user_id,
+            CASE
+                WHEN $2 IS NULL THEN NULL
+                ELSE strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime')
+            END,
+            $2
+        FROM tasks
+        WHERE id = $3 AND user_id = $4
+        RETURNING id;
+        "#,
+        args.id,
+        args.seconds,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+pub struct FindTimeEntryArgs<'a> {
+    pub time_entry_id: &'a str,
+    pub user_id: &'a str,
+}
+pub async fn find_time_entry<'a>(
+    db: &mut Connection,
+    args: FindTimeEntryArgs<'a>,
+) -> anyhow::Result<Option<TimeEntry>> {
+    let entry = sqlx::query_as!(
+        TimeEntry,
+        r#"
+        SELECT id, task_id, started_at, stopped_at, seconds, created_at
+        FROM time_entries
+        WHERE id = $1 AND user_id = $2;
+        "#,
+        args.time_entry_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(entry)
+}
+
+pub struct FindTimeEntriesArgs<'a> {
+    pub task_id: &'a str,
+    pub user_id: &'a str,
+}
+/// タスクの時間の記録を古い順に返す
+pub async fn find_time_entries<'a>(
+    db: &mut Connection,
+    args: FindTimeEntriesArgs<'a>,
+) -> anyhow::Result<Vec<TimeEntry>> {
+    let entries = sqlx::query_as!(
+        TimeEntry,
+        r#"
+        SELECT id, task_id, started_at, stopped_at, seconds, created_at
+        FROM time_entries
+        WHERE task_id = $1 AND user_id = $2
+        ORDER BY started_at, rowid;
+        "#,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    Ok(entries)
+}
+
+/// タスクで動いているタイマーの記録のIDを返す
+pub async fn find_running_time_entry_id<'a>(
+    db: &mut Connection,
+    args: FindTimeEntriesArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        SELECT id
+        FROM time_entries
+        WHERE task_id = $1 AND user_id = $2 AND stopped_at IS NULL;
+        "#,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+/// タスクで動いているタイマーを止めて、止めた記録のIDを返す。タイマーが動いていない場合はNoneを返す
+pub async fn stop_time_entry<'a>(
+    db: &mut Connection,
+    args: FindTimeEntriesArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    // started_atは'/'区切りなので、julianday関数が読める形式に変換してから差を求める
+    let result = sqlx::query!(
+        r#"
+        UPDATE time_entries
+        SET
+            stopped_at = strftime('%Y/%m/%d %H:%M:%S', CURRENT_TIMESTAMP, 'localtime'),
+            seconds = MAX(
+                CAST(
+                    ROUND(
+                        (julianday(CURRENT_TIMESTAMP, 'localtime') - julianday(replace(started_at, '/', '-')))
+                        * 86400
+                    ) AS INTEGER
+                ),
+                0
+            )
+        WHERE task_id = $1 AND user_id = $2 AND stopped_at IS NULL
+        RETURNING id;
+        "#,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+pub struct DeleteTimeEntryArgs<'a> {
+    pub time_entry_id: &'a str,
+    pub user_id: &'a str,
+}
+/// 削除した記録のIDを返す。記録が存在しない場合はNoneを返す
+pub async fn delete_time_entry<'a>(
+    db: &mut Connection,
+    args: DeleteTimeEntryArgs<'a>,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        "DELETE FROM time_entries WHERE id = $1 AND user_id = $2 RETURNING id;",
+        args.time_entry_id,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(result.map(|r| r.id))
+}
+
+/// タスクと子孫サブタスクの見積もり時間と費やした時間の合計
+pub struct SubtreeTime {
+    pub estimated_minutes: i64,
+    pub spent_seconds: i64,
+    pub remaining_seconds: i64,
+}
+/// タスクと、そのすべての子孫サブタスクの時間を集計する。タスクが存在しない場合はNoneを返す
+pub async fn sum_subtree_time<'a>(
+    db: &mut Connection,
+    args: FindTimeEntriesArgs<'a>,
+) -> anyhow::Result<Option<SubtreeTime>> {
+    let result = sqlx::query!(
+        r#"
+        WITH RECURSIVE subtree AS (
+            SELECT id AS task_id
+            FROM tasks
+            WHERE id = $1 AND user_id = $2
+
+            UNION
+
+            SELECT s.sub_task_id
+            FROM sub_tasks s
+            JOIN subtree st ON s.main_task_id = st.task_id
+        ),
+        spent AS (
+            SELECT task_id, SUM(seconds) AS seconds
+            FROM time_entries
+            WHERE user_id = $2 AND seconds IS NOT NULL
+            GROUP BY task_id
+        )
+
+        SELECT
+            COUNT(*) as "task_count!: i64",
+            COALESCE(SUM(COALESCE(t.estimated_minutes, 0)), 0) as "estimated_minutes!: i64",
+            COALESCE(SUM(COALESCE(sp.seconds, 0)), 0) as "spent_seconds!: i64",
+            COALESCE(
+                SUM(
+                    CASE
+                        WHEN t.status = 'Done' THEN 0
+                        ELSE MAX(COALESCE(t.estimated_minutes, 0) * 60 - COALESCE(sp.seconds, 0), 0)
+                    END
+                ),
+                0
+            ) as "remaining_seconds!: i64"
+        FROM subtree st
+        JOIN tasks t ON st.task_id = t.id
+        LEFT JOIN spent sp ON t.id = sp.task_id
+        "#,
+        args.task_id,
+        args.user_id,
+    )
+    .fetch_one(&mut *db)
+    .await?;
+
+    if result.task_count == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(SubtreeTime {
+        estimated_minutes: result.estimated_minutes,
+        spent_seconds: result.spent_seconds,
+        remaining_seconds: result.remaining_seconds,
+    }))
+}
@@ -0,0 +1,120 @@
+use axum::{
+    middleware,
+    routing::{delete, get, post, put},
+    Router,
+};
+use axum_login::login_required;
+
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope, task::routes::TaskPaths},
+};
+
+pub mod create_time_entry;
+pub mod delete_time_entry;
+pub mod get_task_time;
+pub mod get_time_entries;
+pub mod start_timer;
+pub mod stop_timer;
+pub mod update_task_estimate;
+
+pub const TAG: &str = "time_tracking";
+
+pub struct TimeTrackingPaths;
+impl TimeTrackingPaths {
+    pub fn task_estimate_base() -> String {
+        "/estimate".into()
+    }
+
+    pub fn task_estimate() -> String {
+        TaskPaths::task() + &Self::task_estimate_base()
+    }
+
+    pub fn task_estimate_open_api() -> String {
+        TaskPaths::task_open_api() + &Self::task_estimate_base()
+    }
+
+    pub fn task_time_base() -> String {
+        "/time".into()
+    }
+
+    pub fn task_time() -> String {
+        TaskPaths::task() + &Self::task_time_base()
+    }
+
+    pub fn task_time_open_api() -> String {
+        TaskPaths::task_open_api() + &Self::task_time_base()
+    }
+
+    pub fn task_time_entries_base() -> String {
+        "/time-entries".into()
+    }
+
+    pub fn task_time_entries() -> String {
+        TaskPaths::task() + &Self::task_time_entries_base()
+    }
+
+    pub fn task_time_entries_open_api() -> String {
+        TaskPaths::task_open_api() + &Self::task_time_entries_base()
+    }
+
+    pub fn start_timer_base() -> String {
+        "/timer/start".into()
+    }
+
+    pub fn start_timer() -> String {
+        TaskPaths::task() + &Self::start_timer_base()
+    }
+
+    pub fn start_timer_open_api() -> String {
+        TaskPaths::task_open_api() + &Self::start_timer_base()
+    }
+
+    pub fn stop_timer_base() -> String {
+        "/timer/stop".into()
+    }
+
+    pub fn stop_timer() -> String {
+        TaskPaths::task() + &Self::stop_timer_base()
+    }
+
+    pub fn stop_timer_open_api() -> String {
+        TaskPaths::task_open_api() + &Self::stop_timer_base()
+    }
+
+    pub fn time_entries() -> String {
+        "/time-entries".into()
+    }
+
+    pub fn time_entry() -> String {
+        Self::time_entries() + "/:id"
+    }
+
+    pub fn time_entry_open_api() -> String {
+        Self::time_entries() + "/{id}"
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            &TimeTrackingPaths::task_estimate(),
+            put(update_task_estimate::handler),
+        )
+        .route(&TimeTrackingPaths::task_time(), get(get_task_time::handler))
+        .route(
+            &TimeTrackingPaths::task_time_entries(),
+            get(get_time_entries::handler).post(create_time_entry::handler),
+        )
+        .route(
+            &TimeTrackingPaths::start_timer(),
+            post(start_timer::handler),
+        )
+        .route(&TimeTrackingPaths::stop_timer(), post(stop_timer::handler))
+        .route(
+            &TimeTrackingPaths::time_entry(),
+            delete(delete_time_entry::handler),
+        )
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
+        .route_layer(login_required!(Auth))
+}
@@ -0,0 +1,150 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        auth::Auth,
+        time_tracking::{
+            db::{find_time_entry, insert_time_entry, FindTimeEntryArgs, InsertTimeEntryArgs},
+            CreateTimeEntry, TimeEntry,
+        },
+    },
+    validation::WithValidation,
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::TimeTrackingPaths::task_time_entries_open_api(),
+    request_body = CreateTimeEntry,
+    responses(
+        (status = 201, body = TimeEntry),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<CreateTimeEntry>>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let entry_id = uuid::Uuid::new_v4().to_string();
+    if insert_time_entry(
+        &mut tx,
+        InsertTimeEntryArgs {
+            id: &entry_id,
+            task_id: &id,
+            user_id: &user.id,
+            seconds: Some(payload.minutes * 60),
+        },
+    )
+    .await?
+    .is_none()
+    {
+        return Err(AppError::with_code(ErrorCode::TaskNotFound));
+    }
+
+    let entry: TimeEntry = find_time_entry(
+        &mut tx,
+        FindTimeEntryArgs {
+            time_entry_id: &entry_id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or_else(|| anyhow!("time entry not found"))?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(entry)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::test::task_factory,
+            time_tracking::{routes::TimeTrackingPaths, CreateTimeEntry, TimeEntry},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 費やした時間を手動で記録できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .post(&TimeTrackingPaths::one_task_time_entries(&task.id))
+            .json(&CreateTimeEntry { minutes: 30 })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+        let entry: TimeEntry = res.json();
+        assert_eq!(entry.task_id, task.id);
+        assert_eq!(entry.seconds, Some(30 * 60));
+        assert!(entry.stopped_at.is_some());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 時間が0分の記録は追加できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .post(&TimeTrackingPaths::one_task_time_entries(&task.id))
+            .json(&CreateTimeEntry { minutes: 0 })
+            .await;
+        assert!(res.status_code().is_client_error());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクには時間を記録できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .post(&TimeTrackingPaths::one_task_time_entries(
+                &other_user_task.id,
+            ))
+            .json(&CreateTimeEntry { minutes: 30 })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        time_tracking::{
+            db::{delete_time_entry, DeleteTimeEntryArgs},
+            DeleteTimeEntryResponse,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    delete,
+    tag = super::TAG,
+    path = super::TimeTrackingPaths::time_entry_open_api(),
+    responses(
+        (status = 200, body = DeleteTimeEntryResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<DeleteTimeEntryResponse>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(time_entry_id) = delete_time_entry(
+        &mut tx,
+        DeleteTimeEntryArgs {
+            time_entry_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    tx.commit().await?;
+
+    Ok(Json(DeleteTimeEntryResponse { time_entry_id }))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::test::task_factory,
+            time_tracking::{routes::TimeTrackingPaths, test::time_entry_factory, TimeEntry},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn 時間の記録を削除できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let entry = time_entry_factory::create(&db, &user.id, &task.id, 10).await?;
+
+        test.server()
+            .delete(&TimeTrackingPaths::one_time_entry(&entry.id))
+            .await
+            .assert_status_ok();
+
+        let entries: Vec<TimeEntry> = test
+            .server()
+            .get(&TimeTrackingPaths::one_task_time_entries(&task.id))
+            .await
+            .json();
+        assert!(entries.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人の時間の記録は削除できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+        let entry =
+            time_entry_factory::create(&db, &other_user.id, &other_user_task.id, 10).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .delete(&TimeTrackingPaths::one_time_entry(&entry.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        auth::Auth,
+        time_tracking::{
+            db::{sum_subtree_time, FindTimeEntriesArgs},
+            TaskTimeSummary,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::TimeTrackingPaths::task_time_open_api(),
+    responses(
+        (status = 200, body = TaskTimeSummary),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<TaskTimeSummary>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(time) = sum_subtree_time(
+        &mut tx,
+        FindTimeEntriesArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::with_code(ErrorCode::TaskNotFound));
+    };
+
+    tx.commit().await?;
+
+    // 費やした時間は切り捨て、残りの時間は切り上げて、少しでも残っていれば0分にならないようにする
+    Ok(Json(TaskTimeSummary {
+        task_id: id,
+        estimated_minutes: time.estimated_minutes,
+        spent_minutes: time.spent_seconds / 60,
+        remaining_minutes: (time.remaining_seconds + 59) / 60,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::{test::task_factory, Task, TaskStatus},
+            time_tracking::{
+                routes::TimeTrackingPaths, test::time_entry_factory, TaskTimeSummary,
+                UpdateTaskEstimate,
+            },
+            user::test::user_factory,
+        },
+    };
+
+    async fn set_estimate(test: &AppTest, task_id: &str, minutes: i64) {
+        test.server()
+            .put(&TimeTrackingPaths::one_task_estimate(task_id))
+            .json(&UpdateTaskEstimate {
+                estimated_minutes: Some(minutes),
+            })
+            .await
+            .assert_status_ok();
+    }
+
+    #[sqlx::test]
+    async fn 見積もりと費やした時間がサブタスクからメインタスクに集計される(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        // main ─ sub1 ─ sub1_1
+        //      └ sub2
+        let main = task_factory::create_with_user(&db, &user.id).await?;
+        let sub1 = task_factory::create_sub_task(
+            &db,
+            &main.id,
+            Task {
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let sub1_1 = task_factory::create_sub_task(
+            &db,
+            &sub1.id,
+            Task {
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let sub2 = task_factory::create_sub_task(
+            &db,
+            &main.id,
+            Task {
+                user_id: user.id.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        set_estimate(&test, &main.id, 30).await;
+        set_estimate(&test, &sub1_1.id, 60).await;
+        set_estimate(&test, &sub2.id, 120).await;
+        time_entry_factory::create(&db, &user.id, &main.id, 10).await?;
+        time_entry_factory::create(&db, &user.id, &sub1_1.id, 20).await?;
+        time_entry_factory::create(&db, &user.id, &sub2.id, 150).await?;
+
+        let summary: TaskTimeSummary = test
+            .server()
+            .get(&TimeTrackingPaths::one_task_time(&main.id))
+            .await
+            .json();
+        assert_eq!(
+            summary,
+            TaskTimeSummary {
+                task_id: main.id.clone(),
+                estimated_minutes: 30 + 60 + 120,
+                spent_minutes: 10 + 20 + 150,
+                // sub2は見積もりを超えているが、他のタスクの残りは減らさない
+                remaining_minutes: (30 - 10) + (60 - 20),
+            }
+        );
+
+        let summary: TaskTimeSummary = test
+            .server()
+            .get(&TimeTrackingPaths::one_task_time(&sub1.id))
+            .await
+            .json();
+        assert_eq!(summary.estimated_minutes, 60);
+        assert_eq!(summary.spent_minutes, 20);
+        assert_eq!(summary.remaining_minutes, 40);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 完了したタスクの残りの時間は0分になる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create(
+            &db,
+            Task {
+                user_id: user.id.clone(),
+                status: TaskStatus::Done,
+                ..Default::default()
+            },
+        )
+        .await?;
+        set_estimate(&test, &task.id, 60).await;
+        time_entry_factory::create(&db, &user.id, &task.id, 10).await?;
+
+        let summary: TaskTimeSummary = test
+            .server()
+            .get(&TimeTrackingPaths::one_task_time(&task.id))
+            .await
+            .json();
+        assert_eq!(summary.estimated_minutes, 60);
+        assert_eq!(summary.spent_minutes, 10);
+        assert_eq!(summary.remaining_minutes, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクの時間は取得できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .get(&TimeTrackingPaths::one_task_time(&other_user_task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        time_tracking::{
+            db::{find_time_entries, FindTimeEntriesArgs},
+            TimeEntry,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::TimeTrackingPaths::task_time_entries_open_api(),
+    responses(
+        (status = 200, body = [TimeEntry]),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<Vec<TimeEntry>>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let entries = find_time_entries(
+        &mut tx,
+        FindTimeEntriesArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::test::task_factory,
+            time_tracking::{routes::TimeTrackingPaths, test::time_entry_factory, TimeEntry},
+        },
+    };
+
+    #[sqlx::test]
+    async fn タスクの時間の記録を取得できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let other_task = task_factory::create_with_user(&db, &user.id).await?;
+        let entry1 = time_entry_factory::create(&db, &user.id, &task.id, 10).await?;
+        let entry2 = time_entry_factory::create(&db, &user.id, &task.id, 20).await?;
+        time_entry_factory::create(&db, &user.id, &other_task.id, 30).await?;
+
+        let entries: Vec<TimeEntry> = test
+            .server()
+            .get(&TimeTrackingPaths::one_task_time_entries(&task.id))
+            .await
+            .json();
+        let ids: Vec<_> = entries.into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![entry1.id, entry2.id]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        auth::Auth,
+        time_tracking::{
+            db::{
+                find_running_time_entry_id, find_time_entry, insert_time_entry,
+                FindTimeEntriesArgs, FindTimeEntryArgs, InsertTimeEntryArgs,
+            },
+            TimeEntry,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::TimeTrackingPaths::start_timer_open_api(),
+    responses(
+        (status = 201, body = TimeEntry),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails),
+        (status = 409, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<impl IntoResponse> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    if find_running_time_entry_id(
+        &mut tx,
+        FindTimeEntriesArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .is_some()
+    {
+        return Err(AppError::with_code(ErrorCode::TimerAlreadyRunning));
+    }
+
+    let entry_id = uuid::Uuid::new_v4().to_string();
+    if insert_time_entry(
+        &mut tx,
+        InsertTimeEntryArgs {
+            id: &entry_id,
+            task_id: &id,
+            user_id: &user.id,
+            seconds: None,
+        },
+    )
+    .await?
+    .is_none()
+    {
+        return Err(AppError::with_code(ErrorCode::TaskNotFound));
+    }
+
+    let entry: TimeEntry = find_time_entry(
+        &mut tx,
+        FindTimeEntryArgs {
+            time_entry_id: &entry_id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or_else(|| anyhow!("time entry not found"))?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(entry)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::test::task_factory,
+            time_tracking::{routes::TimeTrackingPaths, TimeEntry},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn タイマーを開始できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .post(&TimeTrackingPaths::one_start_timer(&task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+        let entry: TimeEntry = res.json();
+        assert_eq!(entry.task_id, task.id);
+        assert_eq!(entry.stopped_at, None);
+        assert_eq!(entry.seconds, None);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 動いているタイマーがあるタスクではタイマーを開始できない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        test.server()
+            .post(&TimeTrackingPaths::one_start_timer(&task.id))
+            .await;
+        let res = test
+            .server()
+            .post(&TimeTrackingPaths::one_start_timer(&task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CONFLICT);
+
+        // 別のタスクではタイマーを開始できる
+        let other_task = task_factory::create_with_user(&db, &user.id).await?;
+        let res = test
+            .server()
+            .post(&TimeTrackingPaths::one_start_timer(&other_task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクではタイマーを開始できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .post(&TimeTrackingPaths::one_start_timer(&other_user_task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+use http::StatusCode;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        time_tracking::{
+            db::{find_time_entry, stop_time_entry, FindTimeEntriesArgs, FindTimeEntryArgs},
+            TimeEntry,
+        },
+    },
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    post,
+    tag = super::TAG,
+    path = super::TimeTrackingPaths::stop_timer_open_api(),
+    responses(
+        (status = 200, body = TimeEntry),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+) -> AppResult<Json<TimeEntry>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let Some(entry_id) = stop_time_entry(
+        &mut tx,
+        FindTimeEntriesArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    else {
+        return Err(AppError::new(StatusCode::NOT_FOUND, None));
+    };
+
+    let entry = find_time_entry(
+        &mut tx,
+        FindTimeEntryArgs {
+            time_entry_id: &entry_id,
+            user_id: &user.id,
+        },
+    )
+    .await?
+    .ok_or_else(|| anyhow!("time entry not found"))?;
+
+    tx.commit().await?;
+
+    Ok(Json(entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::test::task_factory,
+            time_tracking::{routes::TimeTrackingPaths, TimeEntry},
+        },
+    };
+
+    #[sqlx::test]
+    async fn 動いているタイマーを止められる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+        let started: TimeEntry = test
+            .server()
+            .post(&TimeTrackingPaths::one_start_timer(&task.id))
+            .await
+            .json();
+
+        let res = test
+            .server()
+            .post(&TimeTrackingPaths::one_stop_timer(&task.id))
+            .await;
+        res.assert_status_ok();
+        let stopped: TimeEntry = res.json();
+        assert_eq!(stopped.id, started.id);
+        assert!(stopped.stopped_at.is_some());
+        assert!(stopped.seconds.is_some_and(|s| s >= 0));
+
+        // 止めたあとは、もう一度タイマーを開始できる
+        let res = test
+            .server()
+            .post(&TimeTrackingPaths::one_start_timer(&task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::CREATED);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn タイマーが動いていないときは止められない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .post(&TimeTrackingPaths::one_stop_timer(&task.id))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
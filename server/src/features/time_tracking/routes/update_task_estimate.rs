@@ -0,0 +1,161 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        auth::Auth,
+        task::{
+            db::{find_task, FindTaskArgs},
+            Task,
+        },
+        time_tracking::{
+            db::{update_task_estimate, UpdateTaskEstimateArgs},
+            UpdateTaskEstimate,
+        },
+    },
+    validation::WithValidation,
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    put,
+    tag = super::TAG,
+    path = super::TimeTrackingPaths::task_estimate_open_api(),
+    request_body = UpdateTaskEstimate,
+    responses(
+        (status = 200, body = Task),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails),
+        (status = 404, response = ProblemDetails)
+    ),
+    params(("id" = String, Path,))
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    Path(id): Path<String>,
+    State(AppState { db, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<UpdateTaskEstimate>>,
+) -> AppResult<Json<Task>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    if update_task_estimate(
+        &mut tx,
+        UpdateTaskEstimateArgs {
+            task_id: &id,
+            user_id: &user.id,
+            estimated_minutes: payload.estimated_minutes,
+        },
+    )
+    .await?
+    .is_none()
+    {
+        return Err(AppError::with_code(ErrorCode::TaskNotFound));
+    }
+
+    let task = find_task(
+        &mut tx,
+        FindTaskArgs {
+            task_id: &id,
+            user_id: &user.id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(task))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            task::{test::task_factory, Task},
+            time_tracking::{routes::TimeTrackingPaths, UpdateTaskEstimate},
+            user::test::user_factory,
+        },
+    };
+
+    #[sqlx::test]
+    async fn タスクの見積もり時間を設定できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .put(&TimeTrackingPaths::one_task_estimate(&task.id))
+            .json(&UpdateTaskEstimate {
+                estimated_minutes: Some(90),
+            })
+            .await;
+        res.assert_status_ok();
+        let task: Task = res.json();
+        assert_eq!(task.estimated_minutes, Some(90));
+
+        // 見積もりを取り消せる
+        let task: Task = test
+            .server()
+            .put(&TimeTrackingPaths::one_task_estimate(&task.id))
+            .json(&UpdateTaskEstimate {
+                estimated_minutes: None,
+            })
+            .await
+            .json();
+        assert_eq!(task.estimated_minutes, None);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 負の見積もり時間は設定できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let task = task_factory::create_with_user(&db, &user.id).await?;
+
+        let res = test
+            .server()
+            .put(&TimeTrackingPaths::one_task_estimate(&task.id))
+            .json(&UpdateTaskEstimate {
+                estimated_minutes: Some(-1),
+            })
+            .await;
+        assert!(res.status_code().is_client_error());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 他人のタスクの見積もり時間は設定できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let other_user = user_factory::create_default(&db).await?;
+        let other_user_task = task_factory::create_with_user(&db, &other_user.id).await?;
+
+        test.login(None).await?;
+        let res = test
+            .server()
+            .put(&TimeTrackingPaths::one_task_estimate(&other_user_task.id))
+            .json(&UpdateTaskEstimate {
+                estimated_minutes: Some(90),
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
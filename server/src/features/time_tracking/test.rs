@@ -0,0 +1,76 @@
+#[cfg(test)]
+pub mod time_entry_factory {
+    use crate::{
+        app::{AppResult, Db},
+        features::time_tracking::{
+            db::{find_time_entry, insert_time_entry, FindTimeEntryArgs, InsertTimeEntryArgs},
+            TimeEntry,
+        },
+    };
+
+    /// 指定した分数を費やした記録を追加する
+    pub async fn create(
+        db: &Db,
+        user_id: &str,
+        task_id: &str,
+        minutes: i64,
+    ) -> AppResult<TimeEntry> {
+        let mut conn = db.acquire().await?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        insert_time_entry(
+            &mut conn,
+            InsertTimeEntryArgs {
+                id: &id,
+                task_id,
+                user_id,
+                seconds: Some(minutes * 60),
+            },
+        )
+        .await?
+        .ok_or(anyhow::anyhow!("task not found"))?;
+
+        let entry = find_time_entry(
+            &mut conn,
+            FindTimeEntryArgs {
+                time_entry_id: &id,
+                user_id,
+            },
+        )
+        .await?
+        .ok_or(anyhow::anyhow!("time entry not found"))?;
+
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+pub mod routes {
+    use crate::features::{task::routes::TaskPaths, time_tracking};
+
+    impl time_tracking::routes::TimeTrackingPaths {
+        pub fn one_task_estimate(task_id: &str) -> String {
+            TaskPaths::one_task(task_id) + &Self::task_estimate_base()
+        }
+
+        pub fn one_task_time(task_id: &str) -> String {
+            TaskPaths::one_task(task_id) + &Self::task_time_base()
+        }
+
+        pub fn one_task_time_entries(task_id: &str) -> String {
+            TaskPaths::one_task(task_id) + &Self::task_time_entries_base()
+        }
+
+        pub fn one_start_timer(task_id: &str) -> String {
+            TaskPaths::one_task(task_id) + &Self::start_timer_base()
+        }
+
+        pub fn one_stop_timer(task_id: &str) -> String {
+            TaskPaths::one_task(task_id) + &Self::stop_timer_base()
+        }
+
+        pub fn one_time_entry(id: &str) -> String {
+            Self::time_entries() + "/" + id
+        }
+    }
+}
@@ -1,7 +1,11 @@
 pub mod db;
+pub mod routes;
 pub mod test;
 
+use crate::{i18n::Locale, validation};
 use axum_login::AuthUser;
+use garde::Validate;
+pub use routes::router;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -10,6 +14,8 @@ pub struct User {
     pub id: String,
     pub name: String,
     pub profile: String,
+    /// 設定した言語。設定していない場合はリクエストの`Accept-Language`を使う
+    pub locale: Option<String>,
 }
 
 impl AuthUser for User {
@@ -23,3 +29,24 @@ impl AuthUser for User {
         self.id.as_bytes()
     }
 }
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Validate)]
+pub struct UpdateUser {
+    #[garde(custom(validation::length(1, 100)))]
+    #[schema(min_length = 1, max_length = 100)]
+    pub name: String,
+
+    #[garde(skip)]
+    #[schema(max_length = 500)]
+    pub profile: String,
+
+    /// 指定しない場合は設定を消して、リクエストの`Accept-Language`を使うようにする
+    #[garde(skip)]
+    #[serde(default)]
+    pub locale: Option<Locale>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct DeleteUserResponse {
+    pub user_id: String,
+}
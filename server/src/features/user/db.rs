@@ -10,6 +10,32 @@ pub async fn find_user(db: &mut Connection, user_id: &str) -> anyhow::Result<Opt
     Ok(user)
 }
 
+pub struct FindUserByIdentityArgs<'a> {
+    pub provider: &'a str,
+    pub subject: &'a str,
+}
+/// ログインに使ったプロバイダーと、プロバイダーの中でのユーザーのIDからユーザーを探す
+pub async fn find_user_by_identity<'a>(
+    db: &mut Connection,
+    args: FindUserByIdentityArgs<'a>,
+) -> anyhow::Result<Option<User>> {
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        SELECT u.id, u.name, u.profile, u.locale
+        FROM users u
+        JOIN user_identities i ON u.id = i.user_id
+        WHERE i.provider = $1 AND i.subject = $2;
+        "#,
+        args.provider,
+        args.subject,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(user)
+}
+
 pub struct InsertUserArgs<'a> {
     pub user_id: &'a str,
     pub name: &'a str,
@@ -31,3 +57,66 @@ pub async fn insert_user<'a>(
 
     Ok(user)
 }
+
+pub struct InsertUserIdentityArgs<'a> {
+    pub user_id: &'a str,
+    pub provider: &'a str,
+    pub subject: &'a str,
+}
+pub async fn insert_user_identity<'a>(
+    db: &mut Connection,
+    args: InsertUserIdentityArgs<'a>,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        "INSERT INTO user_identities(provider, subject, user_id) VALUES($1, $2, $3);",
+        args.provider,
+        args.subject,
+        args.user_id,
+    )
+    .execute(&mut *db)
+    .await?;
+
+    Ok(())
+}
+
+pub struct UpdateUserArgs<'a> {
+    pub user_id: &'a str,
+    pub name: &'a str,
+    pub profile: &'a str,
+    pub locale: Option<&'a str>,
+}
+/// 更新したユーザーを返す。ユーザーが存在しない場合はNoneを返す
+pub async fn update_user<'a>(
+    db: &mut Connection,
+    args: UpdateUserArgs<'a>,
+) -> anyhow::Result<Option<User>> {
+    let user = sqlx::query_as!(
+        User,
+        "UPDATE users SET name = $1, profile = $2, locale = $3 WHERE id = $4 RETURNING *;",
+        args.name,
+        args.profile,
+        args.locale,
+        args.user_id,
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    Ok(user)
+}
+
+/// ユーザーを削除する。タスクやつながり、セッションなどのユーザーのデータは外部キーで一緒に削除される
+pub async fn delete_user(db: &mut Connection, user_id: &str) -> anyhow::Result<()> {
+    sqlx::query!("DELETE FROM users WHERE id = $1;", user_id)
+        .execute(&mut *db)
+        .await?;
+
+    // タスクの削除でトリガーが書き込んだバージョンは、外部キーがないので残ってしまう
+    sqlx::query!(
+        "DELETE FROM task_graph_versions WHERE user_id = $1;",
+        user_id
+    )
+    .execute(&mut *db)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use axum::{middleware, routing::get, Router};
+use axum_login::login_required;
+
+use crate::{
+    app::AppState,
+    features::{auth::Auth, personal_access_token::require_token_scope},
+};
+
+pub mod delete_me;
+pub mod get_me;
+pub mod update_me;
+
+pub const TAG: &str = "user";
+
+pub struct UserPaths;
+impl UserPaths {
+    pub fn me() -> String {
+        "/me".into()
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            &UserPaths::me(),
+            get(get_me::handler)
+                .put(update_me::handler)
+                .delete(delete_me::handler),
+        )
+        .route_layer(middleware::from_fn_with_state(TAG, require_token_scope))
+        .route_layer(login_required!(Auth))
+}
@@ -0,0 +1,169 @@
+use axum::{extract::State, Json};
+use axum_login::{tower_sessions::Session, AuthSession};
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ErrorCode, ProblemDetails},
+    features::{
+        attachment::db::find_user_attachment_storage_keys,
+        auth::{
+            session_metadata::{
+                db::{
+                    delete_user_sessions, is_recently_authenticated, IsRecentlyAuthenticatedArgs,
+                },
+                SESSION_METADATA_KEY,
+            },
+            Auth,
+        },
+        user::{db::delete_user, DeleteUserResponse},
+    },
+};
+
+/// アカウントを削除する前に、ログインし直している必要がある時間
+pub const REAUTHENTICATION_MINUTES: i64 = 10;
+
+/// アカウントを削除して、他の端末も含めたすべてのセッションからログアウトする。
+/// 取り消せない操作なので、最近ログインし直したセッションでしか削除できない。
+#[tracing::instrument(err)]
+#[utoipa::path(
+    delete,
+    tag = super::TAG,
+    path = super::UserPaths::me(),
+    responses(
+        (status = 200, body = DeleteUserResponse),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
+)]
+pub async fn handler(
+    mut auth_session: AuthSession<Auth>,
+    session: Session,
+    State(AppState {
+        db,
+        task_graph,
+        attachments,
+        ..
+    }): State<AppState>,
+) -> AppResult<Json<DeleteUserResponse>> {
+    let Some(user) = auth_session.user.clone() else {
+        return Err(AppError::unauthorized());
+    };
+
+    let session_metadata_id = session.get::<String>(SESSION_METADATA_KEY).await?;
+
+    let mut tx = db.begin().await?;
+
+    let reauthenticated = match &session_metadata_id {
+        Some(id) => {
+            is_recently_authenticated(
+                &mut tx,
+                IsRecentlyAuthenticatedArgs {
+                    id,
+                    user_id: &user.id,
+                    within_minutes: REAUTHENTICATION_MINUTES,
+                },
+            )
+            .await?
+        }
+        None => false,
+    };
+    if !reauthenticated {
+        return Err(AppError::with_code(ErrorCode::ReauthenticationRequired));
+    }
+
+    // ユーザーを削除すると添付ファイルの情報も削除されるので、先にストレージのキーを取得しておく
+    let storage_keys = find_user_attachment_storage_keys(&mut tx, &user.id).await?;
+    delete_user_sessions(&mut tx, &user.id).await?;
+    delete_user(&mut tx, &user.id).await?;
+
+    tx.commit().await?;
+    task_graph.remove(&user.id);
+
+    for key in storage_keys {
+        if let Err(e) = attachments.storage.delete(&key).await {
+            tracing::warn!("Failed to delete attachment file {}: {:?}", key, e);
+        }
+    }
+
+    auth_session.logout().await?;
+
+    Ok(Json(DeleteUserResponse { user_id: user.id }))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            auth::routes::{session::SessionResponse, AuthPaths},
+            task::test::task_factory,
+            user::routes::UserPaths,
+        },
+    };
+
+    #[sqlx::test]
+    async fn アカウントを削除するとタスクも削除されてログアウトする(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+        task_factory::create_with_user(&db, &user.id).await?;
+        let other_device = AppTest::new(&db).await?;
+        other_device.login_as(&user.id).await?;
+
+        test.server()
+            .delete(&UserPaths::me())
+            .await
+            .assert_status_ok();
+
+        // 他の端末のセッションからもログアウトしている
+        for test in [&test, &other_device] {
+            let session: SessionResponse = test.server().get(&AuthPaths::session()).await.json();
+            assert!(session.session.is_none());
+        }
+        let stored_sessions = sqlx::query_scalar!("SELECT COUNT(*) FROM sessions;")
+            .fetch_one(&db)
+            .await?;
+        assert_eq!(stored_sessions, 0);
+
+        let users = sqlx::query!("SELECT id FROM users WHERE id = $1;", user.id)
+            .fetch_all(&db)
+            .await?;
+        assert!(users.is_empty());
+        let tasks = sqlx::query!("SELECT id FROM tasks WHERE user_id = $1;", user.id)
+            .fetch_all(&db)
+            .await?;
+        assert!(tasks.is_empty());
+        let sessions = sqlx::query!(
+            "SELECT id FROM session_metadata WHERE user_id = $1;",
+            user.id
+        )
+        .fetch_all(&db)
+        .await?;
+        assert!(sessions.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 最近ログインし直していないとアカウントを削除できない(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        sqlx::query!(
+            "UPDATE session_metadata SET authenticated_at = '2000/01/01 00:00:00' WHERE user_id = $1;",
+            user.id
+        )
+        .execute(&db)
+        .await?;
+
+        let res = test.server().delete(&UserPaths::me()).await;
+        assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+        Ok(())
+    }
+}
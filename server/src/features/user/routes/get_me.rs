@@ -0,0 +1,68 @@
+use axum::Json;
+use axum_login::AuthSession;
+
+use crate::{
+    app::AppResult,
+    error::{AppError, ProblemDetails},
+    features::{auth::Auth, user::User},
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    get,
+    tag = super::TAG,
+    path = super::UserPaths::me(),
+    responses(
+        (status = 200, body = User),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
+)]
+pub async fn handler(auth_session: AuthSession<Auth>) -> AppResult<Json<User>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    Ok(Json(user))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        features::{
+            auth::routes::signup::CreateUser,
+            user::{routes::UserPaths, User},
+        },
+    };
+
+    #[sqlx::test]
+    async fn ログインしているユーザーを取得できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test
+            .login(Some(CreateUser {
+                name: "name".into(),
+                profile: "profile".into(),
+            }))
+            .await?;
+
+        let me: User = test.server().get(&UserPaths::me()).await.json();
+        assert_eq!(me.id, user.id);
+        assert_eq!(me.name, "name");
+        assert_eq!(me.profile, "profile");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn ログインしていないと取得できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let res = test.server().get(&UserPaths::me()).await;
+        assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+
+        Ok(())
+    }
+}
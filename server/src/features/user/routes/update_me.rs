@@ -0,0 +1,182 @@
+use anyhow::anyhow;
+use axum::{extract::State, Json};
+use axum_login::AuthSession;
+
+use crate::{
+    app::{AppResult, AppState},
+    error::{AppError, ProblemDetails},
+    features::{
+        auth::Auth,
+        user::{
+            db::{update_user, UpdateUserArgs},
+            UpdateUser, User,
+        },
+    },
+    validation::WithValidation,
+};
+
+#[tracing::instrument(err)]
+#[utoipa::path(
+    put,
+    tag = super::TAG,
+    path = super::UserPaths::me(),
+    request_body = UpdateUser,
+    responses(
+        (status = 200, body = User),
+        (status = 401, response = ProblemDetails),
+        (status = 403, response = ProblemDetails)
+    )
+)]
+pub async fn handler(
+    auth_session: AuthSession<Auth>,
+    State(AppState { db, .. }): State<AppState>,
+    WithValidation(payload): WithValidation<Json<UpdateUser>>,
+) -> AppResult<Json<User>> {
+    let Some(user) = auth_session.user else {
+        return Err(AppError::unauthorized());
+    };
+
+    let mut tx = db.begin().await?;
+
+    let user = update_user(
+        &mut tx,
+        UpdateUserArgs {
+            user_id: &user.id,
+            name: &payload.name,
+            profile: &payload.profile,
+            locale: payload.locale.as_ref().map(|locale| locale.as_ref()),
+        },
+    )
+    .await?
+    .ok_or_else(|| anyhow!("user not found"))?;
+
+    tx.commit().await?;
+
+    Ok(Json(user))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header::ACCEPT_LANGUAGE, HeaderValue, StatusCode};
+
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        error::{ErrorCode, ProblemDetails},
+        features::user::{routes::UserPaths, UpdateUser, User},
+        i18n::Locale,
+    };
+
+    #[sqlx::test]
+    async fn 名前とプロフィールを更新できる(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        let user = test.login(None).await?;
+
+        let updated: User = test
+            .server()
+            .put(&UserPaths::me())
+            .json(&UpdateUser {
+                name: "new name".into(),
+                profile: "new profile".into(),
+                locale: None,
+            })
+            .await
+            .json();
+        assert_eq!(updated.id, user.id);
+        assert_eq!(updated.name, "new name");
+        assert_eq!(updated.profile, "new profile");
+
+        let me: User = test.server().get(&UserPaths::me()).await.json();
+        assert_eq!(me.name, "new name");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 空の名前には更新できない(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        let res = test
+            .server()
+            .put(&UserPaths::me())
+            .json(&UpdateUser {
+                name: "".into(),
+                profile: "profile".into(),
+                locale: None,
+            })
+            .await;
+        assert_eq!(res.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let problem: ProblemDetails = res.json();
+        assert_eq!(problem.code, ErrorCode::ValidationFailed);
+        assert_eq!(problem.errors[0].field, "name");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 検証のエラーはaccept_languageの言語で返す(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        let update = UpdateUser {
+            name: "".into(),
+            profile: "profile".into(),
+            locale: None,
+        };
+
+        let problem: ProblemDetails = test
+            .server()
+            .put(&UserPaths::me())
+            .add_header(ACCEPT_LANGUAGE, HeaderValue::from_static("en-US,en;q=0.9"))
+            .json(&update)
+            .await
+            .json();
+        assert_eq!(problem.detail.as_deref(), Some("Please check your input."));
+        assert_eq!(problem.errors[0].message, "Length must be at least 1.");
+
+        // 指定しない場合は日本語で返す
+        let problem: ProblemDetails = test
+            .server()
+            .put(&UserPaths::me())
+            .json(&update)
+            .await
+            .json();
+        assert_eq!(problem.errors[0].message, "長さは1以上にしてください");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 設定した言語をaccept_languageより優先する(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        let updated: User = test
+            .server()
+            .put(&UserPaths::me())
+            .json(&UpdateUser {
+                name: "name".into(),
+                profile: "profile".into(),
+                locale: Some(Locale::En),
+            })
+            .await
+            .json();
+        assert_eq!(updated.locale.as_deref(), Some("en"));
+
+        let problem: ProblemDetails = test
+            .server()
+            .put(&UserPaths::me())
+            .add_header(ACCEPT_LANGUAGE, HeaderValue::from_static("ja"))
+            .json(&UpdateUser {
+                name: "".into(),
+                profile: "profile".into(),
+                locale: None,
+            })
+            .await
+            .json();
+        assert_eq!(problem.errors[0].message, "Length must be at least 1.");
+
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@ pub mod user_factory {
                 id: Uuid::new_v4().into(),
                 name: "user".into(),
                 profile: "profile".into(),
+                locale: None,
             }
         }
     }
@@ -0,0 +1,336 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use axum_login::AuthSession;
+use http::{
+    header::{ACCEPT_LANGUAGE, CONTENT_LENGTH, CONTENT_TYPE},
+    HeaderValue,
+};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumIter, EnumString};
+use utoipa::ToSchema;
+
+use crate::{
+    error::{Problem, PROBLEM_JSON_CONTENT_TYPE},
+    features::auth::Auth,
+};
+
+/// エラーメッセージなどを返すときの言語
+#[derive(
+    Serialize,
+    Deserialize,
+    ToSchema,
+    EnumString,
+    EnumIter,
+    AsRefStr,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Copy,
+    Default,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Locale {
+    #[default]
+    Ja,
+    En,
+}
+
+impl Locale {
+    /// `en-US`のような言語タグから、対応している言語を返す。地域などのサブタグは見ない
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?;
+        primary.parse().ok()
+    }
+
+    /// `Accept-Language`ヘッダーの中で、対応している言語のうち一番優先度が高いものを返す
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut ranges: Vec<(f32, Locale)> = header
+            .split(',')
+            .filter_map(|range| {
+                let mut params = range.split(';');
+                let locale = Locale::from_tag(params.next()?)?;
+                let quality = params
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map(|q| q.trim().parse().unwrap_or(0.0))
+                    .unwrap_or(1.0);
+
+                Some((quality, locale))
+            })
+            .filter(|(quality, _)| *quality > 0.0)
+            .collect();
+        // 優先度が同じなら先に書かれているほうを使うので、安定ソートする
+        ranges.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        ranges.first().map(|(_, locale)| *locale)
+    }
+
+    /// カタログの文言に引数を埋め込んで返す。キーがカタログにない場合はNoneを返す
+    pub fn message(self, message: &Message) -> Option<String> {
+        let template = catalogs().get(&self)?.get(&message.key)?;
+
+        Some(
+            message
+                .args
+                .iter()
+                .fold(template.clone(), |text, (name, value)| {
+                    text.replace(&format!("{{{}}}", name), value)
+                }),
+        )
+    }
+}
+
+/// カタログのキーと、文言に埋め込む引数
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Message {
+    pub key: String,
+    pub args: Vec<(String, String)>,
+}
+
+impl Message {
+    pub fn new(key: impl Into<String>) -> Self {
+        Message {
+            key: key.into(),
+            args: vec![],
+        }
+    }
+
+    pub fn arg(mut self, name: &str, value: impl ToString) -> Self {
+        self.args.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// 言語ごとの、`<セクション>.<キー>`から文言へのマップ
+type Catalog = HashMap<String, String>;
+
+fn catalogs() -> &'static HashMap<Locale, Catalog> {
+    static CATALOGS: OnceLock<HashMap<Locale, Catalog>> = OnceLock::new();
+
+    CATALOGS.get_or_init(|| {
+        HashMap::from([
+            (
+                Locale::Ja,
+                parse_catalog(include_str!("../locales/ja.toml")),
+            ),
+            (
+                Locale::En,
+                parse_catalog(include_str!("../locales/en.toml")),
+            ),
+        ])
+    })
+}
+
+fn parse_catalog(source: &str) -> Catalog {
+    let sections: HashMap<String, HashMap<String, String>> =
+        toml::from_str(source).expect("Invalid message catalog");
+
+    sections
+        .into_iter()
+        .flat_map(|(section, messages)| {
+            messages
+                .into_iter()
+                .map(move |(key, text)| (format!("{}.{}", section, key), text))
+        })
+        .collect()
+}
+
+/// ログインしているユーザーが設定した言語、`Accept-Language`の順に言語を決めて、
+/// エラーのレスポンスをその言語で作り直す。
+/// AppErrorから作られていない4xxのレスポンスも、ステータスコードからproblem+jsonにする。
+/// ただし、OAuthのエラーのようにすでにJSONで返しているレスポンスは、形式が決まっているのでそのまま返す。
+/// トークンで認証したユーザーの設定も使えるように、トークンで認証したあとで実行する。
+pub async fn localize(auth_session: AuthSession<Auth>, req: Request, next: Next) -> Response {
+    let preferred = auth_session
+        .user
+        .and_then(|user| user.locale)
+        .and_then(|locale| Locale::from_tag(&locale));
+    let accepted = req
+        .headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(Locale::from_accept_language);
+    let locale = preferred.or(accepted).unwrap_or_default();
+
+    let res = next.run(req).await;
+    let problem = match res.extensions().get::<Problem>() {
+        Some(problem) => problem.clone(),
+        None if res.status().is_client_error() && !is_json(&res) => {
+            Problem::from_status(res.status())
+        }
+        None => return res,
+    };
+
+    // CSRFトークンのCookieなど、途中で付けられたヘッダーは残す
+    let (mut parts, _) = res.into_parts();
+    parts.headers.remove(CONTENT_LENGTH);
+    parts.headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(PROBLEM_JSON_CONTENT_TYPE),
+    );
+    let body = problem.into_response_with(locale).into_body();
+
+    Response::from_parts(parts, body)
+}
+
+/// `application/json`や`application/problem+json`のような、JSONのレスポンスかどうか
+fn is_json(res: &Response) -> bool {
+    res.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .is_some_and(|v| v == "application/json" || v.ends_with("+json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use http::{
+        header::{ACCEPT_LANGUAGE, CONTENT_TYPE},
+        HeaderValue, StatusCode,
+    };
+    use strum::IntoEnumIterator;
+
+    use super::{catalogs, Locale, Message};
+    use crate::{
+        app::{tests::AppTest, AppResult, Db},
+        error::{ErrorCode, ProblemDetails, PROBLEM_JSON_CONTENT_TYPE},
+        features::{
+            auth::mock_idp::{MockIdp, MockIdpPaths},
+            task::routes::TaskPaths,
+            user::routes::UserPaths,
+        },
+    };
+
+    #[test]
+    fn すべての言語のカタログに同じキーがある() {
+        let keys: Vec<HashSet<&String>> = Locale::iter()
+            .map(|locale| catalogs()[&locale].keys().collect())
+            .collect();
+
+        for other in &keys[1..] {
+            assert_eq!(&keys[0], other);
+        }
+    }
+
+    #[test]
+    fn すべてのエラーコードに文言がある() {
+        for locale in Locale::iter() {
+            for code in ErrorCode::iter() {
+                let key = format!("error.{}", code.as_ref());
+                assert!(
+                    locale.message(&Message::new(&key)).is_some(),
+                    "{:?} has no message for {}",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn accept_languageの優先度が一番高い言語を使う() {
+        assert_eq!(
+            Locale::from_accept_language("en-US,en;q=0.9,ja;q=0.8"),
+            Some(Locale::En)
+        );
+        assert_eq!(
+            Locale::from_accept_language("fr, en;q=0.5, ja;q=0.7"),
+            Some(Locale::Ja)
+        );
+        assert_eq!(Locale::from_accept_language("ja;q=0, EN"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language("fr, de"), None);
+    }
+
+    #[test]
+    fn 文言に引数を埋め込む() {
+        let message = Message::new("validation.length_max").arg("max", 100);
+
+        assert_eq!(
+            Locale::En.message(&message).unwrap(),
+            "Length must be at most 100."
+        );
+        assert_eq!(
+            Locale::Ja.message(&message).unwrap(),
+            "長さは100以下にしてください"
+        );
+    }
+
+    #[sqlx::test]
+    async fn ログインしていないときのエラーもproblem_jsonで返す(
+        db: Db,
+    ) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+
+        let res = test
+            .server()
+            .get(&UserPaths::me())
+            .add_header(ACCEPT_LANGUAGE, HeaderValue::from_static("en"))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+        assert_eq!(res.header(CONTENT_TYPE), PROBLEM_JSON_CONTENT_TYPE);
+
+        let problem: ProblemDetails = res.json();
+        assert_eq!(problem.code, ErrorCode::Unauthorized);
+        assert_eq!(problem.detail.as_deref(), Some("Please log in."));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn 抽出に失敗したエラーもproblem_jsonで返す(db: Db) -> AppResult<()> {
+        let test = AppTest::new(&db).await?;
+        test.login(None).await?;
+
+        // JSONとして読み取れないボディ
+        let res = test
+            .server()
+            .put(&TaskPaths::update_task_status().replace(":id", "id"))
+            .text("{")
+            .content_type("application/json")
+            .await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.header(CONTENT_TYPE), PROBLEM_JSON_CONTENT_TYPE);
+        let problem: ProblemDetails = res.json();
+        assert_eq!(problem.code, ErrorCode::BadRequest);
+        assert_eq!(
+            problem.detail.as_deref(),
+            Some("リクエストが正しくありません")
+        );
+
+        let res = test.server().get("/unknown").await;
+        assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+        let problem: ProblemDetails = res.json();
+        assert_eq!(problem.code, ErrorCode::NotFound);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn すでにjsonで返しているエラーはproblem_jsonにしない(
+        db: Db,
+    ) -> AppResult<()> {
+        // 開発中と同じように、モックの発行者を同じサーバーで動かす
+        let idp = MockIdp::new(format!("http://localhost{}", MockIdpPaths::issuer()));
+        let test = AppTest::with_routes(&db, idp.router()).await?;
+
+        let res = test
+            .server()
+            .post(&MockIdpPaths::token())
+            .form(&[("code", "unknown")])
+            .await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.header(CONTENT_TYPE), "application/json");
+        assert_eq!(
+            res.json::<serde_json::Value>(),
+            serde_json::json!({ "error": "invalid_grant" })
+        );
+
+        Ok(())
+    }
+}
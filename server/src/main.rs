@@ -1,24 +1,37 @@
+use std::net::SocketAddr;
+
 use sqlx::SqlitePool;
 use tracing::debug;
 
-use crate::config::Env;
+use crate::config::Config;
 mod app;
 mod config;
 mod error;
 mod features;
+mod i18n;
+mod validation;
 
 #[tokio::main]
 async fn main() {
-    Env::load();
     tracing_subscriber::fmt::init();
 
-    let db = SqlitePool::connect(&Env::database_url())
+    // 設定の問題はまとめて表示して、起動しない
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    let db = SqlitePool::connect(&config.server.database_url)
         .await
         .expect("Failed to connect");
 
-    let app = app::build(db).await;
+    let addr = format!("{}:{}", config.server.host, config.server.port);
+    let app = app::build(db, config).await;
 
-    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", Env::port()))
+    let listener = tokio::net::TcpListener::bind(addr)
         .await
         .expect("Failed to bind");
 
@@ -26,5 +39,11 @@ async fn main() {
         "listening on {:#}",
         listener.local_addr().expect("Failed to get local_adde")
     );
-    axum::serve(listener, app).await.unwrap();
+    // セッションの一覧にIPアドレスを表示するために、接続元のアドレスを渡す
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
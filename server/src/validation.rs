@@ -0,0 +1,181 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    Json,
+};
+use garde::{
+    rules::{
+        length::{InvalidLength, Length},
+        range::{Bounds, OutOfBounds},
+    },
+    Validate,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    error::{AppError, Violation},
+    i18n::Message,
+};
+
+/// gardeで検証したリクエストのボディ。
+/// 検証に失敗した場合は、項目ごとのエラーをリクエストの言語で返す
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WithValidation<E>(pub E);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for WithValidation<Json<T>>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate<Context = ()>,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|e| AppError::new(e.status(), Some(&e.body_text())))?;
+
+        value
+            .validate(&())
+            .map_err(|report| AppError::validation(violations(&report)))?;
+
+        Ok(WithValidation(Json(value)))
+    }
+}
+
+fn violations(report: &garde::Report) -> Vec<Violation> {
+    report
+        .iter()
+        .map(|(path, error)| Violation {
+            field: path.to_string(),
+            message: message(error.message()),
+        })
+        .collect()
+}
+
+/// カタログのメッセージを持つ検証のエラーを作る。
+/// gardeのエラーは文字列しか持てないので、メッセージをJSONにして渡す
+pub fn error(message: Message) -> garde::Error {
+    garde::Error::new(serde_json::to_string(&message).unwrap_or_default())
+}
+
+/// 検証のエラーからカタログのメッセージを取り出す。
+/// `error`で作っていないエラー(gardeの組み込みの検証など)は、入力が正しくないというメッセージにする
+fn message(garde_message: &str) -> Message {
+    serde_json::from_str(garde_message).unwrap_or_else(|_| Message::new("validation.invalid"))
+}
+
+/// 文字数や要素数が`min`以上`max`以下かを検証する。`#[garde(custom(length(1, 100)))]`のように使う
+pub fn length<T>(min: usize, max: usize) -> impl Fn(&T, &()) -> garde::Result
+where
+    T: Length + ?Sized,
+{
+    move |value, _| match value.validate_length(min, max) {
+        Ok(()) => Ok(()),
+        Err(InvalidLength::Min) => {
+            Err(error(Message::new("validation.length_min").arg("min", min)))
+        }
+        Err(InvalidLength::Max) => {
+            Err(error(Message::new("validation.length_max").arg("max", max)))
+        }
+    }
+}
+
+/// 値が`min`以上`max`以下かを検証する。`#[garde(custom(range(1, 365)))]`のように使う
+pub fn range<T>(min: T::Size, max: T::Size) -> impl Fn(&T, &()) -> garde::Result
+where
+    T: Bounds,
+{
+    move |value, _| match value.validate_bounds(min, max) {
+        Ok(()) => Ok(()),
+        Err(OutOfBounds::Lower) => Err(error(Message::new("validation.range_min").arg("min", min))),
+        Err(OutOfBounds::Upper) => Err(error(Message::new("validation.range_max").arg("max", max))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use garde::Validate;
+
+    use super::{error, length, range, violations};
+    use crate::i18n::{Locale, Message};
+
+    #[derive(Validate)]
+    struct Input {
+        #[garde(custom(length(2, 3)))]
+        short: String,
+        #[garde(custom(length(2, 3)))]
+        long: String,
+        #[garde(custom(range(1, 1440)))]
+        small: i64,
+        #[garde(custom(range(1, 1440)))]
+        large: i64,
+        #[garde(custom(invalid_date))]
+        date: String,
+        #[garde(ascii)]
+        builtin: String,
+    }
+
+    fn invalid_date(_: &str, _: &()) -> garde::Result {
+        Err(error(Message::new("validation.invalid_date")))
+    }
+
+    /// 検証に失敗した項目と、その項目のメッセージを指定した言語にしたもの
+    fn localized(locale: Locale) -> Vec<(String, String)> {
+        let input = Input {
+            short: "a".into(),
+            long: "abcd".into(),
+            small: 0,
+            large: 1441,
+            date: "2024/02/30".into(),
+            builtin: "あ".into(),
+        };
+        let report = input.validate(&()).unwrap_err();
+
+        let mut messages: Vec<(String, String)> = violations(&report)
+            .into_iter()
+            .map(|v| {
+                let text = locale
+                    .message(&v.message)
+                    .unwrap_or_else(|| panic!("{:?} is not in the catalog", v.message));
+                (v.field, text)
+            })
+            .collect();
+        messages.sort();
+        messages
+    }
+
+    #[test]
+    fn gardeの検証エラーをリクエストの言語のメッセージにする() {
+        let expected = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(field, text)| (field.to_string(), text.to_string()))
+                .collect()
+        };
+
+        assert_eq!(
+            localized(Locale::Ja),
+            expected(&[
+                // 組み込みの検証は、どの検証に失敗したかまでは伝えない
+                ("builtin", "入力が正しくありません"),
+                ("date", "日付は%Y/%m/%dの形式で指定してください"),
+                ("large", "1440以下にしてください"),
+                ("long", "長さは3以下にしてください"),
+                ("short", "長さは2以上にしてください"),
+                ("small", "1以上にしてください"),
+            ])
+        );
+        assert_eq!(
+            localized(Locale::En),
+            expected(&[
+                ("builtin", "The value is invalid."),
+                ("date", "Dates must be in the %Y/%m/%d format."),
+                ("large", "Must be at most 1440."),
+                ("long", "Length must be at most 3."),
+                ("short", "Length must be at least 2."),
+                ("small", "Must be at least 1."),
+            ])
+        );
+    }
+}